@@ -300,6 +300,15 @@ pub enum FocusCommand {
     Rename,
     #[strum(serialize = "confirm_rename")]
     ConfirmRename,
+    #[strum(message = "Next Settings Section")]
+    #[strum(serialize = "next_settings_section")]
+    NextSettingsSection,
+    #[strum(message = "Previous Settings Section")]
+    #[strum(serialize = "previous_settings_section")]
+    PreviousSettingsSection,
+    #[strum(message = "Expand or Collapse the Settings Descriptions")]
+    #[strum(serialize = "toggle_settings_descriptions")]
+    ToggleSettingsDescriptions,
 }
 
 #[derive(
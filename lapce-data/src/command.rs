@@ -46,6 +46,10 @@
 pub const LAPCE_OPEN_FILE: Selector<FileInfo> = Selector::new("lapce.open-file");
 pub const LAPCE_SAVE_FILE_AS: Selector<FileInfo> =
     Selector::new("lapce.save-file-as");
+pub const LAPCE_IMPORT_VSCODE_SETTINGS: Selector<FileInfo> =
+    Selector::new("lapce.import-vscode-settings");
+pub const LAPCE_IMPORT_THEME: Selector<FileInfo> =
+    Selector::new("lapce.import-theme");
 pub const LAPCE_COMMAND: Selector<LapceCommand> = Selector::new("lapce.new-command");
 pub const LAPCE_UI_COMMAND: Selector<LapceUICommand> =
     Selector::new("lapce.ui_command");
@@ -88,8 +92,82 @@ pub fn str(&self) -> &'static str {
             CommandKind::MultiSelection(cmd) => cmd.into(),
         }
     }
+
+    /// The category the command is listed under in the key bindings, one of
+    /// `COMMAND_CATEGORIES`
+    pub fn category(&self) -> &'static str {
+        match &self {
+            CommandKind::Edit(_)
+            | CommandKind::MotionMode(_)
+            | CommandKind::MultiSelection(_) => "Editing",
+            CommandKind::Move(_) => "Navigation",
+            CommandKind::Focus(_) | CommandKind::Workbench(_) => {
+                let name = self.str();
+                COMMAND_CATEGORY_KEYWORDS
+                    .iter()
+                    .find(|(keyword, _)| name.contains(keyword))
+                    .map(|(_, category)| *category)
+                    .unwrap_or(match self {
+                        CommandKind::Focus(_) => "Editing",
+                        _ => "General",
+                    })
+            }
+        }
+    }
 }
 
+/// The categories the key bindings list groups the commands by, in the order
+/// they're listed
+pub const COMMAND_CATEGORIES: [&str; 9] = [
+    "Editing",
+    "Navigation",
+    "Search",
+    "Layout",
+    "Terminal",
+    "Source Control",
+    "Settings",
+    "Workspace",
+    "General",
+];
+
+/// The categories of the focus and workbench commands by a part of their
+/// name, the first match wins. The rest of the focus commands act on the
+/// editor, and the rest of the workbench commands are general.
+const COMMAND_CATEGORY_KEYWORDS: &[(&str, &str)] = &[
+    ("terminal", "Terminal"),
+    ("source_control", "Source Control"),
+    ("diff", "Source Control"),
+    ("setting", "Settings"),
+    ("theme", "Settings"),
+    ("keybinding", "Settings"),
+    ("keyboard_shortcuts", "Settings"),
+    ("modal_editing", "Settings"),
+    ("inlay_hints", "Settings"),
+    ("search", "Search"),
+    ("inline_find", "Search"),
+    ("_of_window", "Navigation"),
+    ("goto", "Navigation"),
+    ("jump", "Navigation"),
+    ("page_", "Navigation"),
+    ("scroll", "Navigation"),
+    ("list.", "Navigation"),
+    ("error", "Navigation"),
+    ("split", "Layout"),
+    ("panel", "Layout"),
+    ("_tab", "Layout"),
+    ("window", "Layout"),
+    ("focus", "Layout"),
+    ("_visual", "Layout"),
+    ("palette", "Workspace"),
+    ("folder", "Workspace"),
+    ("file", "Workspace"),
+    ("directory", "Workspace"),
+    ("ssh", "Workspace"),
+    ("wsl", "Workspace"),
+    ("remote", "Workspace"),
+    ("save", "Workspace"),
+];
+
 impl LapceCommand {
     pub const PALETTE: &'static str = "palette";
 
@@ -102,6 +180,13 @@ pub fn is_palette_command(&self) -> bool {
                 | LapceWorkbenchCommand::PaletteCommand
                 | LapceWorkbenchCommand::ChangeFileLanguage
                 | LapceWorkbenchCommand::ChangeTheme
+                | LapceWorkbenchCommand::OpenThemeGallery
+                | LapceWorkbenchCommand::RestoreSettingsFromBackup
+                | LapceWorkbenchCommand::ShowChangedDefaultSettings
+                | LapceWorkbenchCommand::ToggleSetting
+                | LapceWorkbenchCommand::SearchThemeColors
+                | LapceWorkbenchCommand::RenameTheme
+                | LapceWorkbenchCommand::DuplicateTheme
                 | LapceWorkbenchCommand::ConnectSshHost
                 | LapceWorkbenchCommand::ConnectWsl
                 | LapceWorkbenchCommand::PaletteWorkspace => return true,
@@ -119,6 +204,16 @@ pub enum CommandExecuted {
     No,
 }
 
+/// The command a key binding to "toggle_setting:table.key" runs, which
+/// toggles that setting
+pub fn toggle_setting_command(command: &str) -> Option<LapceCommand> {
+    let id = command.strip_prefix("toggle_setting:")?;
+    Some(LapceCommand {
+        kind: CommandKind::Workbench(LapceWorkbenchCommand::ToggleSetting),
+        data: Some(serde_json::json!(id.trim())),
+    })
+}
+
 pub fn lapce_internal_commands() -> IndexMap<String, LapceCommand> {
     let mut commands = IndexMap::new();
 
@@ -221,10 +316,83 @@ pub enum LapceWorkbenchCommand {
     #[strum(message = "Open Settings File")]
     OpenSettingsFile,
 
+    #[strum(serialize = "toggle_settings_alphabetical_order")]
+    #[strum(message = "Toggle Alphabetical Order of Settings")]
+    ToggleSettingsAlphabeticalOrder,
+
+    #[strum(serialize = "toggle_settings_raw_keys")]
+    #[strum(message = "Toggle Raw Keys of Settings")]
+    ToggleSettingsRawKeys,
+
+    #[strum(serialize = "toggle_settings_switcher_collapsed")]
+    #[strum(message = "Toggle Icons Only Settings Switcher")]
+    ToggleSettingsSwitcherCollapsed,
+
+    #[strum(serialize = "record_settings_search")]
+    RecordSettingsSearch,
+
+    #[strum(serialize = "run_settings_search")]
+    RunSettingsSearch,
+
+    #[strum(serialize = "toggle_settings_advanced")]
+    #[strum(message = "Toggle Advanced Settings")]
+    ToggleSettingsAdvanced,
+
+    #[strum(serialize = "toggle_settings_deprecated")]
+    #[strum(message = "Toggle Deprecated Settings")]
+    ToggleSettingsDeprecated,
+
+    /// Expands or collapses the descriptions of the settings section whose
+    /// table is the data
+    #[strum(serialize = "toggle_section_descriptions")]
+    ToggleSectionDescriptions,
+
+    #[strum(serialize = "toggle_settings_effective_config")]
+    #[strum(message = "Toggle the Effective Config Preview")]
+    ToggleSettingsEffectiveConfig,
+
+    #[strum(serialize = "toggle_settings_group_by_changed")]
+    #[strum(message = "Toggle Grouping Settings by Changed")]
+    ToggleSettingsGroupByChanged,
+
+    #[strum(serialize = "reset_settings_panel_layout")]
+    #[strum(message = "Reset Settings Panel Layout")]
+    ResetSettingsPanelLayout,
+
+    #[strum(serialize = "reveal_setting_in_panel")]
+    #[strum(message = "Reveal Setting in Settings Panel")]
+    RevealSettingInPanel,
+
+    #[strum(serialize = "copy_settings_section_as_json")]
+    CopySettingsSectionAsJson,
+
+    #[strum(serialize = "reveal_theme_color_in_file")]
+    RevealThemeColorInFile,
+
+    #[strum(serialize = "apply_theme_changes")]
+    #[strum(message = "Apply the Typed Theme Colors")]
+    ApplyThemeChanges,
+
+    #[strum(serialize = "discard_theme_changes")]
+    #[strum(message = "Discard the Typed Theme Colors")]
+    DiscardThemeChanges,
+
     #[strum(serialize = "open_settings_directory")]
     #[strum(message = "Open Settings Directory")]
     OpenSettingsDirectory,
 
+    #[strum(serialize = "reveal_settings_file_in_file_explorer")]
+    #[strum(message = "Reveal Settings File in File Explorer")]
+    RevealSettingsFileInFileExplorer,
+
+    #[strum(serialize = "reveal_workspace_settings_file_in_file_explorer")]
+    #[strum(message = "Reveal Workspace Settings File in File Explorer")]
+    RevealWorkspaceSettingsFileInFileExplorer,
+
+    #[strum(serialize = "reveal_theme_file_in_file_explorer")]
+    #[strum(message = "Reveal Theme File in File Explorer")]
+    RevealThemeFileInFileExplorer,
+
     #[strum(serialize = "open_keyboard_shortcuts")]
     #[strum(message = "Open Keyboard Shortcuts")]
     OpenKeyboardShortcuts,
@@ -411,14 +579,189 @@ pub enum LapceWorkbenchCommand {
     #[strum(serialize = "source_control_discard_workspace_changes")]
     SourceControlDiscardWorkspaceChanges,
 
+    #[strum(serialize = "flush_settings")]
+    #[strum(message = "Flush Settings")]
+    FlushSettings,
+
+    #[strum(serialize = "reset_focused_setting")]
+    #[strum(message = "Reset Focused Setting")]
+    ResetFocusedSetting,
+
+    /// Puts the settings changed since the settings were opened back to
+    /// their values from before, once it's confirmed
+    #[strum(serialize = "revert_settings_session")]
+    #[strum(message = "Revert Settings Changed Since the Settings Opened")]
+    RevertSettingsSession,
+
+    #[strum(serialize = "apply_revert_settings_session")]
+    ApplyRevertSettingsSession,
+
+    /// Applies the settings that are only read when a file is opened to the
+    /// open files
+    #[strum(serialize = "reload_open_files")]
+    #[strum(message = "Reload Open Files")]
+    ReloadOpenFiles,
+
+    /// Resets the setting given as (table, key) by its data
+    #[strum(serialize = "reset_setting")]
+    ResetSetting,
+
+    /// Copies the text given by its data, like the identifier or the value of
+    /// a setting
+    #[strum(serialize = "copy_setting_text")]
+    CopySettingText,
+
+    #[strum(serialize = "open_default_settings")]
+    #[strum(message = "Open Default Settings (read-only)")]
+    OpenDefaultSettings,
+
+    #[strum(serialize = "compare_settings_with_defaults")]
+    #[strum(message = "Compare Settings with Defaults")]
+    CompareSettingsWithDefaults,
+
+    #[strum(serialize = "compare_changed_settings_with_defaults")]
+    #[strum(message = "Compare Changed Settings with Defaults")]
+    CompareChangedSettingsWithDefaults,
+
+    #[strum(serialize = "import_vscode_settings")]
+    #[strum(message = "Import VS Code Settings")]
+    ImportVSCodeSettings,
+
+    #[strum(serialize = "apply_vscode_settings_import")]
+    ApplyVSCodeSettingsImport,
+
+    #[strum(serialize = "reset_to_import_baseline")]
+    #[strum(message = "Reset Settings to the Last Import")]
+    ResetToImportBaseline,
+
+    #[strum(serialize = "apply_import_baseline_reset")]
+    ApplyImportBaselineReset,
+
+    #[strum(serialize = "switch_to_previous_theme")]
+    #[strum(message = "Switch to the Previous Theme")]
+    SwitchToPreviousTheme,
+
+    #[strum(serialize = "open_theme_gallery")]
+    #[strum(message = "Open Theme Gallery")]
+    OpenThemeGallery,
+
+    #[strum(serialize = "rename_theme")]
+    #[strum(message = "Rename the Current Theme")]
+    RenameTheme,
+
+    #[strum(serialize = "duplicate_theme")]
+    #[strum(message = "Duplicate the Current Theme")]
+    DuplicateTheme,
+
+    #[strum(serialize = "apply_rename_theme")]
+    ApplyRenameTheme,
+
+    #[strum(serialize = "apply_duplicate_theme")]
+    ApplyDuplicateTheme,
+
+    #[strum(serialize = "replace_theme_color")]
+    #[strum(message = "Replace a Color in the Current Theme")]
+    ReplaceThemeColor,
+
+    #[strum(serialize = "apply_replace_theme_color")]
+    ApplyReplaceThemeColor,
+
+    /// Lists the base, syntax and UI colors of the theme together, picking
+    /// one shows it in its column of the theme settings
+    #[strum(serialize = "search_theme_colors")]
+    #[strum(message = "Search Theme Colors")]
+    SearchThemeColors,
+
+    #[strum(serialize = "reset_all_settings")]
+    #[strum(message = "Reset All Settings to Defaults")]
+    ResetAllSettings,
+
+    #[strum(serialize = "apply_reset_all_settings")]
+    ApplyResetAllSettings,
+
+    #[strum(serialize = "restore_settings_from_backup")]
+    #[strum(message = "Restore Settings from Backup")]
+    RestoreSettingsFromBackup,
+
+    /// Flips the boolean setting named "table.key" by its data, without it
+    /// the boolean settings are listed to pick one from
+    #[strum(serialize = "toggle_setting")]
+    #[strum(message = "Toggle Setting")]
+    ToggleSetting,
+
+    #[strum(serialize = "show_changed_default_settings")]
+    #[strum(message = "Show Default Settings Changed Since Last Version")]
+    ShowChangedDefaultSettings,
+
+    #[strum(serialize = "preview_settings_backup")]
+    PreviewSettingsBackup,
+
+    #[strum(serialize = "apply_settings_backup")]
+    ApplySettingsBackup,
+
+    #[strum(serialize = "edit_keybinding")]
+    EditKeybinding,
+
+    #[strum(serialize = "copy_keybinding")]
+    CopyKeybinding,
+
+    #[strum(serialize = "next_invalid_setting")]
+    #[strum(message = "Next Invalid Setting")]
+    NextInvalidSetting,
+
+    #[strum(serialize = "previous_invalid_setting")]
+    #[strum(message = "Previous Invalid Setting")]
+    PreviousInvalidSetting,
+
     #[strum(serialize = "export_current_theme_settings")]
     #[strum(message = "Export current settings to a theme file")]
     ExportCurrentThemeSettings,
 
+    #[strum(serialize = "export_settings")]
+    #[strum(message = "Export current settings with descriptions")]
+    ExportSettings,
+
+    #[strum(serialize = "export_settings_docs")]
+    #[strum(message = "Export Settings Documentation as Markdown")]
+    ExportSettingsDocs,
+
+    #[strum(serialize = "export_keybindings")]
+    #[strum(message = "Export Key Bindings")]
+    ExportKeybindings,
+
+    /// Exports the bindings added to the defaults and unbinds of the
+    /// defaults that were removed, which can be added to another keymaps file
+    #[strum(serialize = "export_changed_keybindings")]
+    #[strum(message = "Export Key Bindings Changed from Defaults")]
+    ExportChangedKeybindings,
+
+    #[strum(serialize = "export_keybindings_cheat_sheet")]
+    #[strum(message = "Export Key Bindings Cheat Sheet as Markdown")]
+    ExportKeybindingsCheatSheet,
+
     #[strum(serialize = "install_theme")]
     #[strum(message = "Install current theme file")]
     InstallTheme,
 
+    #[strum(serialize = "import_theme_file")]
+    #[strum(message = "Import Theme File")]
+    ImportThemeFile,
+
+    #[strum(serialize = "apply_theme_import")]
+    ApplyThemeImport,
+
+    #[strum(serialize = "copy_theme_as_json")]
+    #[strum(message = "Copy Theme as JSON")]
+    CopyThemeAsJson,
+
+    #[strum(serialize = "copy_theme_name")]
+    #[strum(message = "Copy Theme Name")]
+    CopyThemeName,
+
+    #[strum(serialize = "paste_theme_from_json")]
+    #[strum(message = "Paste Theme from JSON")]
+    PasteThemeFromJson,
+
     #[strum(serialize = "change_file_language")]
     #[strum(message = "Change current file language")]
     ChangeFileLanguage,
@@ -507,6 +850,8 @@ pub enum LapceUICommand {
     SetWorkspace(LapceWorkspace),
     SetTheme(String, bool),
     UpdateKeymap(KeyMap, Vec<KeyPress>),
+    /// Change the "when" condition of a key binding, `None` removes it
+    UpdateKeymapCondition(KeyMap, Option<String>),
     OpenURI(String),
     OpenPaths {
         window_tab_id: Option<(WindowId, WidgetId)>,
@@ -539,6 +884,7 @@ pub enum LapceUICommand {
     EnsureEditorTabActiveVisible,
     FocusSourceControl,
     ShowSettings,
+    ShowSettingsField(String, String),
     ShowKeybindings,
     FocusEditor,
     RunPalette(Option<PaletteType>),
@@ -549,8 +895,22 @@ pub enum LapceUICommand {
     FilterPaletteItems(String, String, im::Vector<PaletteItem>),
     UpdateKeymapsFilter(String),
     ResetSettingsFile(String, String),
+    /// Reset the setting in the settings panel that has the focus
+    ResetFocusedSetting,
+    /// Reset the (table, key) setting, like its settings item does
+    ResetSetting(String, String),
+    /// Forget the settings changed before, as the settings panel opened
+    StartSettingsSession,
     UpdateSettingsFile(String, String, Value),
     UpdateSettingsFilter(String),
+    /// The vertical offset a settings section has been scrolled to
+    SettingsScrolled(f64),
+    /// Bring the settings panel back to its first section, scrolled to the
+    /// top
+    ResetSettingsPanelLayout,
+    /// Focus the first color input of a theme settings column, or the last one
+    /// if true
+    FocusThemeColor(bool),
     FilterKeymaps(String, Arc<Vec<KeyMap>>, Arc<Vec<LapceCommand>>),
     UpdatePickerPwd(PathBuf),
     UpdatePickerItems(PathBuf, HashMap<PathBuf, FileNodeItem>),
@@ -720,6 +1080,14 @@ pub enum LapceUICommand {
     /// An item in a list was chosen  
     /// This is typically targeted at the widget which contains the list
     ListItemSelected,
+
+    /// An item in a list was right clicked, at the given window position  
+    /// The item is already the selected one when this is received
+    ListItemContextMenu(Point),
+
+    /// The mouse moved onto an item in a list with `preview_on_hover`, which
+    /// is the selected one now
+    ListItemHovered,
 }
 
 /// This can't be an `FnOnce` because we only ever get a reference to
@@ -10,10 +10,12 @@
     Color, ExtEventSink, FontFamily, Size, Target,
 };
 use indexmap::IndexMap;
+use itertools::Itertools;
 use lapce_proxy::{directory::Directory, plugin::wasi::find_all_volts};
+use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use structdesc::FieldNames;
 use thiserror::Error;
 use toml_edit::easy as toml;
@@ -21,6 +23,10 @@
 use crate::{
     command::{LapceUICommand, LAPCE_UI_COMMAND},
     data::{LapceWorkspace, LapceWorkspaceType},
+    settings::{
+        json_type_name, normalize_pasted_color, parse_setting_input,
+        settings_value_text, SettingChange, SettingsValueKind,
+    },
 };
 
 pub use lapce_proxy::APPLICATION_NAME;
@@ -30,6 +36,28 @@
 const DEFAULT_DARK_THEME: &str = include_str!("../../defaults/dark-theme.toml");
 pub const LOGO: &str = include_str!("../../extra/images/logo.svg");
 
+/// The tables of the bundled default settings as JSON, keyed by settings
+/// table, parsed once as every settings item looks its default up
+static DEFAULT_SETTING_TABLES: Lazy<HashMap<&'static str, serde_json::Value>> =
+    Lazy::new(|| {
+        let defaults: Config = Config::default_settings()
+            .try_into()
+            .expect("the bundled default settings are valid");
+        [
+            "lapce",
+            "ui",
+            "editor",
+            "terminal",
+            "theme",
+            "theme.base",
+            "theme.ui",
+            "theme.syntax",
+        ]
+        .into_iter()
+        .filter_map(|table| Some((table, defaults.setting_table(table)?)))
+        .collect()
+    });
+
 pub struct LapceTheme {}
 
 impl LapceTheme {
@@ -106,6 +134,11 @@ impl LapceTheme {
     pub const PANEL_CURRENT: &'static str = "panel.current";
     pub const PANEL_HOVERED: &'static str = "panel.hovered";
 
+    pub const SETTINGS_BACKGROUND: &'static str = "settings.background";
+    pub const SETTINGS_SWITCHER_BACKGROUND: &'static str =
+        "settings.switcher.background";
+    pub const SETTINGS_SWITCHER_CURRENT: &'static str = "settings.switcher.current";
+
     pub const STATUS_BACKGROUND: &'static str = "status.background";
     pub const STATUS_MODAL_NORMAL: &'static str = "status.modal.normal";
     pub const STATUS_MODAL_INSERT: &'static str = "status.modal.insert";
@@ -124,6 +157,191 @@ impl LapceTheme {
         druid::Key::new("lapce.input_font_size");
 
     pub const MARKDOWN_BLOCKQUOTE: &'static str = "markdown.blockquote";
+
+    /// The foreground and background colors that text is read in, their
+    /// contrast is shown in the theme settings
+    pub const CONTRAST_PAIRS: &'static [(&'static str, &'static str)] = &[
+        (Self::EDITOR_FOREGROUND, Self::EDITOR_BACKGROUND),
+        (Self::EDITOR_DIM, Self::EDITOR_BACKGROUND),
+        (Self::EDITOR_LINK, Self::EDITOR_BACKGROUND),
+        (Self::EDITOR_FOREGROUND, Self::PANEL_BACKGROUND),
+        (Self::EDITOR_FOREGROUND, Self::PALETTE_BACKGROUND),
+        (Self::EDITOR_FOREGROUND, Self::STATUS_BACKGROUND),
+        (Self::INLAY_HINT_FOREGROUND, Self::INLAY_HINT_BACKGROUND),
+        (Self::TERMINAL_FOREGROUND, Self::TERMINAL_BACKGROUND),
+    ];
+    /// The WCAG minimum contrast ratio of normal text
+    pub const MIN_CONTRAST_RATIO: f64 = 4.5;
+}
+
+/// Evaluate a theme color: a hex color, a base color written as `$blue` or
+/// `base.blue`, or a color derived from one with `lighten(color, 10%)` or
+/// `darken(color, 10%)`, which mix it with that much white or black.
+/// `base` looks up the base colors.
+pub fn eval_color(
+    value: &str,
+    base: &dyn Fn(&str) -> Option<Color>,
+) -> Result<Color, String> {
+    let value = value.trim();
+    if let Some(name) = value
+        .strip_prefix('$')
+        .or_else(|| value.strip_prefix("base."))
+    {
+        if name.is_empty() {
+            return Err("Expected the name of a base color after $".to_string());
+        }
+        return base(name).ok_or_else(|| format!("There is no base color {name}"));
+    }
+
+    if let Some((function, args)) = value
+        .strip_suffix(')')
+        .and_then(|value| value.split_once('('))
+    {
+        let function = function.trim();
+        let mix: fn(f64, f64) -> f64 = match function {
+            "lighten" => |c, amount| c + (1.0 - c) * amount,
+            "darken" => |c, amount| c * (1.0 - amount),
+            _ => {
+                return Err(format!(
+                    "Unknown function {function}, expected lighten or darken"
+                ))
+            }
+        };
+        let (color, amount) = args
+            .rsplit_once(',')
+            .ok_or_else(|| format!("Expected {function}(color, amount%)"))?;
+        let color = eval_color(color, base)?;
+        let amount = amount
+            .trim()
+            .strip_suffix('%')
+            .and_then(|amount| amount.trim().parse::<f64>().ok())
+            .filter(|amount| (0.0..=100.0).contains(amount))
+            .ok_or_else(|| {
+                format!(
+                    "Expected a percentage from 0% to 100%, not {}",
+                    amount.trim()
+                )
+            })?
+            / 100.0;
+        let (r, g, b, a) = color.as_rgba();
+        return Ok(Color::rgba(
+            mix(r, amount),
+            mix(g, amount),
+            mix(b, amount),
+            a,
+        ));
+    }
+
+    Color::from_hex_str(value).map_err(|_| {
+        "Expected a color like #RRGGBB, #RRGGBBAA or $base-color".to_string()
+    })
+}
+
+/// The base colors a theme color value refers to, as `$blue` or `base.blue`,
+/// including the ones in expressions
+pub fn referenced_base_colors(value: &str) -> Vec<&str> {
+    let is_name = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(|c: char| c == '$' || c == 'b') {
+        let after = &rest[start..];
+        let prefix = if after.starts_with('$') {
+            1
+        } else if after.starts_with("base.") && !rest[..start].ends_with(is_name) {
+            5
+        } else {
+            rest = &after[1..];
+            continue;
+        };
+        let name = &after[prefix..];
+        let end = name.find(|c: char| !is_name(c)).unwrap_or(name.len());
+        if end > 0 {
+            names.push(&name[..end]);
+        }
+        rest = &name[end..];
+    }
+    names
+}
+
+/// Expand the variables in a setting, written as `$VAR` or `${VAR}`, with
+/// `$$` for a literal `$`. `vars` looks up the variables, the ones it doesn't
+/// know are left as written.
+pub fn expand_vars(value: &str, vars: &dyn Fn(&str) -> Option<String>) -> String {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        let (name, written, after) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &rest[..end + 2], &braced[end + 1..]),
+                None => {
+                    expanded.push('$');
+                    continue;
+                }
+            },
+            None => {
+                let end = rest.find(|c: char| !is_name(c)).unwrap_or(rest.len());
+                (&rest[..end], &rest[..end], &rest[end..])
+            }
+        };
+        match Some(name).filter(|name| !name.is_empty()).and_then(vars) {
+            Some(value) => expanded.push_str(&value),
+            None => {
+                expanded.push('$');
+                expanded.push_str(written);
+            }
+        }
+        rest = after;
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Expand the environment variables in a path-like setting, see `expand_vars`
+pub fn expand_env_vars(value: &str) -> String {
+    expand_vars(value, &|name| std::env::var(name).ok())
+}
+
+/// The WCAG contrast ratio of `fg` drawn on `bg`, from 1 to 21
+pub fn contrast_ratio(fg: &Color, bg: &Color) -> f64 {
+    fn luminance((r, g, b): (f64, f64, f64)) -> f64 {
+        let channel = |c: f64| {
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+    }
+
+    let (fg_r, fg_g, fg_b, fg_a) = fg.as_rgba();
+    let (bg_r, bg_g, bg_b, _) = bg.as_rgba();
+    // A translucent foreground is seen blended with the background
+    let blend = |fg: f64, bg: f64| fg * fg_a + bg * (1.0 - fg_a);
+    let fg = luminance((blend(fg_r, bg_r), blend(fg_g, bg_g), blend(fg_b, bg_b)));
+    let bg = luminance((bg_r, bg_g, bg_b));
+    (fg.max(bg) + 0.05) / (fg.min(bg) + 0.05)
+}
+
+/// Why the value of a setting is rejected
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("There is no setting {kind}.{name}")]
+    UnknownSetting { kind: String, name: String },
+    #[error("{kind}.{name} is invalid: {reason}")]
+    InvalidValue {
+        kind: String,
+        name: String,
+        reason: String,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -154,6 +372,23 @@ pub struct LapceConfig {
         desc = "Enable customised titlebar and disable OS native one (Linux, BSD, Windows)"
     )]
     pub custom_titlebar: bool,
+    #[field_names(
+        desc = "Copy the settings file to this path whenever a setting is changed, e.g. to a dotfile kept in git. Environment variables written as $VAR or ${VAR} are expanded. Empty doesn't copy it."
+    )]
+    pub settings_mirror: String,
+    #[field_names(
+        desc = "The path of a reference settings file, like the one a team agreed on. The settings that differ from it are highlighted, and can be aligned to it. Environment variables written as $VAR or ${VAR} are expanded. Empty doesn't compare the settings."
+    )]
+    pub reference_settings: String,
+}
+
+impl LapceConfig {
+    /// Example paths of the settings naming a settings file, shown below
+    /// their descriptions, `$HOME` and `${HOME}` being expanded
+    pub const EXAMPLES: &'static [(&'static str, &'static str)] = &[
+        ("settings_mirror", "$HOME/dotfiles/lapce/settings.toml"),
+        ("reference_settings", "${HOME}/team/lapce-settings.toml"),
+    ];
 }
 
 #[derive(FieldNames, Debug, Clone, Deserialize, Serialize, Default)]
@@ -174,7 +409,11 @@ pub struct EditorConfig {
     #[field_names(desc = "If opened editors are shown in a tab")]
     pub show_tab: bool,
     #[field_names(desc = "If navigation breadcrumbs are shown for the file")]
-    pub show_bread_crumbs: bool,
+    show_bread_crumbs: bool,
+    #[field_names(
+        desc = "If navigation breadcrumbs are shown for the file. If unset, it follows show-bread-crumbs."
+    )]
+    show_breadcrumbs: Option<bool>,
     #[field_names(desc = "If the editor can scroll beyond the last line")]
     pub scroll_beyond_last_line: bool,
     #[field_names(
@@ -240,6 +479,50 @@ pub struct EditorConfig {
 }
 
 impl EditorConfig {
+    /// The caret and multiple cursor settings the settings panel keeps out
+    /// of the list until advanced settings are shown
+    pub const ADVANCED: &'static [&'static str] = &[
+        "blink_interval",
+        "multicursor_case_sensitive",
+        "multicursor_whole_words",
+    ];
+
+    /// The deprecated settings, with the key of the setting replacing each
+    /// one or "" if nothing does
+    pub const DEPRECATED: &'static [(&'static str, &'static str)] =
+        &[("show_bread_crumbs", "editor.show-breadcrumbs")];
+
+    /// The units the editor sizes and delays are shown in: the font sizes
+    /// in px, the tab width in spaces and the delays in ms
+    pub const UNITS: &'static [(&'static str, &'static str)] = &[
+        ("font_size", "px"),
+        ("code_lens_font_size", "px"),
+        ("tab_width", "spaces"),
+        ("hover_delay", "ms"),
+        ("inlay_hint_font_size", "px"),
+        ("error_lens_font_size", "px"),
+        ("blink_interval", "ms"),
+    ];
+
+    /// The (min, max) of the numbers Lapce uses for the settings, a setting
+    /// outside its range is clamped or doesn't load
+    pub const RANGES: &'static [(&'static str, f64, f64)] = &[
+        ("font_size", 1.0, f64::INFINITY),
+        ("code_lens_font_size", 0.0, f64::INFINITY),
+        ("line_height", 0.0, f64::INFINITY),
+        ("tab_width", 1.0, f64::INFINITY),
+        ("hover_delay", 0.0, f64::INFINITY),
+        ("inlay_hint_font_size", 0.0, f64::INFINITY),
+        ("error_lens_font_size", 0.0, f64::INFINITY),
+        ("blink_interval", 0.0, f64::INFINITY),
+    ];
+
+    /// Whether navigation breadcrumbs are shown for the file,
+    /// `show-bread-crumbs` decides while `show-breadcrumbs` is unset
+    pub fn show_breadcrumbs(&self) -> bool {
+        self.show_breadcrumbs.unwrap_or(self.show_bread_crumbs)
+    }
+
     pub fn line_height(&self) -> usize {
         const SCALE_OR_SIZE_LIMIT: f64 = 5.0;
 
@@ -333,9 +616,73 @@ pub struct UIConfig {
     hover_font_family: String,
     #[field_names(desc = "Set the hover font size. If 0, uses the UI font size")]
     hover_font_size: usize,
+
+    #[field_names(
+        desc = "Set the theme the \"Light Theme\" button in the theme settings switches to"
+    )]
+    preferred_light_theme: String,
+    #[field_names(
+        desc = "Set the theme the \"Dark Theme\" button in the theme settings switches to"
+    )]
+    preferred_dark_theme: String,
+
+    #[field_names(
+        desc = "Set the sections listed in the settings panel switcher, out of core, ui, editor, terminal, theme, keybindings and recent. If empty, all of them are listed."
+    )]
+    settings_sections: Vec<String>,
+
+    #[field_names(
+        desc = "Store a change made in the settings panel when its input loses focus, instead of shortly after you stop typing"
+    )]
+    save_settings_on_focus_loss: bool,
+
+    #[field_names(
+        desc = "Keep the theme colors typed in the settings panel until they're applied with the Apply link, instead of storing them as they're typed"
+    )]
+    confirm_theme_changes: bool,
+
+    #[field_names(
+        desc = "Store the hex theme colors set in the settings panel as uppercase #RRGGBBAA, so the colors of a theme are written the same way"
+    )]
+    normalize_theme_colors: bool,
 }
 
 impl UIConfig {
+    /// The widths of the UI parts, which rarely need changing, the settings
+    /// panel only lists them while advanced settings are shown
+    pub const ADVANCED: &'static [&'static str] = &[
+        "tab_min_width",
+        "scroll_width",
+        "drop_shadow_width",
+        "preview_editor_width",
+    ];
+
+    /// The UI font sizes and the heights and widths of the UI parts, which
+    /// are all in px
+    pub const UNITS: &'static [(&'static str, &'static str)] = &[
+        ("font_size", "px"),
+        ("header_height", "px"),
+        ("status_height", "px"),
+        ("tab_min_width", "px"),
+        ("scroll_width", "px"),
+        ("drop_shadow_width", "px"),
+        ("preview_editor_width", "px"),
+        ("hover_font_size", "px"),
+    ];
+
+    /// The (min, max) of the numbers Lapce uses for the settings, a setting
+    /// outside its range is clamped or doesn't load
+    pub const RANGES: &'static [(&'static str, f64, f64)] = &[
+        ("font_size", 6.0, 32.0),
+        ("header_height", 0.0, f64::INFINITY),
+        ("status_height", 0.0, f64::INFINITY),
+        ("tab_min_width", 0.0, f64::INFINITY),
+        ("scroll_width", 0.0, f64::INFINITY),
+        ("drop_shadow_width", 0.0, f64::INFINITY),
+        ("preview_editor_width", 0.0, f64::INFINITY),
+        ("hover_font_size", 0.0, f64::INFINITY),
+    ];
+
     pub fn font_family(&self) -> FontFamily {
         if self.font_family.is_empty() {
             FontFamily::SYSTEM_UI
@@ -348,6 +695,12 @@ pub fn font_size(&self) -> usize {
         self.font_size.max(6).min(32)
     }
 
+    /// Whether the settings panel switcher lists the section `name`
+    pub fn shows_settings_section(&self, name: &str) -> bool {
+        self.settings_sections.is_empty()
+            || self.settings_sections.iter().any(|section| section == name)
+    }
+
     pub fn header_height(&self) -> usize {
         let font_size = self.font_size();
         self.header_height.max(font_size)
@@ -389,6 +742,31 @@ pub fn hover_font_size(&self) -> usize {
             self.hover_font_size
         }
     }
+
+    /// Whether the settings panel stores changes on focus loss instead of
+    /// after a delay
+    pub fn save_settings_on_focus_loss(&self) -> bool {
+        self.save_settings_on_focus_loss
+    }
+
+    /// Whether the theme colors typed in the settings panel wait to be
+    /// applied
+    pub fn confirm_theme_changes(&self) -> bool {
+        self.confirm_theme_changes
+    }
+
+    /// Whether the hex theme colors are stored as `#RRGGBBAA`
+    pub fn normalize_theme_colors(&self) -> bool {
+        self.normalize_theme_colors
+    }
+
+    pub fn preferred_light_theme(&self) -> &str {
+        &self.preferred_light_theme
+    }
+
+    pub fn preferred_dark_theme(&self) -> &str {
+        &self.preferred_dark_theme
+    }
 }
 
 #[derive(FieldNames, Debug, Clone, Deserialize, Serialize, Default)]
@@ -406,15 +784,88 @@ pub struct TerminalConfig {
         desc = "Set the terminal line height, If 0, it uses editor line height"
     )]
     pub line_height: usize,
-    #[field_names(desc = "Set the terminal Shell")]
+    #[field_names(
+        desc = "Set the terminal Shell. Environment variables written as $VAR or ${VAR} are expanded."
+    )]
     pub shell: String,
 }
 
+impl TerminalConfig {
+    /// The shell with its environment variables expanded
+    pub fn shell(&self) -> String {
+        expand_env_vars(&self.shell)
+    }
+
+    /// The terminal font size and line height, which are both in px
+    pub const UNITS: &'static [(&'static str, &'static str)] =
+        &[("font_size", "px"), ("line_height", "px")];
+
+    /// The (min, max) of the numbers Lapce uses for the settings, a setting
+    /// outside its range doesn't load
+    pub const RANGES: &'static [(&'static str, f64, f64)] = &[
+        ("font_size", 0.0, f64::INFINITY),
+        ("line_height", 0.0, f64::INFINITY),
+    ];
+
+    /// An example command line for the shell the terminal starts, which
+    /// takes the shell's own arguments after it
+    pub const EXAMPLES: &'static [(&'static str, &'static str)] =
+        &[("shell", "$SHELL -l")];
+}
+
+/// The `Option<bool>` settings as (table, key), a null value means the
+/// setting is unset
+pub const NULLABLE_BOOL_SETTINGS: &[(&str, &str)] =
+    &[("editor", "show-breadcrumbs")];
+
+/// The settings that only apply to the files opened after they change as
+/// (table, key), like the mode the editors start in
+pub const REQUIRES_RELOAD: &[(&str, &str)] = &[("lapce", "modal")];
+
+/// The string settings that can't be empty as (table, key), the settings
+/// panel doesn't store an empty value for them
+pub const REQUIRED_SETTINGS: &[(&str, &str)] = &[("lapce", "color-theme")];
+
+/// The string settings that only take some values as ((table, key), values)
+pub const SETTING_OPTIONS: &[((&str, &str), &[&str])] = &[(
+    ("editor", "render-whitespace"),
+    &["none", "all", "boundary", "trailing"],
+)];
+
+/// The string settings naming a theme as (table, key), a name that isn't
+/// one of the available themes is rejected
+pub const THEME_NAME_SETTINGS: &[(&str, &str)] = &[
+    ("lapce", "color-theme"),
+    ("ui", "preferred-light-theme"),
+    ("ui", "preferred-dark-theme"),
+];
+
+/// The settings that follow a setting of another section while they have
+/// their default value, as ((table, key), (table, key)) of the dependent
+/// setting and the one it follows
+pub const SHARED_SETTINGS: &[((&str, &str), (&str, &str))] = &[
+    (("terminal", "font-family"), ("editor", "font-family")),
+    (("terminal", "font-size"), ("editor", "font-size")),
+];
+
+/// The settings whose value often applies to a related setting as
+/// ((table, key), (table, key)), the settings panel can copy the value of
+/// the first to the second
+pub const LINKED_SETTINGS: &[((&str, &str), (&str, &str))] = &[
+    (("ui", "font-family"), ("editor", "font-family")),
+    (("editor", "font-family"), ("ui", "font-family")),
+    (("terminal", "font-family"), ("editor", "font-family")),
+];
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ThemeConfig {
     #[serde(skip)]
     pub path: PathBuf,
     pub name: String,
+    /// The name of the theme this theme builds on, its colors are used for
+    /// the colors this theme doesn't define
+    #[serde(default)]
+    pub extends: String,
     pub base: ThemeBaseConfig,
     pub syntax: IndexMap<String, String>,
     pub ui: IndexMap<String, String>,
@@ -429,19 +880,7 @@ fn resolve_color(
         colors
             .iter()
             .map(|(name, hex)| {
-                if let Some(stripped) = hex.strip_prefix('$') {
-                    if let Some(c) = base.get(stripped) {
-                        return (name.to_string(), c.clone());
-                    }
-                    if let Some(default) = default {
-                        if let Some(c) = default.get(name) {
-                            return (name.to_string(), c.clone());
-                        }
-                    }
-                    return (name.to_string(), Color::rgb8(0, 0, 0));
-                }
-
-                if let Ok(c) = Color::from_hex_str(hex) {
+                if let Ok(c) = eval_color(hex, &|name| base.get(name).cloned()) {
                     return (name.to_string(), c);
                 }
                 if let Some(default) = default {
@@ -606,7 +1045,7 @@ fn default() -> Self {
 }
 
 impl ThemeBaseColor {
-    fn get(&self, name: &str) -> Option<&Color> {
+    pub fn get(&self, name: &str) -> Option<&Color> {
         Some(match name {
             "white" => &self.white,
             "black" => &self.black,
@@ -648,8 +1087,30 @@ pub struct Config {
     pub color: ThemeColor,
     #[serde(skip)]
     pub available_themes: HashMap<String, (String, config::Config)>,
+    /// The colors shown for each available theme in the theme gallery, by
+    /// the lowercase theme name
+    #[serde(skip)]
+    theme_swatches: HashMap<String, Vec<Color>>,
     #[serde(skip)]
     tab_layout_info: Arc<RwLock<HashMap<(FontFamily, usize), f64>>>,
+    /// The settings file of the workspace, if it has one
+    #[serde(skip)]
+    workspace_settings: Option<PathBuf>,
+    /// The settings set by the workspace settings file as "{table}.{key}",
+    /// these take precedence over the user settings
+    #[serde(skip)]
+    workspace_overrides: HashSet<String>,
+    /// The settings enforced by the workspace policy file as "{table}.{key}",
+    /// these can't be changed in the settings
+    #[serde(skip)]
+    policy_locked: HashSet<String>,
+    /// The values of the reference settings file, keyed by "{table}.{key}"
+    #[serde(skip)]
+    reference_values: HashMap<String, serde_json::Value>,
+    /// The theme colors that come from the themes the current theme extends,
+    /// as "theme.{kind}.{key}"
+    #[serde(skip)]
+    inherited_colors: HashSet<String>,
 }
 
 pub struct ConfigWatcher {
@@ -689,42 +1150,455 @@ pub fn load(workspace: &LapceWorkspace) -> Result<Self> {
         default_config.resolve_colors(None);
 
         let settings =
-            Self::merge_settings(default_settings.clone(), workspace, None);
+            Self::merge_settings(default_settings.clone(), workspace, &[]);
         let mut config: Config = settings.try_into()?;
         let available_themes = Self::load_themes();
+        let theme_swatches = Self::load_theme_swatches(
+            &default_settings,
+            &default_config,
+            &available_themes,
+        );
+        let mut inherited_colors = HashSet::new();
         if let Some((_, theme)) =
             available_themes.get(&config.lapce.color_theme.to_lowercase())
         {
-            if let Ok(theme_settings) =
-                default_settings.clone().with_merged(theme.clone())
-            {
-                if let Ok(mut theme_config) = theme_settings.try_into::<Config>() {
-                    theme_config.resolve_colors(Some(&default_config));
-                    default_config = theme_config;
-                }
-            }
-            config = Self::merge_settings(
-                default_settings,
+            // The theme it extends can be set by the theme file or the user
+            let extends = Self::merge_settings(
+                default_settings.clone(),
                 workspace,
-                Some(theme.clone()),
+                &[theme.clone()],
             )
-            .try_into()?;
+            .try_into::<Config>()?
+            .theme
+            .extends;
+            let mut themes = Self::theme_ancestors(
+                &available_themes,
+                &config.lapce.color_theme,
+                &extends,
+            );
+            themes.reverse();
+            themes.push(theme.clone());
+
+            let mut theme_settings = default_settings.clone();
+            for theme in themes.iter() {
+                let _ = theme_settings.merge(theme.clone());
+            }
+            if let Ok(mut theme_config) = theme_settings.try_into::<Config>() {
+                theme_config.resolve_colors(Some(&default_config));
+                default_config = theme_config;
+            }
+            config = Self::merge_settings(default_settings, workspace, &themes)
+                .try_into()?;
+            inherited_colors = Self::inherited_colors(&themes, workspace);
         }
+        config.inherited_colors = inherited_colors;
         config.update_id();
         config.available_themes = available_themes;
+        config.theme_swatches = theme_swatches;
         config.resolve_colors(Some(&default_config));
         config.default_theme = default_config.theme.clone();
+        config.workspace_settings =
+            Self::workspace_settings_file(workspace).filter(|p| p.exists());
+        config.workspace_overrides = config
+            .workspace_settings
+            .as_deref()
+            .and_then(Self::settings_keys_of_file)
+            .unwrap_or_default();
+        config.policy_locked = Self::workspace_policy_file(workspace)
+            .and_then(|path| Self::settings_keys_of_file(&path))
+            .unwrap_or_default();
+        let reference = expand_env_vars(config.lapce.reference_settings.trim());
+        config.reference_values = if reference.is_empty() {
+            HashMap::new()
+        } else {
+            std::fs::read_to_string(reference)
+                .ok()
+                .and_then(|content| Self::settings_values_of(&content))
+                .unwrap_or_default()
+        };
 
         Ok(config)
     }
 
+    /// The themes a theme extends, the one it extends directly first. A theme
+    /// that's extended again because of a cycle ends the chain.
+    fn theme_ancestors(
+        themes: &HashMap<String, (String, config::Config)>,
+        name: &str,
+        extends: &str,
+    ) -> Vec<config::Config> {
+        let mut seen = HashSet::new();
+        seen.insert(name.to_lowercase());
+        let mut ancestors = Vec::new();
+        let mut extends = extends.to_string();
+        while !extends.is_empty() && seen.insert(extends.to_lowercase()) {
+            let theme = match themes.get(&extends.to_lowercase()) {
+                Some((_, theme)) => theme,
+                None => break,
+            };
+            ancestors.push(theme.clone());
+            extends = theme.get_str("theme.extends").unwrap_or_default();
+        }
+        ancestors
+    }
+
+    /// The editor background and foreground and some of the base colors of
+    /// each theme, with the colors of the themes it extends filled in
+    fn load_theme_swatches(
+        default_settings: &config::Config,
+        default_config: &Config,
+        themes: &HashMap<String, (String, config::Config)>,
+    ) -> HashMap<String, Vec<Color>> {
+        themes
+            .iter()
+            .filter_map(|(key, (name, theme))| {
+                let extends = theme.get_str("theme.extends").unwrap_or_default();
+                let mut settings = default_settings.clone();
+                for ancestor in Self::theme_ancestors(themes, name, &extends)
+                    .into_iter()
+                    .rev()
+                {
+                    let _ = settings.merge(ancestor);
+                }
+                let _ = settings.merge(theme.clone());
+                let mut config: Config = settings.try_into().ok()?;
+                config.resolve_colors(Some(default_config));
+
+                let ui =
+                    [LapceTheme::EDITOR_BACKGROUND, LapceTheme::EDITOR_FOREGROUND]
+                        .into_iter()
+                        .filter_map(|name| config.color.ui.get(name));
+                let base = ["red", "yellow", "green", "blue", "purple"]
+                    .into_iter()
+                    .filter_map(|name| config.color.base.get(name));
+                Some((key.clone(), ui.chain(base).cloned().collect()))
+            })
+            .collect()
+    }
+
+    /// The colors of a theme file, as "theme.{kind}.{key}"
+    fn theme_color_keys(theme: &config::Config) -> HashSet<String> {
+        ["base", "ui", "syntax"]
+            .iter()
+            .flat_map(|kind| {
+                theme
+                    .get_table(&format!("theme.{kind}"))
+                    .map(|table| {
+                        table
+                            .into_keys()
+                            .map(|key| format!("theme.{kind}.{key}"))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// The colors that come from the extended themes, the last theme is the
+    /// current one and the others are the ones it extends. The colors that
+    /// the current theme, the user or the workspace set aren't inherited.
+    fn inherited_colors(
+        themes: &[config::Config],
+        workspace: &LapceWorkspace,
+    ) -> HashSet<String> {
+        let (theme, ancestors) = match themes.split_last() {
+            Some(themes) => themes,
+            None => return HashSet::new(),
+        };
+        let mut local = Self::theme_color_keys(theme);
+        for path in [
+            Self::settings_file(),
+            Self::workspace_settings_file(workspace),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            local.extend(Self::settings_keys_of_file(&path).unwrap_or_default());
+        }
+        ancestors
+            .iter()
+            .flat_map(Self::theme_color_keys)
+            .filter(|key| !local.contains(key))
+            .collect()
+    }
+
+    /// Whether a theme color comes from a theme the current theme extends,
+    /// `kind` being the settings table e.g. "theme.ui"
+    pub fn inherited_color(&self, kind: &str, key: &str) -> bool {
+        self.inherited_colors.contains(&format!("{kind}.{key}"))
+    }
+
+    /// The (kind, key) of the UI and syntax colors referring to a base color
+    pub fn base_color_references(&self, base: &str) -> Vec<(String, String)> {
+        [
+            ("theme.ui", &self.theme.ui),
+            ("theme.syntax", &self.theme.syntax),
+        ]
+        .into_iter()
+        .flat_map(|(kind, colors)| {
+            colors
+                .iter()
+                .filter(|(_, value)| referenced_base_colors(value).contains(&base))
+                .map(move |(key, _)| (kind.to_string(), key.to_string()))
+        })
+        .collect()
+    }
+
+    /// The settings in effect as pretty-printed JSON: the defaults with the
+    /// theme, the user settings and the workspace settings merged in
+    pub fn effective_json(&self) -> String {
+        let mut json = serde_json::json!({
+            "lapce": self.lapce,
+            "ui": self.ui,
+            "editor": self.editor,
+            "terminal": self.terminal,
+            "theme": self.theme,
+        });
+        if let Some(json) = json.as_object_mut() {
+            let mut plugins: Vec<_> = self.plugins.iter().collect();
+            plugins.sort_by_key(|(name, _)| *name);
+            for (name, value) in plugins {
+                json.insert(name.clone(), value.clone());
+            }
+        }
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+
+    /// The settings in effect of the `table` section, e.g. `editor`, as
+    /// `{"editor": {...}}`, with `changed_only` just the ones that differ
+    /// from the defaults
+    pub fn section_json(&self, table: &str, changed_only: bool) -> Option<String> {
+        let mut values = match self.setting_table(table)? {
+            serde_json::Value::Object(values) => values,
+            _ => return None,
+        };
+        if changed_only {
+            values.retain(|key, value| {
+                Self::default_setting_table(table).and_then(|t| t.get(key))
+                    != Some(value)
+            });
+        }
+        let mut json = serde_json::Map::new();
+        json.insert(table.to_string(), serde_json::Value::Object(values));
+        serde_json::to_string_pretty(&json).ok()
+    }
+
+    /// The colors shown for the available theme `name` in the theme gallery
+    pub fn theme_swatches(&self, name: &str) -> &[Color] {
+        self.theme_swatches
+            .get(&name.to_lowercase())
+            .map(|swatches| swatches.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The theme colors set to the hex color `color`, as (kind, key), e.g.
+    /// `("theme.ui", "background")`. Colors written as `#abc`, `#AABBCC` and
+    /// `#AABBCCFF` are the same.
+    pub fn theme_colors_set_to(&self, color: &str) -> Vec<(String, String)> {
+        let color = match normalize_pasted_color(color) {
+            Some(color) => color,
+            None => return Vec::new(),
+        };
+        let base =
+            self.color.base.keys().into_iter().filter_map(|key| {
+                Some(("theme.base", key, self.theme.base.get(key)?))
+            });
+        let ui = self
+            .theme
+            .ui
+            .iter()
+            .map(|(key, value)| ("theme.ui", key.as_str(), value));
+        let syntax = self
+            .theme
+            .syntax
+            .iter()
+            .map(|(key, value)| ("theme.syntax", key.as_str(), value));
+        base.chain(ui)
+            .chain(syntax)
+            .filter(|(_, _, value)| {
+                normalize_pasted_color(value).as_ref() == Some(&color)
+            })
+            .map(|(kind, key, _)| (kind.to_string(), key.to_string()))
+            .collect()
+    }
+
+    /// Every theme color as (kind, key, value), grouped by kind in the order
+    /// of the theme settings columns, e.g. `("theme.base", "red", "#E06C75")`
+    pub fn theme_color_index(&self) -> Vec<(String, String, String)> {
+        let base = self
+            .color
+            .base
+            .keys()
+            .into_iter()
+            .sorted()
+            .filter_map(|key| Some(("theme.base", key, self.theme.base.get(key)?)));
+        let syntax = self
+            .theme
+            .syntax
+            .iter()
+            .map(|(key, value)| ("theme.syntax", key.as_str(), value))
+            .sorted_by_key(|(_, key, _)| *key);
+        let ui = self
+            .theme
+            .ui
+            .iter()
+            .map(|(key, value)| ("theme.ui", key.as_str(), value))
+            .sorted_by_key(|(_, key, _)| *key);
+        base.chain(syntax)
+            .chain(ui)
+            .map(|(kind, key, value)| {
+                (kind.to_string(), key.to_string(), value.to_string())
+            })
+            .collect()
+    }
+
+    /// The color a theme color of `kind`, like "theme.ui", evaluates to
+    pub fn theme_color(&self, kind: &str, key: &str) -> Option<&Color> {
+        match kind {
+            "theme.base" => self.color.base.get(key),
+            "theme.ui" => self.color.ui.get(key),
+            "theme.syntax" => self.color.syntax.get(key),
+            _ => None,
+        }
+    }
+
+    /// The policy file of a workspace, with the settings a team enforces. Its
+    /// values take precedence over both the user and the workspace settings.
+    fn workspace_policy_file(workspace: &LapceWorkspace) -> Option<PathBuf> {
+        match workspace.kind {
+            LapceWorkspaceType::Local => workspace
+                .path
+                .as_ref()
+                .map(|path| path.join("./.lapce/policy.toml")),
+            LapceWorkspaceType::RemoteSSH(_, _) => None,
+            LapceWorkspaceType::RemoteWSL => None,
+        }
+    }
+
+    /// The settings file of a workspace, only local workspaces can have one
+    fn workspace_settings_file(workspace: &LapceWorkspace) -> Option<PathBuf> {
+        match workspace.kind {
+            LapceWorkspaceType::Local => workspace
+                .path
+                .as_ref()
+                .map(|path| path.join("./.lapce/settings.toml")),
+            LapceWorkspaceType::RemoteSSH(_, _) => None,
+            LapceWorkspaceType::RemoteWSL => None,
+        }
+    }
+
+    /// All the settings that are set in a settings file, as "{table}.{key}"
+    fn settings_keys_of_file(path: &Path) -> Option<HashSet<String>> {
+        fn collect(
+            table: &dyn toml_edit::TableLike,
+            prefix: &str,
+            keys: &mut HashSet<String>,
+        ) {
+            for (key, item) in table.iter() {
+                let name = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                if let Some(table) = item.as_table_like() {
+                    collect(table, &name, keys);
+                } else if !prefix.is_empty() {
+                    let key = if prefix.starts_with("theme") {
+                        key.to_string()
+                    } else {
+                        key.replace('_', "-")
+                    };
+                    keys.insert(format!("{prefix}.{key}"));
+                }
+            }
+        }
+
+        let content = std::fs::read_to_string(path).ok()?;
+        let document: toml_edit::Document = content.parse().ok()?;
+        let mut keys = HashSet::new();
+        collect(document.as_table(), "", &mut keys);
+        Some(keys)
+    }
+
+    /// The workspace settings file, when the workspace has settings of its own
+    pub fn workspace_settings(&self) -> Option<&Path> {
+        self.workspace_settings.as_deref()
+    }
+
+    /// The values a settings file sets, keyed by "{table}.{key}" like the
+    /// keys of `settings_keys_of_file`
+    pub fn settings_values_of(
+        content: &str,
+    ) -> Option<HashMap<String, serde_json::Value>> {
+        fn collect(
+            table: &toml::value::Table,
+            prefix: &str,
+            values: &mut HashMap<String, serde_json::Value>,
+        ) {
+            for (key, value) in table.iter() {
+                let name = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                if let Some(table) = value.as_table() {
+                    collect(table, &name, values);
+                } else if !prefix.is_empty() {
+                    let key = if prefix.starts_with("theme") {
+                        key.to_string()
+                    } else {
+                        key.replace('_', "-")
+                    };
+                    if let Ok(value) = serde_json::to_value(value) {
+                        values.insert(format!("{prefix}.{key}"), value);
+                    }
+                }
+            }
+        }
+
+        let value: toml::Value = toml::from_str(content).ok()?;
+        let mut values = HashMap::new();
+        collect(value.as_table()?, "", &mut values);
+        Some(values)
+    }
+
+    /// The value the reference settings file gives a setting, when the value
+    /// in effect is a different one. Numbers are the same whether they're
+    /// written as integers or floats.
+    pub fn reference_difference(
+        &self,
+        parent: &str,
+        key: &str,
+    ) -> Option<&serde_json::Value> {
+        let reference = self.reference_values.get(&format!("{parent}.{key}"))?;
+        let value = self.setting_value(parent, key)?;
+        let same = match (value.as_f64(), reference.as_f64()) {
+            (Some(value), Some(reference)) => value == reference,
+            _ => &value == reference,
+        };
+        (!same).then(|| reference)
+    }
+
+    /// Whether the workspace settings override the user value of a setting
+    pub fn overridden_by_workspace(&self, parent: &str, key: &str) -> bool {
+        self.workspace_overrides
+            .contains(&format!("{parent}.{key}"))
+    }
+
+    /// Whether the workspace policy enforces the value of a setting
+    pub fn locked_by_policy(&self, parent: &str, key: &str) -> bool {
+        self.policy_locked.contains(&format!("{parent}.{key}"))
+    }
+
+    /// Merge the themes, in order, and the user and workspace settings into
+    /// the settings
     fn merge_settings(
         mut settings: config::Config,
         workspace: &LapceWorkspace,
-        theme: Option<config::Config>,
+        themes: &[config::Config],
     ) -> config::Config {
-        if let Some(theme) = theme {
-            let _ = settings.merge(theme);
+        for theme in themes {
+            let _ = settings.merge(theme.clone());
         }
 
         if let Some(path) = Self::settings_file() {
@@ -732,16 +1606,14 @@ fn merge_settings(
                 settings.merge(config::File::from(path.as_path()).required(false));
         }
 
-        match workspace.kind {
-            LapceWorkspaceType::Local => {
-                if let Some(path) = workspace.path.as_ref() {
-                    let path = path.join("./.lapce/settings.toml");
-                    let _ = settings
-                        .merge(config::File::from(path.as_path()).required(false));
-                }
-            }
-            LapceWorkspaceType::RemoteSSH(_, _) => {}
-            LapceWorkspaceType::RemoteWSL => {}
+        if let Some(path) = Self::workspace_settings_file(workspace) {
+            let _ =
+                settings.merge(config::File::from(path.as_path()).required(false));
+        }
+
+        if let Some(path) = Self::workspace_policy_file(workspace) {
+            let _ =
+                settings.merge(config::File::from(path.as_path()).required(false));
         }
 
         settings
@@ -826,36 +1698,418 @@ fn load_theme(path: &Path) -> Option<(String, (String, config::Config))> {
         Some((name.to_lowercase(), (name, settings)))
     }
 
-    fn default_settings() -> config::Config {
-        config::Config::default()
-            .with_merged(config::File::from_str(
-                DEFAULT_SETTINGS,
-                config::FileFormat::Toml,
-            ))
-            .unwrap()
+    /// Check that the file at `path` is a theme, returning the theme's name
+    pub fn theme_file_name(path: &Path) -> Result<String, String> {
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            return Err(format!("{} is not a .toml file", path.display()));
+        }
+        let settings = config::Config::new()
+            .with_merged(config::File::from(path))
+            .map_err(|e| format!("Can't read {}: {e}", path.display()))?;
+        settings
+            .get_table("theme")
+            .ok()
+            .and_then(|table| table.get("name").map(|name| name.to_string()))
+            .ok_or_else(|| format!("{} doesn't have a [theme] name", path.display()))
     }
 
-    pub fn export_theme(&self) -> String {
-        let mut table = toml::value::Table::new();
-        let mut theme = self.theme.clone();
-        theme.name = "".to_string();
-        theme.syntax.sort_keys();
-        theme.ui.sort_keys();
-        table.insert("theme".to_string(), toml::Value::try_from(&theme).unwrap());
-        table.insert("ui".to_string(), toml::Value::try_from(&self.ui).unwrap());
-        let value = toml::Value::Table(table);
-        toml::to_string_pretty(&value).unwrap()
+    /// The file in the themes folder that defines the theme `name`
+    pub fn user_theme_file(name: &str) -> Option<PathBuf> {
+        let folder = Directory::themes_directory()?;
+        std::fs::read_dir(folder)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| {
+                Self::theme_file_name(path)
+                    .map(|theme| theme.eq_ignore_ascii_case(name))
+                    .unwrap_or(false)
+            })
     }
 
-    pub fn keymaps_file() -> Option<PathBuf> {
-        let path = Directory::config_directory()?.join("keymaps.toml");
-
-        if !path.exists() {
-            let _ = std::fs::OpenOptions::new()
-                .create_new(true)
-                .write(true)
-                .open(&path);
-        }
+    /// The file of the theme `name`, in the themes folder or in a plugin
+    pub fn theme_file(name: &str) -> Option<PathBuf> {
+        Self::user_theme_file(name).or_else(|| {
+            find_all_volts()
+                .into_iter()
+                .flat_map(|meta| meta.themes.unwrap_or_default())
+                .map(PathBuf::from)
+                .find(|path| {
+                    Self::theme_file_name(path)
+                        .map(|theme| theme.eq_ignore_ascii_case(name))
+                        .unwrap_or(false)
+                })
+        })
+    }
+
+    /// The files that can set a theme color, in order of precedence: the
+    /// workspace and the user settings, then the files of the current theme
+    /// and of the themes it extends. The built-in themes don't have a file.
+    pub fn theme_color_files(&self) -> Vec<PathBuf> {
+        let ancestors = Self::theme_ancestors(
+            &self.available_themes,
+            &self.lapce.color_theme,
+            &self.theme.extends,
+        );
+        let themes = std::iter::once(self.lapce.color_theme.clone()).chain(
+            ancestors
+                .iter()
+                .filter_map(|theme| theme.get_str("theme.name").ok()),
+        );
+        [self.workspace_settings.clone(), Self::settings_file()]
+            .into_iter()
+            .flatten()
+            .chain(themes.filter_map(|name| Self::theme_file(&name)))
+            .collect()
+    }
+
+    /// Why `name` can't be the name of a new or renamed theme, if it can't
+    pub fn theme_name_error(&self, name: &str) -> Option<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Some("The theme needs a name".to_string());
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| c.is_control() || "/\\:*?\"<>|".contains(*c))
+        {
+            return Some(format!("A theme name can't contain {c:?}"));
+        }
+        if self.available_themes.contains_key(&name.to_lowercase()) {
+            return Some(format!("There is already a theme named \"{name}\""));
+        }
+        None
+    }
+
+    /// Write the theme file at `path` to a new file in the themes folder as
+    /// the theme `name`, removing the old file when renaming. The new name
+    /// can't be the name of an available theme or of an existing file.
+    pub fn save_theme_file_as(
+        &self,
+        path: &Path,
+        name: &str,
+        rename: bool,
+    ) -> Result<PathBuf, String> {
+        if let Some(err) = self.theme_name_error(name) {
+            return Err(err);
+        }
+        let name = name.trim();
+        let folder = Directory::themes_directory()
+            .ok_or_else(|| "There is no themes folder".to_string())?;
+        let file_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let dest = folder.join(format!("{file_name}.toml"));
+        if dest.exists() {
+            return Err(format!("{} already exists", dest.display()));
+        }
+
+        let mut document: toml_edit::Document = std::fs::read_to_string(path)
+            .map_err(|e| format!("Can't read {}: {e}", path.display()))?
+            .parse()
+            .map_err(|e| format!("Can't parse {}: {e}", path.display()))?;
+        document["theme"]["name"] = toml_edit::value(name);
+        std::fs::write(&dest, document.to_string())
+            .map_err(|e| format!("Can't write {}: {e}", dest.display()))?;
+        if rename {
+            std::fs::remove_file(path)
+                .map_err(|e| format!("Can't remove {}: {e}", path.display()))?;
+        }
+        Ok(dest)
+    }
+
+    fn default_settings() -> config::Config {
+        config::Config::default()
+            .with_merged(config::File::from_str(
+                DEFAULT_SETTINGS,
+                config::FileFormat::Toml,
+            ))
+            .unwrap()
+    }
+
+    /// The bundled default settings, shown to users as a read-only reference
+    pub fn default_settings_text() -> &'static str {
+        DEFAULT_SETTINGS
+    }
+
+    /// The core, UI, editor and terminal settings whose default differs
+    /// between the default settings texts `previous` and `current`. Settings
+    /// new in `current` had no default to change from and aren't listed.
+    pub fn changed_defaults_between(
+        previous: &str,
+        current: &str,
+    ) -> Vec<SettingChange> {
+        let previous: serde_json::Value =
+            toml::from_str(previous).unwrap_or_default();
+        let current: serde_json::Value = toml::from_str(current).unwrap_or_default();
+        let mut changes = Vec::new();
+        for table in ["lapce", "ui", "editor", "terminal"] {
+            let values = match current.get(table).and_then(|t| t.as_object()) {
+                Some(values) => values,
+                None => continue,
+            };
+            for (key, new) in values {
+                let old = match previous.get(table).and_then(|t| t.get(key)) {
+                    Some(old) => old,
+                    None => continue,
+                };
+                if old != new {
+                    changes.push(SettingChange {
+                        table: table.to_string(),
+                        key: key.to_string(),
+                        old: Some(old.clone()),
+                        new: new.clone(),
+                        unset: false,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn export_theme(&self) -> String {
+        let mut table = toml::value::Table::new();
+        let mut theme = self.theme.clone();
+        theme.name = "".to_string();
+        theme.syntax.sort_keys();
+        theme.ui.sort_keys();
+        table.insert("theme".to_string(), toml::Value::try_from(&theme).unwrap());
+        table.insert("ui".to_string(), toml::Value::try_from(&self.ui).unwrap());
+        let value = toml::Value::Table(table);
+        toml::to_string_pretty(&value).unwrap()
+    }
+
+    /// The settings of the core, UI, editor and terminal tables as a settings
+    /// file with every field commented with its description. The fields that
+    /// have the default value are commented out, so they're listed without
+    /// overriding anything.
+    pub fn export_settings(&self) -> String {
+        fn into_settings_map(
+            data: &impl Serialize,
+        ) -> serde_json::Map<String, serde_json::Value> {
+            match serde_json::to_value(data) {
+                Ok(serde_json::Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            }
+        }
+
+        let tables = [
+            (
+                "lapce",
+                &LapceConfig::FIELDS[..],
+                &LapceConfig::DESCS[..],
+                into_settings_map(&self.lapce),
+            ),
+            (
+                "ui",
+                &UIConfig::FIELDS[..],
+                &UIConfig::DESCS[..],
+                into_settings_map(&self.ui),
+            ),
+            (
+                "editor",
+                &EditorConfig::FIELDS[..],
+                &EditorConfig::DESCS[..],
+                into_settings_map(&self.editor),
+            ),
+            (
+                "terminal",
+                &TerminalConfig::FIELDS[..],
+                &TerminalConfig::DESCS[..],
+                into_settings_map(&self.terminal),
+            ),
+        ];
+
+        let mut text = String::new();
+        for (table, fields, descs, values) in tables {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("[{table}]\n"));
+            for (field, desc) in fields.iter().zip(descs.iter()) {
+                let key = field.replace('_', "-");
+                let value = match values.get(&key) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let toml_value = match toml_edit::ser::to_item(value)
+                    .ok()
+                    .and_then(|item| item.into_value().ok())
+                {
+                    Some(toml_value) => toml_value,
+                    None => continue,
+                };
+                for line in desc.lines() {
+                    text.push_str(&format!("# {line}\n"));
+                }
+                if Self::default_setting_table(table).and_then(|t| t.get(&key))
+                    == Some(value)
+                {
+                    text.push_str("# ");
+                }
+                text.push_str(&format!("{key} = {toml_value}\n"));
+            }
+        }
+        text
+    }
+
+    /// Documentation of the core, UI, editor and terminal settings as
+    /// Markdown, a table of the settings with their type, default and
+    /// description under a header for each section
+    pub fn settings_docs_markdown() -> String {
+        fn type_name(value: &serde_json::Value) -> &'static str {
+            match value {
+                serde_json::Value::Bool(_) => "boolean",
+                serde_json::Value::Number(n) if n.is_f64() => "float",
+                serde_json::Value::Number(_) => "integer",
+                serde_json::Value::String(_) => "string",
+                serde_json::Value::Array(_) => "array",
+                serde_json::Value::Object(_) => "table",
+                serde_json::Value::Null => "optional",
+            }
+        }
+
+        let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        let tables = [
+            (
+                "Core Settings",
+                "lapce",
+                &LapceConfig::FIELDS[..],
+                &LapceConfig::DESCS[..],
+            ),
+            (
+                "UI Settings",
+                "ui",
+                &UIConfig::FIELDS[..],
+                &UIConfig::DESCS[..],
+            ),
+            (
+                "Editor Settings",
+                "editor",
+                &EditorConfig::FIELDS[..],
+                &EditorConfig::DESCS[..],
+            ),
+            (
+                "Terminal Settings",
+                "terminal",
+                &TerminalConfig::FIELDS[..],
+                &TerminalConfig::DESCS[..],
+            ),
+        ];
+
+        let mut text = String::from("# Lapce Settings\n");
+        for (title, table, fields, descs) in tables {
+            text.push_str(&format!("\n## {title}\n\n"));
+            text.push_str(&format!(
+                "In the `[{table}]` table of settings.toml.\n\n"
+            ));
+            text.push_str("| Setting | Type | Default | Description |\n");
+            text.push_str("| --- | --- | --- | --- |\n");
+            for (field, desc) in fields.iter().zip(descs.iter()) {
+                let key = field.replace('_', "-");
+                let default = Self::default_setting_table(table)
+                    .and_then(|t| t.get(&key))
+                    .unwrap_or(&serde_json::Value::Null);
+                let kind = match Self::setting_unit(table, &key) {
+                    Some(unit) => format!("{}, {unit}", type_name(default)),
+                    None => type_name(default).to_string(),
+                };
+                let default = match default {
+                    serde_json::Value::Null => "unset".to_string(),
+                    value => format!("`{}`", cell(&value.to_string())),
+                };
+                text.push_str(&format!(
+                    "| `{key}` | {kind} | {default} | {} |\n",
+                    cell(desc)
+                ));
+            }
+        }
+        text
+    }
+
+    /// The settings side by side with their defaults, as TOML with the
+    /// default of each changed setting in a comment after its value
+    pub fn compare_with_defaults(&self, changed_only: bool) -> String {
+        // Written as TOML, so that the rows can be pasted into settings.toml
+        fn display(value: &serde_json::Value) -> String {
+            match value {
+                serde_json::Value::Null => "unset".to_string(),
+                value => toml_edit::ser::to_item(value)
+                    .ok()
+                    .and_then(|item| item.into_value().ok())
+                    .map(|value| value.to_string().trim().to_string())
+                    .unwrap_or_else(|| value.to_string()),
+            }
+        }
+
+        let mut text = String::new();
+        for table in ["lapce", "ui", "editor", "terminal"] {
+            let values = match self.setting_table(table) {
+                Some(serde_json::Value::Object(values)) => values,
+                _ => continue,
+            };
+            let rows: Vec<(String, Option<String>)> = values
+                .iter()
+                .filter_map(|(key, value)| {
+                    let default = Self::default_setting_table(table)
+                        .and_then(|t| t.get(key))
+                        .unwrap_or(&serde_json::Value::Null);
+                    let changed = default != value;
+                    if changed_only && !changed {
+                        return None;
+                    }
+                    // Unset settings can't be written in TOML
+                    let row = if value.is_null() {
+                        format!("# {key} = unset")
+                    } else {
+                        format!("{key} = {}", display(value))
+                    };
+                    Some((row, changed.then(|| display(default))))
+                })
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+
+            let width = rows
+                .iter()
+                .map(|(row, _)| row.chars().count())
+                .max()
+                .unwrap_or(0);
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("[{table}]\n"));
+            for (row, default) in rows {
+                let note = match default {
+                    Some(default) => format!("changed, default: {default}"),
+                    None => "default".to_string(),
+                };
+                text.push_str(&format!("{row:width$}  # {note}\n"));
+            }
+        }
+        if text.is_empty() {
+            text.push_str("# All settings are at their defaults\n");
+        }
+        text
+    }
+
+    pub fn keymaps_file() -> Option<PathBuf> {
+        let path = Directory::config_directory()?.join("keymaps.toml");
+
+        if !path.exists() {
+            let _ = std::fs::OpenOptions::new()
+                .create_new(true)
+                .write(true)
+                .open(&path);
+        }
 
         Some(path)
     }
@@ -890,35 +2144,518 @@ pub fn settings_file() -> Option<PathBuf> {
         Some(path)
     }
 
-    fn get_file_table() -> Option<toml_edit::Document> {
-        let path = Self::settings_file()?;
-        let content = std::fs::read_to_string(path).ok()?;
-        let document: toml_edit::Document = content.parse().ok()?;
-        Some(document)
+    /// The `(parent, key)` of every setting the settings file sets
+    pub fn settings_file_keys() -> Vec<(String, String)> {
+        Self::settings_file()
+            .map(|path| Self::settings_file_keys_at(&path))
+            .unwrap_or_default()
     }
 
-    pub fn reset_setting(parent: &str, key: &str) -> Option<()> {
-        let mut main_table = Self::get_file_table().unwrap_or_default();
+    /// The `(parent, key)` of every setting the settings file at `path` sets
+    fn settings_file_keys_at(path: &Path) -> Vec<(String, String)> {
+        fn collect(
+            table: &toml_edit::Table,
+            parent: &str,
+            keys: &mut Vec<(String, String)>,
+        ) {
+            for (key, item) in table.iter() {
+                match item.as_table() {
+                    Some(table) => {
+                        let parent = if parent.is_empty() {
+                            key.to_string()
+                        } else {
+                            format!("{parent}.{key}")
+                        };
+                        collect(table, &parent, keys);
+                    }
+                    None if !parent.is_empty() => {
+                        keys.push((parent.to_string(), key.to_string()));
+                    }
+                    None => {}
+                }
+            }
+        }
 
-        // Find the container table
-        let mut table = main_table.as_table_mut();
-        for key in parent.split('.') {
-            if !table.contains_key(key) {
-                table.insert(
-                    key,
-                    toml_edit::Item::Table(toml_edit::Table::default()),
+        let mut keys = Vec::new();
+        if let Some(document) = Self::get_file_table_at(path) {
+            collect(document.as_table(), "", &mut keys);
+        }
+        keys
+    }
+
+    /// Copy the settings file and the installed themes into a new timestamped
+    /// folder under `backups` in the config directory, returning that folder
+    pub fn backup_settings() -> Result<PathBuf, String> {
+        let time = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let dir = Directory::config_directory()
+            .ok_or_else(|| "Can't find the config directory".to_string())?
+            .join("backups")
+            .join(time.to_string());
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Can't create {}: {e}", dir.display()))?;
+
+        if let Some(path) = Self::settings_file() {
+            std::fs::copy(&path, dir.join("settings.toml"))
+                .map_err(|e| format!("Can't back up {}: {e}", path.display()))?;
+        }
+
+        if let Some(themes) = Directory::themes_directory() {
+            let themes_backup = dir.join("themes");
+            std::fs::create_dir_all(&themes_backup).map_err(|e| {
+                format!("Can't create {}: {e}", themes_backup.display())
+            })?;
+            for entry in std::fs::read_dir(&themes)
+                .map_err(|e| format!("Can't read {}: {e}", themes.display()))?
+                .flatten()
+            {
+                let path = entry.path();
+                if path.is_file() {
+                    std::fs::copy(&path, themes_backup.join(entry.file_name()))
+                        .map_err(|e| {
+                            format!("Can't back up {}: {e}", path.display())
+                        })?;
+                }
+            }
+        }
+
+        Ok(dir)
+    }
+
+    /// Keep the default settings of the version run last in the config
+    /// directory, with the ones of the version before it once they differ,
+    /// so the defaults an upgrade changed can be listed
+    pub fn snapshot_default_settings() {
+        let dir = match Directory::config_directory() {
+            Some(dir) => dir,
+            None => return,
+        };
+        let path = dir.join("default-settings.toml");
+        match std::fs::read_to_string(&path) {
+            Ok(snapshot) if snapshot == DEFAULT_SETTINGS => return,
+            Ok(_) => {
+                let _ = std::fs::rename(
+                    &path,
+                    dir.join("previous-default-settings.toml"),
                 );
             }
-            table = table.get_mut(key)?.as_table_mut()?;
+            Err(_) => {}
         }
+        let _ = std::fs::write(&path, DEFAULT_SETTINGS);
+    }
 
-        table.remove(key);
+    /// The settings whose defaults changed since the version run before this
+    /// one, from the previous default to the current one
+    pub fn changed_defaults() -> Vec<SettingChange> {
+        Directory::config_directory()
+            .and_then(|dir| {
+                std::fs::read_to_string(dir.join("previous-default-settings.toml"))
+                    .ok()
+            })
+            .map(|previous| {
+                Self::changed_defaults_between(&previous, DEFAULT_SETTINGS)
+            })
+            .unwrap_or_default()
+    }
 
-        // Store
-        let path = Self::settings_file()?;
-        std::fs::write(&path, main_table.to_string().as_bytes()).ok()?;
+    /// The backup folders made by `backup_settings` that have a settings
+    /// file, newest first
+    pub fn settings_backups() -> Vec<PathBuf> {
+        let dir = match Directory::config_directory() {
+            Some(dir) => dir.join("backups"),
+            None => return Vec::new(),
+        };
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.join("settings.toml").is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // The folders are named by the time they were made
+        backups.sort();
+        backups.reverse();
+        backups
+    }
+
+    /// The changes that set the settings back to the ones of the backup
+    /// folder `dir`, the settings the backup doesn't set but the settings
+    /// file at `settings_file` does are unset so that they follow their
+    /// defaults again
+    pub fn backup_changes(
+        &self,
+        dir: &Path,
+        settings_file: &Path,
+    ) -> Result<Vec<SettingChange>, String> {
+        let path = dir.join("settings.toml");
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("Can't read {}: {e}", path.display()))?
+            .parse::<toml_edit::Document>()
+            .map_err(|e| format!("Can't parse {}: {e}", path.display()))?;
+
+        let mut keys = Self::settings_file_keys_at(&path);
+        for key in Self::settings_file_keys_at(settings_file) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        Ok(keys
+            .into_iter()
+            .filter_map(|(table, key)| {
+                let backed_up = Self::file_setting_value_at(&path, &table, &key);
+                let unset = backed_up.is_none();
+                let new = backed_up
+                    .or_else(|| Self::default_setting_value(&table, &key))?;
+                let old = self.setting_value(&table, &key);
+                // The settings only the current settings file sets are unset
+                // even when it sets them to their default, so that they
+                // follow later defaults
+                (unset || old.as_ref() != Some(&new)).then(|| SettingChange {
+                    table,
+                    key,
+                    old,
+                    new,
+                    unset,
+                })
+            })
+            .collect())
+    }
+
+    /// The value the settings file at `path` gives a setting, if it sets it
+    pub fn file_setting_value_at(
+        path: &Path,
+        parent: &str,
+        key: &str,
+    ) -> Option<serde_json::Value> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let mut value: toml::Value = toml::from_str(&content).ok()?;
+        for table in parent.split('.') {
+            value = value.get(table)?.clone();
+        }
+        serde_json::to_value(value.get(key)?).ok()
+    }
+
+    /// The value of a setting in effect, as it's shown in the settings panel
+    pub fn setting_value(
+        &self,
+        parent: &str,
+        key: &str,
+    ) -> Option<serde_json::Value> {
+        self.setting_table(parent)?.get(key).cloned()
+    }
+
+    /// The settings of a settings file table, keyed by setting
+    fn setting_table(&self, parent: &str) -> Option<serde_json::Value> {
+        match parent {
+            "lapce" => serde_json::to_value(&self.lapce),
+            "ui" => serde_json::to_value(&self.ui),
+            "editor" => serde_json::to_value(&self.editor),
+            "terminal" => serde_json::to_value(&self.terminal),
+            "theme" => serde_json::to_value(&self.theme),
+            "theme.base" => serde_json::to_value(&self.theme.base),
+            "theme.ui" => serde_json::to_value(&self.theme.ui),
+            "theme.syntax" => serde_json::to_value(&self.theme.syntax),
+            _ => return None,
+        }
+        .ok()
+    }
+
+    /// Check the value a setting has in effect, with the same checks the
+    /// settings panel runs on what's typed into its inputs
+    pub fn validate(&self, kind: &str, name: &str) -> Result<(), ValidationError> {
+        let value = self.setting_value(kind, name).ok_or_else(|| {
+            ValidationError::UnknownSetting {
+                kind: kind.to_string(),
+                name: name.to_string(),
+            }
+        })?;
+        let value_kind = SettingsValueKind::of(&value);
+        parse_setting_input(
+            self,
+            &value_kind,
+            kind,
+            name,
+            &settings_value_text(&value_kind, &value),
+        )
+        .map(|_| ())
+        .map_err(|reason| ValidationError::InvalidValue {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            reason,
+        })
+    }
+
+    /// Check a value for a setting, telling why Lapce wouldn't use it: an
+    /// empty required string, a number out of range, a string that isn't one
+    /// of the options or themes it takes, or another type than the default
+    pub fn check_setting_value(
+        &self,
+        parent: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), String> {
+        match value {
+            serde_json::Value::String(s) => {
+                if s.trim().is_empty() && Self::required_setting(parent, key) {
+                    return Err("This setting can't be empty".to_string());
+                }
+                if let Some(options) = Self::setting_options(parent, key) {
+                    if !options.contains(&s.as_str()) {
+                        return Err(format!(
+                            "Expected one of {}",
+                            options.join(", ")
+                        ));
+                    }
+                }
+                if !s.is_empty()
+                    && Self::theme_name_setting(parent, key)
+                    && !self.available_themes.contains_key(&s.to_lowercase())
+                {
+                    return Err(format!("There's no theme named {s}"));
+                }
+            }
+            serde_json::Value::Number(n) => {
+                if let Some(err) = n
+                    .as_f64()
+                    .and_then(|n| Self::setting_range_error(parent, key, n))
+                {
+                    return Err(err);
+                }
+            }
+            _ => {}
+        }
+        match Self::default_setting_value(parent, key) {
+            Some(default)
+                if !default.is_null()
+                    && json_type_name(value) != json_type_name(&default) =>
+            {
+                Err(format!("Expected {}", json_type_name(&default)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Why Lapce doesn't use the number for a setting, if it's out of the
+    /// range of the setting
+    pub fn setting_range_error(parent: &str, key: &str, n: f64) -> Option<String> {
+        let (min, max) = Self::setting_range(parent, key)?;
+        if n < min {
+            Some(format!("Lapce uses {min} or more"))
+        } else if n > max {
+            Some(format!("Lapce uses {max} or less"))
+        } else {
+            None
+        }
+    }
+
+    /// The values a string setting takes, if it only takes some
+    pub fn setting_options(
+        parent: &str,
+        key: &str,
+    ) -> Option<&'static [&'static str]> {
+        SETTING_OPTIONS
+            .iter()
+            .find(|((table, field), _)| *table == parent && *field == key)
+            .map(|(_, options)| *options)
+    }
+
+    /// Whether a setting names one of the available themes
+    pub fn theme_name_setting(parent: &str, key: &str) -> bool {
+        THEME_NAME_SETTINGS
+            .iter()
+            .any(|(table, field)| *table == parent && *field == key)
+    }
+
+    /// The value a setting has in the bundled default settings
+    pub fn default_setting_value(
+        parent: &str,
+        key: &str,
+    ) -> Option<serde_json::Value> {
+        Self::default_setting_table(parent)?.get(key).cloned()
+    }
+
+    /// The settings of a table of the bundled default settings, keyed by
+    /// setting
+    pub fn default_setting_table(
+        parent: &str,
+    ) -> Option<&'static serde_json::Value> {
+        DEFAULT_SETTING_TABLES.get(parent)
+    }
+
+    /// Whether a setting is a boolean that can also be unset, leaving the
+    /// choice to the default
+    pub fn nullable_bool_setting(parent: &str, key: &str) -> bool {
+        NULLABLE_BOOL_SETTINGS
+            .iter()
+            .any(|(table, field)| *table == parent && *field == key)
+    }
+
+    /// The (table, key) of the setting the value of a setting can be copied
+    /// to, if one is linked to it
+    pub fn linked_setting(
+        parent: &str,
+        key: &str,
+    ) -> Option<(&'static str, &'static str)> {
+        LINKED_SETTINGS
+            .iter()
+            .find(|((table, field), _)| *table == parent && *field == key)
+            .map(|(_, target)| *target)
+    }
+
+    /// The (table, key) of the setting a setting follows while it has its
+    /// default value, if it follows one
+    pub fn shared_setting(
+        parent: &str,
+        key: &str,
+    ) -> Option<(&'static str, &'static str)> {
+        SHARED_SETTINGS
+            .iter()
+            .find(|((table, field), _)| *table == parent && *field == key)
+            .map(|(_, source)| *source)
+    }
+
+    /// The (table, key) and value of the setting a setting doesn't follow
+    /// anymore, when it's set to something else than its default and that
+    /// setting's value
+    pub fn diverged_setting(
+        &self,
+        parent: &str,
+        key: &str,
+    ) -> Option<(&'static str, &'static str, serde_json::Value)> {
+        let (table, field) = Self::shared_setting(parent, key)?;
+        let value = self.setting_value(parent, key)?;
+        let source = self.setting_value(table, field)?;
+        let diverged = value != source
+            && Some(&value) != Self::default_setting_value(parent, key).as_ref();
+        diverged.then(|| (table, field, source))
+    }
+
+    /// Whether the open files only follow a change of the setting once
+    /// they're reloaded
+    pub fn requires_reload(parent: &str, key: &str) -> bool {
+        REQUIRES_RELOAD
+            .iter()
+            .any(|(table, field)| *table == parent && *field == key)
+    }
+
+    /// Whether a setting is a string that can't be empty
+    pub fn required_setting(parent: &str, key: &str) -> bool {
+        REQUIRED_SETTINGS
+            .iter()
+            .any(|(table, field)| *table == parent && *field == key)
+    }
+
+    /// The (min, max) of the numbers Lapce uses for a setting, if it's limited
+    pub fn setting_range(parent: &str, key: &str) -> Option<(f64, f64)> {
+        let ranges = match parent {
+            "ui" => UIConfig::RANGES,
+            "editor" => EditorConfig::RANGES,
+            "terminal" => TerminalConfig::RANGES,
+            _ => return None,
+        };
+        let field = key.replace('-', "_");
+        ranges
+            .iter()
+            .find(|(name, _, _)| *name == field)
+            .map(|(_, min, max)| (*min, *max))
+    }
+
+    /// Whether a setting is advanced, the settings panel hides it unless
+    /// advanced settings are shown
+    pub fn advanced_setting(parent: &str, key: &str) -> bool {
+        let advanced = match parent {
+            "ui" => UIConfig::ADVANCED,
+            "editor" => EditorConfig::ADVANCED,
+            _ => return false,
+        };
+        let field = key.replace('-', "_");
+        advanced.iter().any(|name| *name == field)
+    }
+
+    /// The key of the setting replacing a deprecated setting, or "" if
+    /// nothing does. Deprecated settings are still read and can be edited,
+    /// the settings panel hides them unless deprecated settings are shown.
+    pub fn deprecated_setting(parent: &str, key: &str) -> Option<&'static str> {
+        let deprecated = match parent {
+            "editor" => EditorConfig::DEPRECATED,
+            _ => return None,
+        };
+        let field = key.replace('-', "_");
+        deprecated
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, replacement)| *replacement)
+    }
+
+    /// The identifiers of the core, UI, editor and terminal settings as
+    /// `table.key`, in the order they are declared
+    pub fn setting_ids() -> Vec<String> {
+        let tables = [
+            ("lapce", &LapceConfig::FIELDS[..]),
+            ("ui", &UIConfig::FIELDS[..]),
+            ("editor", &EditorConfig::FIELDS[..]),
+            ("terminal", &TerminalConfig::FIELDS[..]),
+        ];
+        tables
+            .iter()
+            .flat_map(|(table, fields)| {
+                fields
+                    .iter()
+                    .map(move |field| format!("{table}.{}", field.replace('_', "-")))
+            })
+            .collect()
+    }
+
+    /// The unit a setting is measured in, it's only shown next to the value
+    pub fn setting_unit(parent: &str, key: &str) -> Option<&'static str> {
+        let units = match parent {
+            "ui" => UIConfig::UNITS,
+            "editor" => EditorConfig::UNITS,
+            "terminal" => TerminalConfig::UNITS,
+            _ => return None,
+        };
+        let field = key.replace('-', "_");
+        units
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, unit)| *unit)
+    }
+
+    /// An example of the string a setting expects, it's only shown below
+    /// the description
+    pub fn setting_example(parent: &str, key: &str) -> Option<&'static str> {
+        let examples = match parent {
+            "lapce" => LapceConfig::EXAMPLES,
+            "terminal" => TerminalConfig::EXAMPLES,
+            _ => return None,
+        };
+        let field = key.replace('-', "_");
+        examples
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, example)| *example)
+    }
+
+    /// Whether the path is the global settings file or a workspace one
+    pub fn is_settings_file(path: &Path) -> bool {
+        Self::settings_file().as_deref() == Some(path)
+            || path.ends_with(".lapce/settings.toml")
+    }
+
+    fn get_file_table_at(path: &Path) -> Option<toml_edit::Document> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let document: toml_edit::Document = content.parse().ok()?;
+        Some(document)
+    }
 
-        Some(())
+    pub fn reset_setting(parent: &str, key: &str) -> Option<()> {
+        Self::reset_setting_at(&Self::settings_file()?, parent, key)
+    }
+
+    /// Remove a setting from the settings file at `path`
+    pub fn reset_setting_at(path: &Path, parent: &str, key: &str) -> Option<()> {
+        Self::write_setting_at(path, parent, key, None).ok()
     }
 
     pub fn update_file(
@@ -926,28 +2663,93 @@ pub fn update_file(
         key: &str,
         value: toml_edit::Value,
     ) -> Option<()> {
-        let mut main_table = Self::get_file_table().unwrap_or_default();
+        Self::update_file_at(&Self::settings_file()?, parent, key, value)
+    }
+
+    /// Write a setting to the settings file at `path`
+    pub fn update_file_at(
+        path: &Path,
+        parent: &str,
+        key: &str,
+        value: toml_edit::Value,
+    ) -> Option<()> {
+        Self::write_setting_at(path, parent, key, Some(value)).ok()
+    }
+
+    /// Write a setting to the settings file, or remove it with `None`,
+    /// telling why when the file can't be written
+    pub fn write_setting(
+        parent: &str,
+        key: &str,
+        value: Option<toml_edit::Value>,
+    ) -> Result<(), String> {
+        let path = Self::settings_file()
+            .ok_or_else(|| "There is no settings file location".to_string())?;
+        Self::write_setting_at(&path, parent, key, value)
+    }
+
+    /// Copy the settings file to the `settings-mirror` path once a setting
+    /// is written, unless it's the settings file already, like when one is a
+    /// symlink to the other
+    pub fn mirror_settings_file(&self) -> Result<(), String> {
+        let mirror = expand_env_vars(self.lapce.settings_mirror.trim());
+        if mirror.is_empty() {
+            return Ok(());
+        }
+        let mirror = PathBuf::from(mirror);
+        let path = Self::settings_file()
+            .ok_or_else(|| "There is no settings file location".to_string())?;
+        if let (Ok(path), Ok(mirror)) =
+            (std::fs::canonicalize(&path), std::fs::canonicalize(&mirror))
+        {
+            if path == mirror {
+                return Ok(());
+            }
+        }
+        std::fs::copy(&path, &mirror)
+            .map(|_| ())
+            .map_err(|err| format!("{}: {err}", mirror.display()))
+    }
+
+    /// Write a setting to the settings file at `path`, or remove it with
+    /// `None`
+    pub fn write_setting_at(
+        path: &Path,
+        parent: &str,
+        key: &str,
+        value: Option<toml_edit::Value>,
+    ) -> Result<(), String> {
+        let mut main_table = Self::get_file_table_at(path).unwrap_or_default();
 
         // Find the container table
         let mut table = main_table.as_table_mut();
-        for key in parent.split('.') {
-            if !table.contains_key(key) {
+        for name in parent.split('.') {
+            if !table.contains_key(name) {
                 table.insert(
-                    key,
+                    name,
                     toml_edit::Item::Table(toml_edit::Table::default()),
                 );
             }
-            table = table.get_mut(key)?.as_table_mut()?;
+            table = table
+                .get_mut(name)
+                .and_then(|item| item.as_table_mut())
+                .ok_or_else(|| {
+                    format!("{parent} isn't a table in {}", path.display())
+                })?;
         }
 
-        // Update key
-        table.insert(key, toml_edit::Item::Value(value));
+        match value {
+            Some(value) => {
+                table.insert(key, toml_edit::Item::Value(value));
+            }
+            None => {
+                table.remove(key);
+            }
+        }
 
         // Store
-        let path = Self::settings_file()?;
-        std::fs::write(&path, main_table.to_string().as_bytes()).ok()?;
-
-        Some(())
+        std::fs::write(path, main_table.to_string().as_bytes())
+            .map_err(|err| format!("{}: {err}", path.display()))
     }
 
     fn update_id(&mut self) {
@@ -988,11 +2790,33 @@ pub fn get_color_unchecked(&self, name: &str) -> &Color {
             .unwrap_or_else(|| panic!("Key not found: {name}"))
     }
 
+    /// Retrieve a color value, using the `fallback` key if the theme doesn't set it
+    pub fn get_color_or(&self, name: &str, fallback: &str) -> &Color {
+        self.color
+            .ui
+            .get(name)
+            .unwrap_or_else(|| self.get_color_unchecked(fallback))
+    }
+
     /// Retrieve a color value whose key starts with "style."
     pub fn get_style_color(&self, name: &str) -> Option<&Color> {
         self.color.syntax.get(name)
     }
 
+    /// The contrast ratios of the theme color `fg` on the backgrounds it's
+    /// paired with in `LapceTheme::CONTRAST_PAIRS`
+    pub fn contrast_ratios(&self, fg: &str) -> Vec<(&'static str, f64)> {
+        LapceTheme::CONTRAST_PAIRS
+            .iter()
+            .filter(|(pair_fg, _)| *pair_fg == fg)
+            .filter_map(|(pair_fg, bg)| {
+                let fg = self.color.ui.get(*pair_fg)?;
+                let bg = self.color.ui.get(*bg)?;
+                Some((*bg, contrast_ratio(fg, bg)))
+            })
+            .collect()
+    }
+
     /// Calculate the width of the character "W" (being the widest character)
     /// in the editor's current font family at the specified font size.
     pub fn char_width(&self, text: &mut PietText, font_size: f64) -> f64 {
@@ -1219,3 +3043,362 @@ pub fn tab_width(
         self.editor.tab_width as f64 * width
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_values_of() {
+        let values = Config::settings_values_of(
+            "[editor]\nfont-size = 13\ntab_width = 2\n\n\
+             [color-theme.ui]\n\"editor.background\" = \"#000000\"\n",
+        )
+        .unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.get("editor.font-size"), Some(&serde_json::json!(13)));
+        assert_eq!(values.get("editor.tab-width"), Some(&serde_json::json!(2)));
+        assert_eq!(
+            values.get("color-theme.ui.editor.background"),
+            Some(&serde_json::json!("#000000"))
+        );
+        assert!(Config::settings_values_of("[editor").is_none());
+    }
+
+    #[test]
+    fn test_theme_colors_set_to() {
+        let mut config = Config::default();
+        config.theme.base.red = "#E06C75".to_string();
+        config
+            .theme
+            .ui
+            .insert("error".to_string(), "#e06c75ff".to_string());
+        config
+            .theme
+            .syntax
+            .insert("keyword".to_string(), "$red".to_string());
+        assert_eq!(
+            config.theme_colors_set_to("#E06C75FF"),
+            vec![
+                ("theme.base".to_string(), "red".to_string()),
+                ("theme.ui".to_string(), "error".to_string()),
+            ]
+        );
+        assert!(config.theme_colors_set_to("$red").is_empty());
+    }
+
+    #[test]
+    fn test_theme_color_index() {
+        let mut config = Config::default();
+        config.theme.base.red = "#E06C75".to_string();
+        for key in ["panel.background", "editor.background"] {
+            config
+                .theme
+                .ui
+                .insert(key.to_string(), "$black".to_string());
+        }
+        config
+            .theme
+            .syntax
+            .insert("keyword".to_string(), "$red".to_string());
+        let index: Vec<(String, String)> = config
+            .theme_color_index()
+            .into_iter()
+            .map(|(kind, key, _)| (kind, key))
+            .collect();
+        assert_eq!(index.len(), 14);
+        assert_eq!(index[0], ("theme.base".to_string(), "black".to_string()));
+        assert_eq!(
+            &index[11..],
+            &[
+                ("theme.syntax".to_string(), "keyword".to_string()),
+                ("theme.ui".to_string(), "editor.background".to_string()),
+                ("theme.ui".to_string(), "panel.background".to_string()),
+            ]
+        );
+        assert!(config.theme_color_index().contains(&(
+            "theme.base".to_string(),
+            "red".to_string(),
+            "#E06C75".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_eval_color() {
+        let base = |name: &str| (name == "blue").then_some(Color::rgb8(0, 0, 200));
+        // Mixing rounds to whole color components either way
+        let rgba8 = |value: &str| eval_color(value, &base).unwrap().as_rgba8();
+        let close = |a: (u8, u8, u8, u8), b: (u8, u8, u8, u8)| {
+            [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)]
+                .iter()
+                .all(|(a, b)| a.abs_diff(*b) <= 1)
+        };
+        assert!(close(rgba8("lighten($blue, 50%)"), (127, 127, 227, 255)));
+        assert!(close(
+            rgba8("darken(lighten(base.blue, 0%), 50%)"),
+            (0, 0, 100, 255)
+        ));
+        assert_eq!(rgba8("darken(#FFFFFF, 100%)"), (0, 0, 0, 255));
+        assert_eq!(rgba8(" lighten( #000000 , 100% )"), (255, 255, 255, 255));
+        assert!(eval_color("lighten($red, 10%)", &base).is_err());
+        assert!(eval_color("lighten($blue, 110%)", &base).is_err());
+        assert!(eval_color("saturate($blue, 10%)", &base).is_err());
+        assert!(eval_color("lighten($blue)", &base).is_err());
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let vars = |name: &str| (name == "HOME").then(|| "/home/me".to_string());
+        assert_eq!(expand_vars("$HOME/bin/fish", &vars), "/home/me/bin/fish");
+        assert_eq!(expand_vars("${HOME}_fish -l", &vars), "/home/me_fish -l");
+        assert_eq!(expand_vars("$HOMES ${NOPE} $", &vars), "$HOMES ${NOPE} $");
+        assert_eq!(expand_vars("$$HOME ${HOME", &vars), "$HOME ${HOME");
+    }
+
+    #[test]
+    fn test_referenced_base_colors() {
+        assert_eq!(referenced_base_colors("$blue"), vec!["blue"]);
+        assert_eq!(
+            referenced_base_colors("darken(lighten(base.blue, 5%), 10%)"),
+            vec!["blue"]
+        );
+        assert_eq!(
+            referenced_base_colors("lighten($dark-grey,10%) $red"),
+            vec!["dark-grey", "red"]
+        );
+        assert!(referenced_base_colors("#282C34").is_empty());
+        assert!(referenced_base_colors("database.blue").is_empty());
+    }
+
+    #[test]
+    fn test_changed_defaults_between() {
+        let previous = "[editor]\nfont-size = 13\ntab-width = 4\n\n\
+                        [ui]\nfont-family = \"\"\n";
+        let current = "[editor]\nfont-size = 14\ntab-width = 4\n\
+                       line-height = 1.5\n\n[ui]\nfont-family = \"\"\n";
+        assert_eq!(
+            Config::changed_defaults_between(previous, current),
+            vec![SettingChange {
+                table: "editor".to_string(),
+                key: "font-size".to_string(),
+                old: Some(serde_json::json!(13)),
+                new: serde_json::json!(14),
+                unset: false,
+            }]
+        );
+        assert!(Config::changed_defaults_between(current, current).is_empty());
+    }
+
+    #[test]
+    fn test_backup_changes() {
+        let dir = std::env::temp_dir()
+            .join(format!("lapce-backup-changes-{}", std::process::id()));
+        let backup = dir.join("backup");
+        std::fs::create_dir_all(&backup).unwrap();
+        std::fs::write(
+            backup.join("settings.toml"),
+            "[editor]\nfont-size = 16\ntab-width = 4\n",
+        )
+        .unwrap();
+        let settings_file = dir.join("settings.toml");
+        std::fs::write(&settings_file, "[ui]\nfont-size = 15\n").unwrap();
+        let mut config = Config::default();
+        config.editor.font_size = 13;
+        config.editor.tab_width = 4;
+        config.ui.font_size = 15;
+
+        let changes = config.backup_changes(&backup, &settings_file).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&SettingChange {
+            table: "editor".to_string(),
+            key: "font-size".to_string(),
+            old: Some(serde_json::json!(13)),
+            new: serde_json::json!(16),
+            unset: false,
+        }));
+        // Only the settings file sets it, so it follows the default again
+        assert!(changes.contains(&SettingChange {
+            table: "ui".to_string(),
+            key: "font-size".to_string(),
+            old: Some(serde_json::json!(15)),
+            new: Config::default_setting_value("ui", "font-size").unwrap(),
+            unset: true,
+        }));
+        // A setting at the backed up value isn't changed
+        assert!(!changes
+            .iter()
+            .any(|change| change.table == "editor" && change.key == "tab-width"));
+        assert!(config
+            .backup_changes(&dir.join("missing"), &settings_file)
+            .is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_section_json() {
+        let mut config: Config =
+            toml::from_str(Config::default_settings_text()).unwrap();
+        config.editor.tab_width = 7;
+        let json: serde_json::Value =
+            serde_json::from_str(&config.section_json("editor", true).unwrap())
+                .unwrap();
+        assert_eq!(json["editor"]["tab-width"], 7);
+        assert!(json["editor"].get("font-size").is_none());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&config.section_json("editor", false).unwrap())
+                .unwrap();
+        assert!(json["editor"].get("font-size").is_some());
+        assert!(config.section_json("recent", false).is_none());
+    }
+
+    #[test]
+    fn test_compare_with_defaults() {
+        let mut config: Config =
+            toml::from_str(Config::default_settings_text()).unwrap();
+        config.editor.font_family = "C:\\Fonts \"Mono\"".to_string();
+        config.ui.settings_sections = vec!["core".to_string()];
+        let text = config.compare_with_defaults(true);
+        let values: serde_json::Value = toml::from_str(&text).unwrap();
+        assert_eq!(
+            values["editor"]["font-family"],
+            serde_json::json!("C:\\Fonts \"Mono\"")
+        );
+        assert_eq!(
+            values["ui"]["settings-sections"],
+            serde_json::json!(["core"])
+        );
+        assert!(text.contains("# changed, default: \"Cascadia Code\"\n"));
+        assert!(values["editor"].get("tab-width").is_none());
+    }
+
+    #[test]
+    fn test_setting_example() {
+        assert_eq!(
+            Config::setting_example("terminal", "shell"),
+            Some("$SHELL -l")
+        );
+        assert!(Config::setting_example("lapce", "settings-mirror").is_some());
+        assert_eq!(Config::setting_example("lapce", "modal"), None);
+        assert_eq!(Config::setting_example("editor", "font-family"), None);
+        for (field, _) in LapceConfig::EXAMPLES {
+            assert!(LapceConfig::FIELDS.contains(field));
+        }
+        for (field, _) in TerminalConfig::EXAMPLES {
+            assert!(TerminalConfig::FIELDS.contains(field));
+        }
+    }
+
+    #[test]
+    fn test_settings_docs_markdown() {
+        let docs = Config::settings_docs_markdown();
+        assert!(docs.contains("\n## Editor Settings\n"));
+        assert!(docs.contains("| `font-size` | integer, px | `13` |"));
+        assert!(docs
+            .lines()
+            .all(|line| !line.starts_with("| `") || line.ends_with(" |")));
+    }
+
+    #[test]
+    fn test_deprecated_setting() {
+        assert_eq!(
+            Config::deprecated_setting("editor", "show-bread-crumbs"),
+            Some("editor.show-breadcrumbs")
+        );
+        assert_eq!(
+            Config::deprecated_setting("editor", "show-breadcrumbs"),
+            None
+        );
+        assert_eq!(Config::deprecated_setting("ui", "font-size"), None);
+        // The replacements are settings the panel can reveal
+        for (_, replacement) in EditorConfig::DEPRECATED {
+            assert!(
+                replacement.is_empty()
+                    || Config::setting_ids().contains(&replacement.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_show_breadcrumbs() {
+        assert!(Config::nullable_bool_setting("editor", "show-breadcrumbs"));
+        assert!(!Config::nullable_bool_setting(
+            "editor",
+            "show-bread-crumbs"
+        ));
+
+        let mut config = Config::default();
+        config.editor.show_bread_crumbs = true;
+        assert!(config.editor.show_breadcrumbs());
+        assert_eq!(
+            config.setting_value("editor", "show-breadcrumbs"),
+            Some(serde_json::Value::Null)
+        );
+        config.editor.show_breadcrumbs = Some(false);
+        assert!(!config.editor.show_breadcrumbs());
+        assert_eq!(
+            config.setting_value("editor", "show-breadcrumbs"),
+            Some(serde_json::json!(false))
+        );
+    }
+
+    #[test]
+    fn test_setting_range() {
+        assert_eq!(Config::setting_range("ui", "font-size"), Some((6.0, 32.0)));
+        assert_eq!(Config::setting_range("editor", "font-family"), None);
+        assert_eq!(Config::setting_range("lapce", "modal"), None);
+        assert_eq!(
+            Config::setting_range_error("ui", "font-size", 40.0),
+            Some("Lapce uses 32 or less".to_string())
+        );
+        assert_eq!(
+            Config::setting_range_error("editor", "tab-width", 0.0),
+            Some("Lapce uses 1 or more".to_string())
+        );
+        assert_eq!(Config::setting_range_error("ui", "font-size", 14.0), None);
+    }
+
+    #[test]
+    fn test_validate_setting() {
+        let mut config = Config::default();
+        config.editor.font_size = 13;
+        assert_eq!(config.validate("editor", "font-size"), Ok(()));
+        config.editor.font_size = 0;
+        assert!(matches!(
+            config.validate("editor", "font-size"),
+            Err(ValidationError::InvalidValue { .. })
+        ));
+        config.editor.render_whitespace = "x".to_string();
+        assert!(matches!(
+            config.validate("editor", "render-whitespace"),
+            Err(ValidationError::InvalidValue { .. })
+        ));
+        config.lapce.color_theme = "No Such Theme".to_string();
+        assert!(matches!(
+            config.validate("lapce", "color-theme"),
+            Err(ValidationError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            config.validate("editor", "no-such-setting"),
+            Err(ValidationError::UnknownSetting {
+                kind: "editor".to_string(),
+                name: "no-such-setting".to_string(),
+            })
+        );
+
+        config
+            .theme
+            .ui
+            .insert("background".to_string(), "#12345G".to_string());
+        assert!(matches!(
+            config.validate("theme.ui", "background"),
+            Err(ValidationError::InvalidValue { .. })
+        ));
+        config
+            .theme
+            .ui
+            .insert("background".to_string(), "$black".to_string());
+        assert_eq!(config.validate("theme.ui", "background"), Ok(()));
+    }
+}
@@ -34,7 +34,7 @@
 use lapce_rpc::{
     buffer::BufferId,
     core::{CoreNotification, CoreRequest, CoreResponse},
-    plugin::VoltInfo,
+    plugin::{SettingsChange, VoltInfo},
     proxy::ProxyResponse,
     source_control::FileDiff,
     terminal::TermId,
@@ -52,8 +52,9 @@
     alert::{AlertContentData, AlertData},
     command::{
         CommandKind, EnsureVisiblePosition, InitBufferContentCb, LapceCommand,
-        LapceUICommand, LapceWorkbenchCommand, LAPCE_COMMAND, LAPCE_OPEN_FILE,
-        LAPCE_OPEN_FOLDER, LAPCE_UI_COMMAND,
+        LapceUICommand, LapceWorkbenchCommand, LAPCE_COMMAND, LAPCE_IMPORT_THEME,
+        LAPCE_IMPORT_VSCODE_SETTINGS, LAPCE_OPEN_FILE, LAPCE_OPEN_FOLDER,
+        LAPCE_UI_COMMAND,
     },
     completion::CompletionData,
     config::{Config, ConfigWatcher, GetConfig, LapceTheme},
@@ -67,7 +68,7 @@
     find::Find,
     hover::HoverData,
     keypress::KeyPressData,
-    palette::{PaletteData, PaletteType, PaletteViewData},
+    palette::{PaletteData, PaletteType, PaletteViewData, ThemeFileAction},
     panel::{
         PanelContainerPosition, PanelData, PanelKind, PanelOrder, PanelPosition,
     },
@@ -77,7 +78,14 @@
     proxy::{LapceProxy, ProxyStatus, TermEvent},
     rename::RenameData,
     search::SearchData,
-    settings::LapceSettingsPanelData,
+    settings::{
+        backup_restore_preview, parse_color_replacement, parse_setting_input,
+        parse_settings_value, settings_key_at_line, settings_key_line,
+        settings_revert_summary, settings_value_text, stores_settled_input,
+        theme_file_changes, theme_json_changes, LapceSettingsPanelData,
+        SettingChange, SettingsPanelPreferences, SettingsValueKind, ThemeImport,
+        VSCodeSettingsImport, THEME_MAPS,
+    },
     source_control::SourceControlData,
     split::{SplitDirection, SplitMoveDirection},
     terminal::TerminalSplitData,
@@ -113,6 +121,7 @@ pub fn load(event_sink: ExtEventSink, paths: Vec<PathBuf>) -> Self {
         let db = Arc::new(LapceDb::new().unwrap());
         let mut windows = im::HashMap::new();
         let config = Config::load(&LapceWorkspace::default()).unwrap_or_default();
+        Config::snapshot_default_settings();
         let keypress = Arc::new(KeyPressData::new(&config, event_sink.clone()));
         let panel_orders = db
             .get_panel_orders()
@@ -670,7 +679,12 @@ pub fn new(
         let hover = Arc::new(HoverData::new());
         let rename = Arc::new(RenameData::new());
         let source_control = Arc::new(SourceControlData::new());
-        let settings = Arc::new(LapceSettingsPanelData::new());
+        let settings = Arc::new(LapceSettingsPanelData::new(
+            db.get_settings_preferences().unwrap_or_default(),
+            db.get_recent_settings().unwrap_or_default(),
+            db.get_settings_change_times().unwrap_or_default(),
+            db.get_settings_search_history().unwrap_or_default(),
+        ));
         let about = Arc::new(AboutData::new());
         let alert = Arc::new(AlertData::new());
         let plugin = Arc::new(PluginData::new(
@@ -1126,6 +1140,993 @@ pub fn palette_view_data(&self) -> PaletteViewData {
         }
     }
 
+    /// Reveal the setting after (or before) the current one whose input has a
+    /// validation error, wrapping around at the ends
+    fn show_invalid_setting(&mut self, ctx: &mut EventCtx, forward: bool) {
+        // The order of the sections in the settings panel, themes come last
+        let rank = |parent: &str| {
+            ["lapce", "ui", "editor", "terminal"]
+                .iter()
+                .position(|table| *table == parent)
+                .unwrap_or(4)
+        };
+        let mut invalid: Vec<(usize, String, String)> = self
+            .main_split
+            .value_docs
+            .values()
+            .filter_map(|doc| match doc.content() {
+                BufferContent::SettingsValue(name, _, parent, key)
+                    if self.settings.value_errors.contains_key(name) =>
+                {
+                    Some((rank(parent), parent.clone(), key.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        invalid.sort();
+        invalid.dedup();
+
+        let current = self
+            .settings
+            .current_setting
+            .as_ref()
+            .map(|(_, table, key)| (rank(table), table.clone(), key.clone()));
+        let target = match current {
+            Some(current) if forward => {
+                invalid.iter().find(|setting| **setting > current)
+            }
+            Some(current) => {
+                invalid.iter().rev().find(|setting| **setting < current)
+            }
+            None => None,
+        }
+        .or(if forward {
+            invalid.first()
+        } else {
+            invalid.last()
+        });
+
+        if let Some((_, table, key)) = target.cloned() {
+            self.reveal_setting(ctx, table, key);
+        }
+    }
+
+    /// Open the settings panel at a setting, showing the advanced settings
+    /// first if it's one of them
+    fn reveal_setting(&mut self, ctx: &mut EventCtx, table: String, key: String) {
+        if Config::advanced_setting(&table, &key)
+            && !self.settings.preferences.show_advanced
+        {
+            let settings = Arc::make_mut(&mut self.settings);
+            settings.preferences.show_advanced = true;
+            let _ = self.db.save_settings_preferences(&settings.preferences);
+        }
+        if Config::deprecated_setting(&table, &key).is_some()
+            && !self.settings.preferences.show_deprecated
+        {
+            let settings = Arc::make_mut(&mut self.settings);
+            settings.preferences.show_deprecated = true;
+            let _ = self.db.save_settings_preferences(&settings.preferences);
+        }
+        let widget_id = self.main_split.open_settings(ctx, false, &self.config);
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowSettingsField(table, key),
+            Target::Widget(widget_id),
+        ));
+    }
+
+    /// Tell the plugins about a setting written to the settings file, copy
+    /// the file to the mirror, record the change and offer reloading the open
+    /// files if they only follow it once reopened, `new` being what was
+    /// written or `None` when the setting was removed
+    pub fn settings_file_written(
+        &mut self,
+        parent: &str,
+        key: &str,
+        new: Option<serde_json::Value>,
+    ) {
+        self.send_settings_changed(parent, key, new);
+        let mirror_error = self.config.mirror_settings_file().err();
+        self.record_settings_file_write(parent, key, mirror_error);
+        self.save_settings_changes();
+    }
+
+    /// Tell the plugins of the workspace about a setting written to the
+    /// settings file, `new` being `None` when the setting was removed
+    pub fn send_settings_changed(
+        &self,
+        parent: &str,
+        key: &str,
+        new: Option<serde_json::Value>,
+    ) {
+        self.proxy.proxy_rpc.settings_changed(SettingsChange {
+            kind: parent.to_string(),
+            name: key.to_string(),
+            old: self.config.setting_value(parent, key),
+            new,
+        });
+    }
+
+    /// Update the settings panel after a setting was written to the settings
+    /// file, without touching any file: the write and mirror errors, the
+    /// reload offer and the recent changes
+    pub fn record_settings_file_write(
+        &mut self,
+        parent: &str,
+        key: &str,
+        mirror_error: Option<String>,
+    ) {
+        let settings = Arc::make_mut(&mut self.settings);
+        settings.write_error = None;
+        settings.mirror_error = mirror_error;
+        if Config::requires_reload(parent, key) {
+            settings.reload_pending = Some(format!("{parent}.{key}"));
+        }
+        settings.record_change(parent, key);
+    }
+
+    /// Save the recent changes and the change times of the settings
+    pub fn save_settings_changes(&self) {
+        let _ = self.db.save_recent_settings(&self.settings.recent_changes);
+        let _ = self
+            .db
+            .save_settings_change_times(&self.settings.change_times);
+    }
+
+    /// Store the settings edited in the settings panel right away, instead of
+    /// waiting for the edits to settle
+    fn flush_settings(&mut self, ctx: &mut EventCtx) {
+        let settings = Arc::make_mut(&mut self.settings);
+        settings.flush_id += 1;
+        let pending = std::mem::take(&mut settings.pending_changes);
+        let mut changes: Vec<(String, String, serde_json::Value)> =
+            pending.into_iter().map(|(_, change)| change).collect();
+        let confirm_theme = self.config.ui.confirm_theme_changes();
+
+        for doc in self.main_split.value_docs.values() {
+            if let BufferContent::SettingsValue(name, kind, parent, key) =
+                doc.content()
+            {
+                if !stores_settled_input(kind, parent, key) {
+                    continue;
+                }
+                let content = doc.buffer().to_string();
+                match parse_setting_input(&self.config, kind, parent, key, &content)
+                {
+                    Ok(value) if confirm_theme && parent.starts_with("theme.") => {
+                        Arc::make_mut(&mut self.settings).buffer_theme_change(
+                            name,
+                            (parent.clone(), key.clone(), value),
+                            self.config.setting_value(parent, key).as_ref(),
+                        );
+                    }
+                    Ok(value) => {
+                        if self.config.setting_value(parent, key).as_ref()
+                            != Some(&value)
+                        {
+                            changes.push((parent.clone(), key.clone(), value));
+                        }
+                    }
+                    Err(err) => {
+                        Arc::make_mut(&mut self.settings)
+                            .value_errors
+                            .insert(name.clone(), err);
+                    }
+                }
+            }
+        }
+
+        for (parent, key, value) in changes {
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::UpdateSettingsFile(parent, key, value),
+                Target::Widget(self.id),
+            ));
+        }
+    }
+
+    /// Ask whether to install the theme file at `path` or to merge some of
+    /// its colors into the current theme
+    pub fn import_theme_file(&mut self, ctx: &mut EventCtx, path: &Path) {
+        let content = match Config::theme_file_name(path) {
+            Ok(name) => {
+                let choices = [
+                    ("Install the theme", None),
+                    ("Merge all its colors", Some(&THEME_MAPS[..])),
+                    ("Merge its base colors", Some(&["base"][..])),
+                    ("Merge its UI colors", Some(&["ui"][..])),
+                    ("Merge its syntax colors", Some(&["syntax"][..])),
+                ];
+                AlertContentData {
+                    title: format!("How do you want to import {name}?"),
+                    msg: "Installing adds it to the themes and switches to it. \
+                          Merging applies its colors to the current theme, \
+                          the other colors stay as they are."
+                        .to_string(),
+                    buttons: choices
+                        .into_iter()
+                        .map(|(text, maps)| {
+                            let import = ThemeImport {
+                                path: path.to_path_buf(),
+                                maps: maps.map(|maps| {
+                                    maps.iter().map(|m| m.to_string()).collect()
+                                }),
+                            };
+                            (
+                                text.to_string(),
+                                self.id,
+                                LapceCommand {
+                                    kind: CommandKind::Workbench(
+                                        LapceWorkbenchCommand::ApplyThemeImport,
+                                    ),
+                                    data: serde_json::to_value(&import).ok(),
+                                },
+                            )
+                        })
+                        .collect(),
+                }
+            }
+            Err(msg) => AlertContentData {
+                title: "The theme couldn't be imported".to_string(),
+                msg,
+                buttons: Vec::new(),
+            },
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Merge the colors of the `maps` of the theme file at `path` into the
+    /// current theme
+    fn merge_theme_file(
+        &mut self,
+        ctx: &mut EventCtx,
+        path: &Path,
+        maps: &[String],
+    ) {
+        let maps: Vec<&str> = maps.iter().map(|m| m.as_str()).collect();
+        match theme_file_changes(path, &maps, &self.config) {
+            Ok(changes) => {
+                self.apply_theme_colors(ctx, changes, "Merging the theme colors")
+            }
+            Err(msg) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ShowAlert(AlertContentData {
+                        title: "The theme couldn't be imported".to_string(),
+                        msg,
+                        buttons: Vec::new(),
+                    }),
+                    Target::Widget(self.id),
+                ));
+            }
+        }
+    }
+
+    /// Copy the theme file at `path` into the themes folder and switch to it
+    fn install_theme_file(&mut self, ctx: &mut EventCtx, path: &Path) {
+        let import = Config::theme_file_name(path).and_then(|name| {
+            let folder = Directory::themes_directory()
+                .ok_or_else(|| "There is no themes folder".to_string())?;
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| format!("{} is not a file", path.display()))?;
+            let dest = folder.join(file_name);
+            if dest != path {
+                std::fs::copy(path, &dest).map_err(|e| {
+                    format!(
+                        "Can't copy {} to {}: {e}",
+                        path.display(),
+                        dest.display()
+                    )
+                })?;
+            }
+            Ok(name)
+        });
+        match import {
+            Ok(name) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::SetTheme(name, false),
+                    Target::Auto,
+                ));
+            }
+            Err(msg) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ShowAlert(AlertContentData {
+                        title: "The theme couldn't be imported".to_string(),
+                        msg,
+                        buttons: Vec::new(),
+                    }),
+                    Target::Widget(self.id),
+                ));
+            }
+        }
+    }
+
+    /// Preview the settings imported from a VS Code settings.json, and ask
+    /// whether to apply them
+    pub fn import_vscode_settings(&mut self, ctx: &mut EventCtx, path: &Path) {
+        let import = std::fs::read_to_string(path)
+            .map_err(|e| format!("Can't read {}: {e}", path.display()))
+            .and_then(|text| VSCodeSettingsImport::parse(&text, &self.config));
+        let content = match import {
+            Ok(import) if import.changes.is_empty() => AlertContentData {
+                title: "There are no VS Code settings to import".to_string(),
+                msg: format!(
+                    "{} settings have no equivalent in Lapce.",
+                    import.unmapped.len()
+                ),
+                buttons: Vec::new(),
+            },
+            Ok(import) => {
+                self.main_split.open_read_only_settings(
+                    ctx,
+                    "VS Code Settings Import (read-only)".to_string(),
+                    &import.preview(),
+                    &self.config,
+                );
+                AlertContentData {
+                    title: format!(
+                        "Do you want to import {} settings from VS Code?",
+                        import.changes.len()
+                    ),
+                    msg: format!(
+                        "The preview lists the changes. {} settings have no \
+                         equivalent in Lapce and are skipped.",
+                        import.unmapped.len()
+                    ),
+                    buttons: vec![(
+                        "Import".to_string(),
+                        self.id,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::ApplyVSCodeSettingsImport,
+                            ),
+                            data: serde_json::to_value(&import.changes).ok(),
+                        },
+                    )],
+                }
+            }
+            Err(err) => AlertContentData {
+                title: "Can't import the VS Code settings".to_string(),
+                msg: err,
+                buttons: Vec::new(),
+            },
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    fn apply_settings_changes(
+        &mut self,
+        ctx: &mut EventCtx,
+        changes: Vec<SettingChange>,
+    ) {
+        if !changes.is_empty() {
+            Arc::make_mut(&mut self.settings)
+                .start_reloading("Storing the settings");
+        }
+        for change in changes {
+            let command = if change.unset {
+                LapceUICommand::ResetSettingsFile(change.table, change.key)
+            } else {
+                LapceUICommand::UpdateSettingsFile(
+                    change.table,
+                    change.key,
+                    change.new,
+                )
+            };
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                command,
+                Target::Widget(self.id),
+            ));
+        }
+    }
+
+    /// Ask whether to set the settings changed since the last import back to
+    /// the imported values
+    fn reset_to_import_baseline(&mut self, ctx: &mut EventCtx) {
+        let baseline = self.db.get_import_baseline().unwrap_or_default();
+        let changes: Vec<SettingChange> = baseline
+            .iter()
+            .filter_map(|change| {
+                let old = self.config.setting_value(&change.table, &change.key);
+                if old.as_ref() == Some(&change.new) {
+                    None
+                } else {
+                    Some(SettingChange {
+                        old,
+                        ..change.clone()
+                    })
+                }
+            })
+            .collect();
+        let content = if baseline.is_empty() {
+            AlertContentData {
+                title: "There are no imported settings".to_string(),
+                msg: "Import the VS Code settings first.".to_string(),
+                buttons: Vec::new(),
+            }
+        } else if changes.is_empty() {
+            AlertContentData {
+                title: "The settings are as they were imported".to_string(),
+                msg: format!(
+                    "None of the {} imported settings changed since.",
+                    baseline.len()
+                ),
+                buttons: Vec::new(),
+            }
+        } else {
+            AlertContentData {
+                title: format!(
+                    "Do you want to reset {} settings to the imported values?",
+                    changes.len()
+                ),
+                msg: format!(
+                    "{} changed since the last import.",
+                    changes
+                        .iter()
+                        .map(|change| format!("{}.{}", change.table, change.key))
+                        .join(", ")
+                ),
+                buttons: vec![(
+                    "Reset".to_string(),
+                    self.id,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::ApplyImportBaselineReset,
+                        ),
+                        data: serde_json::to_value(&changes).ok(),
+                    },
+                )],
+            }
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Ask for the new name of the current theme in the palette, if it's
+    /// a theme in the themes folder
+    fn ask_theme_file_name(&mut self, ctx: &mut EventCtx, action: ThemeFileAction) {
+        if Config::user_theme_file(&self.config.lapce.color_theme).is_some() {
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::RunPalette(Some(PaletteType::ThemeName(action))),
+                Target::Widget(self.palette.widget_id),
+            ));
+        } else {
+            self.show_theme_file_error(
+                ctx,
+                action,
+                format!(
+                    "\"{}\" isn't a theme in the themes folder, it's built in or \
+                     comes from a plugin.",
+                    self.config.lapce.color_theme
+                ),
+            );
+        }
+    }
+
+    /// Rename the file of the current theme or copy it for the theme `name`,
+    /// and switch to that theme
+    fn save_theme_file_as(
+        &mut self,
+        ctx: &mut EventCtx,
+        name: &str,
+        action: ThemeFileAction,
+    ) {
+        let saved = Config::user_theme_file(&self.config.lapce.color_theme)
+            .ok_or_else(|| {
+                format!(
+                    "There is no file for \"{}\" in the themes folder",
+                    self.config.lapce.color_theme
+                )
+            })
+            .and_then(|path| {
+                self.config.save_theme_file_as(
+                    &path,
+                    name,
+                    action == ThemeFileAction::Rename,
+                )
+            });
+        match saved {
+            // The renamed theme is gone, so it isn't the previous theme
+            Ok(_) if action == ThemeFileAction::Rename => {
+                let name = name.trim();
+                match Config::write_setting(
+                    "lapce",
+                    "color-theme",
+                    Some(toml_edit::Value::from(name)),
+                ) {
+                    Ok(()) => {
+                        self.settings_file_written(
+                            "lapce",
+                            "color-theme",
+                            Some(serde_json::json!(name)),
+                        );
+                        Arc::make_mut(&mut self.settings)
+                            .start_reloading("Renaming the theme");
+                    }
+                    Err(err) => {
+                        Arc::make_mut(&mut self.settings).write_error = Some((
+                            "lapce".to_string(),
+                            "color-theme".to_string(),
+                            err.clone(),
+                        ));
+                        self.show_theme_file_error(
+                            ctx,
+                            action,
+                            format!(
+                                "The theme file is renamed to \"{name}\", but the \
+                                 settings can't be switched to it: {err}"
+                            ),
+                        );
+                    }
+                }
+            }
+            Ok(_) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::SetTheme(name.trim().to_string(), false),
+                    Target::Auto,
+                ));
+            }
+            Err(msg) => self.show_theme_file_error(ctx, action, msg),
+        }
+    }
+
+    fn show_theme_file_error(
+        &self,
+        ctx: &mut EventCtx,
+        action: ThemeFileAction,
+        msg: String,
+    ) {
+        let title = match action {
+            ThemeFileAction::Rename => "The theme couldn't be renamed",
+            ThemeFileAction::Duplicate => "The theme couldn't be duplicated",
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(AlertContentData {
+                title: title.to_string(),
+                msg,
+                buttons: Vec::new(),
+            }),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// List the theme colors set to the color to replace, typed with its
+    /// replacement as `#E06C75 #FF0000`, asking to replace them
+    fn confirm_replace_theme_color(&mut self, ctx: &mut EventCtx, input: &str) {
+        let content = match parse_color_replacement(input) {
+            Ok((old, new)) => {
+                let keys = self.config.theme_colors_set_to(&old);
+                if keys.is_empty() {
+                    AlertContentData {
+                        title: format!("No theme color is {old}"),
+                        msg: "There is nothing to replace.".to_string(),
+                        buttons: Vec::new(),
+                    }
+                } else {
+                    const LISTED: usize = 20;
+                    let mut msg = keys
+                        .iter()
+                        .take(LISTED)
+                        .map(|(kind, key)| format!("{kind}.{key}"))
+                        .join("\n");
+                    if keys.len() > LISTED {
+                        msg.push_str(&format!("\nand {} more", keys.len() - LISTED));
+                    }
+                    AlertContentData {
+                        title: format!(
+                            "Do you want to replace {old} with {new} in {} colors?",
+                            keys.len()
+                        ),
+                        msg,
+                        buttons: vec![(
+                            "Replace".to_string(),
+                            self.id,
+                            LapceCommand {
+                                kind: CommandKind::Workbench(
+                                    LapceWorkbenchCommand::ApplyReplaceThemeColor,
+                                ),
+                                data: Some(serde_json::json!((old, new))),
+                            },
+                        )],
+                    }
+                }
+            }
+            Err(msg) => AlertContentData {
+                title: "The theme color couldn't be replaced".to_string(),
+                msg,
+                buttons: Vec::new(),
+            },
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Set every theme color that's `old` to `new`, like it's typed into
+    /// the color inputs
+    fn replace_theme_color(&mut self, ctx: &mut EventCtx, old: &str, new: &str) {
+        let changes = self
+            .config
+            .theme_colors_set_to(old)
+            .into_iter()
+            .map(|(kind, key)| (kind, key, new.to_string()))
+            .collect();
+        self.apply_theme_colors(ctx, changes, "Replacing a theme color");
+    }
+
+    /// Ask whether to reset every setting in the settings file to its default
+    fn confirm_reset_all_settings(&mut self, ctx: &mut EventCtx) {
+        let keys = Config::settings_file_keys();
+        let content = if keys.is_empty() {
+            AlertContentData {
+                title: "All settings are at their defaults".to_string(),
+                msg: "The settings file doesn't set anything.".to_string(),
+                buttons: Vec::new(),
+            }
+        } else {
+            AlertContentData {
+                title: format!(
+                    "Do you want to reset {} settings to their defaults?",
+                    keys.len()
+                ),
+                msg: "The settings file and the installed themes are backed up \
+                      first."
+                    .to_string(),
+                buttons: vec![(
+                    "Reset All".to_string(),
+                    self.id,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::ApplyResetAllSettings,
+                        ),
+                        data: None,
+                    },
+                )],
+            }
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    fn confirm_revert_settings_session(&mut self, ctx: &mut EventCtx) {
+        let reverts = self.settings.session_reverts();
+        let content = if reverts.is_empty() {
+            AlertContentData {
+                title: "No settings changed since the settings opened".to_string(),
+                msg: "The settings file sets what it did then.".to_string(),
+                buttons: Vec::new(),
+            }
+        } else {
+            AlertContentData {
+                title: format!(
+                    "Do you want to revert {} settings changed since the \
+                     settings opened?",
+                    reverts.len()
+                ),
+                msg: settings_revert_summary(&reverts),
+                buttons: vec![(
+                    "Revert".to_string(),
+                    self.id,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::ApplyRevertSettingsSession,
+                        ),
+                        data: None,
+                    },
+                )],
+            }
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Write back the values the settings changed since the panel opened had
+    /// before, telling which ones were reverted
+    fn revert_settings_session(&mut self, ctx: &mut EventCtx) {
+        let reverts = self.settings.session_reverts();
+        if reverts.is_empty() {
+            return;
+        }
+        Arc::make_mut(&mut self.settings).start_reloading("Reverting the settings");
+        for (kind, name, value) in reverts.iter() {
+            let command = match value {
+                Some(value) => LapceUICommand::UpdateSettingsFile(
+                    kind.to_string(),
+                    name.to_string(),
+                    value.clone(),
+                ),
+                None => LapceUICommand::ResetSettingsFile(
+                    kind.to_string(),
+                    name.to_string(),
+                ),
+            };
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                command,
+                Target::Widget(self.id),
+            ));
+        }
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(AlertContentData {
+                title: format!("Reverted {} settings", reverts.len()),
+                msg: settings_revert_summary(&reverts),
+                buttons: Vec::new(),
+            }),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Back up the settings and themes, then reset every setting the settings
+    /// file sets, telling where the backup went
+    fn reset_all_settings(&mut self, ctx: &mut EventCtx) {
+        let content = match Config::backup_settings() {
+            Ok(dir) => {
+                let keys = Config::settings_file_keys();
+                if !keys.is_empty() {
+                    Arc::make_mut(&mut self.settings)
+                        .start_reloading("Resetting the settings");
+                }
+                for (parent, key) in keys.iter() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ResetSettingsFile(
+                            parent.to_string(),
+                            key.to_string(),
+                        ),
+                        Target::Widget(self.id),
+                    ));
+                }
+                AlertContentData {
+                    title: format!(
+                        "Reset {} settings to their defaults",
+                        keys.len()
+                    ),
+                    msg: format!(
+                        "The previous settings and themes are in {}",
+                        dir.display()
+                    ),
+                    buttons: Vec::new(),
+                }
+            }
+            Err(e) => AlertContentData {
+                title: "The settings weren't reset".to_string(),
+                msg: e,
+                buttons: Vec::new(),
+            },
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Flip the boolean setting `id`, written as "table.key", telling why
+    /// when it isn't one that can be toggled
+    fn toggle_setting(&mut self, ctx: &mut EventCtx, id: &str) {
+        let (table, key) = id.split_once('.').unwrap_or((id, ""));
+        let error = match self.config.setting_value(table, key) {
+            _ if self.config.locked_by_policy(table, key) => {
+                format!("{id} is locked by policy")
+            }
+            Some(serde_json::Value::Bool(value)) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::UpdateSettingsFile(
+                        table.to_string(),
+                        key.to_string(),
+                        serde_json::json!(!value),
+                    ),
+                    Target::Widget(self.id),
+                ));
+                return;
+            }
+            Some(_) => format!("{id} isn't a setting that's on or off"),
+            None => format!("There's no {id} setting"),
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(AlertContentData {
+                title: "The setting wasn't toggled".to_string(),
+                msg: error,
+                buttons: Vec::new(),
+            }),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Preview the settings restoring the backup folder `dir` changes, and
+    /// ask whether to restore them
+    fn preview_settings_backup(&mut self, ctx: &mut EventCtx, dir: &Path) {
+        let changes = Config::settings_file()
+            .ok_or_else(|| "There is no settings file location".to_string())
+            .and_then(|settings_file| {
+                self.config.backup_changes(dir, &settings_file)
+            });
+        let content = match changes {
+            Ok(changes) if changes.is_empty() => AlertContentData {
+                title: "The settings are as they were backed up".to_string(),
+                msg: format!(
+                    "Nothing changed since the backup in {}",
+                    dir.display()
+                ),
+                buttons: Vec::new(),
+            },
+            Ok(changes) => {
+                self.main_split.open_read_only_settings(
+                    ctx,
+                    "Settings Backup Restore (read-only)".to_string(),
+                    &backup_restore_preview(dir, &changes),
+                    &self.config,
+                );
+                AlertContentData {
+                    title: format!(
+                        "Do you want to restore {} settings from the backup?",
+                        changes.len()
+                    ),
+                    msg: "The preview lists the changes. The themes in the backup \
+                          aren't restored."
+                        .to_string(),
+                    buttons: vec![(
+                        "Restore".to_string(),
+                        self.id,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::ApplySettingsBackup,
+                            ),
+                            data: serde_json::to_value(&changes).ok(),
+                        },
+                    )],
+                }
+            }
+            Err(err) => AlertContentData {
+                title: "Can't restore the settings backup".to_string(),
+                msg: err,
+                buttons: Vec::new(),
+            },
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowAlert(content),
+            Target::Widget(self.id),
+        ));
+    }
+
+    /// Open the file that sets the theme color at the line it's set on,
+    /// `kind` being the settings table e.g. "theme.ui"
+    fn reveal_theme_color_in_file(
+        &mut self,
+        ctx: &mut EventCtx,
+        kind: &str,
+        key: &str,
+    ) {
+        let found = self
+            .config
+            .theme_color_files()
+            .into_iter()
+            .find_map(|path| {
+                let text = std::fs::read_to_string(&path).ok()?;
+                settings_key_line(&text, kind, key).map(|line| (path, line))
+            });
+        match found {
+            Some((path, line)) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::JumpToLineLocation(
+                        None,
+                        EditorLocation {
+                            path,
+                            position: Some(Line(line + 1)),
+                            scroll_offset: None,
+                            history: None,
+                        },
+                    ),
+                    Target::Widget(self.id),
+                ));
+            }
+            None => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ShowAlert(AlertContentData {
+                        title: format!("No file sets {kind}.{key}"),
+                        msg: "The color comes from a built-in theme, which \
+                              doesn't have a file."
+                            .to_string(),
+                        buttons: Vec::new(),
+                    }),
+                    Target::Widget(self.id),
+                ));
+            }
+        }
+    }
+
+    /// Apply the colors of a theme copied as JSON from the clipboard
+    fn paste_theme_from_json(&mut self, ctx: &mut EventCtx) {
+        let text = druid::Application::global()
+            .clipboard()
+            .get_string()
+            .unwrap_or_default();
+        match theme_json_changes(&text, &self.config) {
+            Ok(changes) => {
+                self.apply_theme_colors(ctx, changes, "Pasting the theme colors")
+            }
+            Err(err) => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ShowAlert(AlertContentData {
+                        title: "Can't paste the theme".to_string(),
+                        msg: err,
+                        buttons: Vec::new(),
+                    }),
+                    Target::Widget(self.id),
+                ));
+            }
+        }
+    }
+
+    /// Store the theme colors, given as (table, key, color), in the settings
+    /// or keep them until they're applied while theme changes are confirmed,
+    /// `what` telling what the theme reloads for
+    fn apply_theme_colors(
+        &mut self,
+        ctx: &mut EventCtx,
+        changes: Vec<(String, String, String)>,
+        what: &str,
+    ) {
+        let confirm_theme = self.config.ui.confirm_theme_changes();
+        if !changes.is_empty() && !confirm_theme {
+            Arc::make_mut(&mut self.settings).start_reloading(what);
+        }
+        for (table, key, color) in changes {
+            let value = serde_json::Value::String(color);
+            // Update the inputs in the theme settings right away
+            self.main_split
+                .sync_settings_value_docs(&table, &key, &value);
+            if confirm_theme {
+                let current = self.config.setting_value(&table, &key);
+                Arc::make_mut(&mut self.settings).buffer_theme_change(
+                    &format!("{table}.{key}"),
+                    (table, key, value),
+                    current.as_ref(),
+                );
+            } else {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::UpdateSettingsFile(table, key, value),
+                    Target::Widget(self.id),
+                ));
+            }
+        }
+    }
+
     pub fn run_workbench_command(
         &mut self,
         ctx: &mut EventCtx,
@@ -1246,6 +2247,13 @@ pub fn run_workbench_command(
                     Target::Widget(self.palette.widget_id),
                 ));
             }
+            LapceWorkbenchCommand::OpenThemeGallery => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::RunPalette(Some(PaletteType::ThemeGallery)),
+                    Target::Widget(self.palette.widget_id),
+                ));
+            }
             LapceWorkbenchCommand::NewFile => {
                 self.main_split.new_file(ctx, &self.config);
             }
@@ -1265,8 +2273,172 @@ pub fn run_workbench_command(
                     );
                 }
             }
-            LapceWorkbenchCommand::OpenSettings => {
-                self.main_split.open_settings(ctx, false, &self.config);
+            LapceWorkbenchCommand::OpenSettings => {
+                self.main_split.open_settings(ctx, false, &self.config);
+            }
+            LapceWorkbenchCommand::ToggleSettingsAlphabeticalOrder => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.sort_alphabetically =
+                    !settings.preferences.sort_alphabetically;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::ToggleSettingsRawKeys => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.show_raw_keys =
+                    !settings.preferences.show_raw_keys;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::RecordSettingsSearch => {
+                let settings = Arc::make_mut(&mut self.settings);
+                let pattern = settings.filter_pattern.clone();
+                if settings.record_search(&pattern) {
+                    let _ = self
+                        .db
+                        .save_settings_search_history(&settings.search_history);
+                }
+            }
+            LapceWorkbenchCommand::RunSettingsSearch => {
+                if let Some(pattern) =
+                    data.and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    // The search input reports its new text as the filter
+                    if let Some(doc) = self
+                        .main_split
+                        .local_docs
+                        .get_mut(&LocalBufferKind::Settings)
+                    {
+                        Arc::make_mut(doc)
+                            .reload(Rope::from(pattern.as_str()), true);
+                    }
+                    let settings = Arc::make_mut(&mut self.settings);
+                    if settings.record_search(&pattern) {
+                        let _ = self
+                            .db
+                            .save_settings_search_history(&settings.search_history);
+                    }
+                }
+            }
+            LapceWorkbenchCommand::ResetSettingsPanelLayout => {
+                // Only the panel's own state, the settings values are kept
+                if let Some(doc) = self
+                    .main_split
+                    .local_docs
+                    .get_mut(&LocalBufferKind::Settings)
+                {
+                    Arc::make_mut(doc).reload(Rope::from(""), true);
+                }
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences = SettingsPanelPreferences::default();
+                settings.scroll_offsets.clear();
+                settings.filter_pattern.clear();
+                settings.section_filters.clear();
+                settings.current_setting = None;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ResetSettingsPanelLayout,
+                    Target::Widget(settings.panel_widget_id),
+                ));
+            }
+            LapceWorkbenchCommand::ToggleSettingsGroupByChanged => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.group_by_changed =
+                    !settings.preferences.group_by_changed;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::ToggleSettingsEffectiveConfig => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.show_effective_config =
+                    !settings.preferences.show_effective_config;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::ToggleSettingsDeprecated => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.show_deprecated =
+                    !settings.preferences.show_deprecated;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::ToggleSectionDescriptions => {
+                if let Some(table) =
+                    data.and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    let settings = Arc::make_mut(&mut self.settings);
+                    settings.preferences.toggle_descriptions(&table);
+                    let _ = self.db.save_settings_preferences(&settings.preferences);
+                }
+            }
+            LapceWorkbenchCommand::ToggleSettingsAdvanced => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.show_advanced =
+                    !settings.preferences.show_advanced;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::ToggleSettingsSwitcherCollapsed => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.preferences.switcher_collapsed =
+                    !settings.preferences.switcher_collapsed;
+                let _ = self.db.save_settings_preferences(&settings.preferences);
+            }
+            LapceWorkbenchCommand::NextInvalidSetting => {
+                self.show_invalid_setting(ctx, true);
+            }
+            LapceWorkbenchCommand::PreviousInvalidSetting => {
+                self.show_invalid_setting(ctx, false);
+            }
+            LapceWorkbenchCommand::RevealSettingInPanel => {
+                let field = data
+                    .and_then(|data| {
+                        serde_json::from_value::<(String, String)>(data).ok()
+                    })
+                    .or_else(|| self.main_split.settings_key_at_cursor());
+                if let Some((table, key)) = field {
+                    self.reveal_setting(ctx, table, key);
+                }
+            }
+            LapceWorkbenchCommand::ApplyThemeChanges => {
+                let changes = std::mem::take(
+                    &mut Arc::make_mut(&mut self.settings).buffered_theme_changes,
+                );
+                for (_, (parent, key, value)) in changes {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::UpdateSettingsFile(parent, key, value),
+                        Target::Widget(self.id),
+                    ));
+                }
+            }
+            LapceWorkbenchCommand::DiscardThemeChanges => {
+                let changes = std::mem::take(
+                    &mut Arc::make_mut(&mut self.settings).buffered_theme_changes,
+                );
+                // The inputs go back to the stored colors
+                for (name, (parent, key, _)) in changes {
+                    let value = self.config.setting_value(&parent, &key);
+                    if let (Some(doc), Some(serde_json::Value::String(color))) =
+                        (self.main_split.value_docs.get_mut(&name), value)
+                    {
+                        Arc::make_mut(doc).reload(Rope::from(color.as_str()), true);
+                    }
+                    Arc::make_mut(&mut self.settings).value_errors.remove(&name);
+                }
+            }
+            LapceWorkbenchCommand::CopySettingsSectionAsJson => {
+                if let Some((table, changed_only)) = data.and_then(|data| {
+                    serde_json::from_value::<(String, bool)>(data).ok()
+                }) {
+                    if let Some(json) =
+                        self.config.section_json(&table, changed_only)
+                    {
+                        druid::Application::global().clipboard().put_string(json);
+                    }
+                }
+            }
+            LapceWorkbenchCommand::RevealThemeColorInFile => {
+                if let Some((kind, key)) = data.and_then(|data| {
+                    serde_json::from_value::<(String, String)>(data).ok()
+                }) {
+                    self.reveal_theme_color_in_file(ctx, &kind, &key);
+                }
             }
             LapceWorkbenchCommand::OpenSettingsFile => {
                 if let Some(path) = Config::settings_file() {
@@ -1284,6 +2456,46 @@ pub fn run_workbench_command(
                     );
                 }
             }
+            LapceWorkbenchCommand::RevealSettingsFileInFileExplorer
+            | LapceWorkbenchCommand::RevealWorkspaceSettingsFileInFileExplorer
+            | LapceWorkbenchCommand::RevealThemeFileInFileExplorer => {
+                use LapceWorkbenchCommand::*;
+                let theme = &self.config.lapce.color_theme;
+                let (path, missing) = match command {
+                    RevealSettingsFileInFileExplorer => (
+                        Config::settings_file(),
+                        "Lapce couldn't find its config directory.".to_string(),
+                    ),
+                    RevealWorkspaceSettingsFileInFileExplorer => (
+                        self.config.workspace_settings().map(Path::to_path_buf),
+                        "This workspace doesn't have a settings file.".to_string(),
+                    ),
+                    RevealThemeFileInFileExplorer => (
+                        Config::theme_file(theme),
+                        format!(
+                            "The theme {theme} is built in, which doesn't have \
+                             a file."
+                        ),
+                    ),
+                    _ => return,
+                };
+                match path {
+                    Some(path) => ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::RevealInFileExplorer(path),
+                        Target::Auto,
+                    )),
+                    None => ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ShowAlert(AlertContentData {
+                            title: "There is no file to reveal".to_string(),
+                            msg: missing,
+                            buttons: Vec::new(),
+                        }),
+                        Target::Widget(self.id),
+                    )),
+                }
+            }
             LapceWorkbenchCommand::OpenSettingsDirectory
             | LapceWorkbenchCommand::OpenProxyDirectory
             | LapceWorkbenchCommand::OpenThemesDirectory
@@ -1609,12 +2821,340 @@ pub fn run_workbench_command(
                     Target::Auto,
                 ))
             }
+            LapceWorkbenchCommand::FlushSettings => {
+                self.flush_settings(ctx);
+            }
+            LapceWorkbenchCommand::ResetFocusedSetting => {
+                // Only the focused settings item knows it has the focus
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ResetFocusedSetting,
+                    Target::Global,
+                ));
+            }
+            LapceWorkbenchCommand::ReloadOpenFiles => {
+                self.main_split.reload_open_files(&self.config);
+                Arc::make_mut(&mut self.settings).reload_pending = None;
+            }
+            LapceWorkbenchCommand::ResetSetting => {
+                if let Some((table, key)) = data.and_then(|data| {
+                    serde_json::from_value::<(String, String)>(data).ok()
+                }) {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ResetSetting(table, key),
+                        Target::Global,
+                    ));
+                }
+            }
+            LapceWorkbenchCommand::CopySettingText => {
+                if let Some(text) =
+                    data.and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    druid::Application::global().clipboard().put_string(text);
+                }
+            }
+            LapceWorkbenchCommand::OpenDefaultSettings => {
+                self.main_split.open_default_settings(ctx, &self.config);
+            }
+            LapceWorkbenchCommand::CompareSettingsWithDefaults => {
+                self.main_split.open_read_only_settings(
+                    ctx,
+                    "Settings and Defaults (read-only)".to_string(),
+                    &self.config.compare_with_defaults(false),
+                    &self.config,
+                );
+            }
+            LapceWorkbenchCommand::CompareChangedSettingsWithDefaults => {
+                self.main_split.open_read_only_settings(
+                    ctx,
+                    "Changed Settings and Defaults (read-only)".to_string(),
+                    &self.config.compare_with_defaults(true),
+                    &self.config,
+                );
+            }
+            LapceWorkbenchCommand::ImportVSCodeSettings => {
+                let options = FileDialogOptions::new()
+                    .allowed_types(vec![druid::FileSpec::new("JSON", &["json"])])
+                    .accept_command(LAPCE_IMPORT_VSCODE_SETTINGS);
+                ctx.submit_command(druid::commands::SHOW_OPEN_PANEL.with(options));
+            }
+            LapceWorkbenchCommand::ApplyVSCodeSettingsImport => {
+                let changes = data
+                    .and_then(|data| {
+                        serde_json::from_value::<Vec<SettingChange>>(data).ok()
+                    })
+                    .unwrap_or_default();
+                // What was imported can be gone back to until the next import
+                let _ = self.db.save_import_baseline(&changes);
+                self.apply_settings_changes(ctx, changes);
+            }
+            LapceWorkbenchCommand::ResetToImportBaseline => {
+                self.reset_to_import_baseline(ctx);
+            }
+            LapceWorkbenchCommand::ApplyImportBaselineReset => {
+                let changes = data
+                    .and_then(|data| {
+                        serde_json::from_value::<Vec<SettingChange>>(data).ok()
+                    })
+                    .unwrap_or_default();
+                self.apply_settings_changes(ctx, changes);
+            }
+            LapceWorkbenchCommand::SwitchToPreviousTheme => {
+                let recent = self.db.get_recent_themes().unwrap_or_default();
+                let previous = recent.iter().find(|theme| {
+                    !theme.eq_ignore_ascii_case(&self.config.lapce.color_theme)
+                });
+                match previous {
+                    Some(theme) => {
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::SetTheme(theme.to_string(), false),
+                            Target::Auto,
+                        ));
+                    }
+                    None => {
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::ShowAlert(AlertContentData {
+                                title: "There is no previous theme".to_string(),
+                                msg: "After picking another theme, this switches \
+                                      back to the one before it."
+                                    .to_string(),
+                                buttons: Vec::new(),
+                            }),
+                            Target::Widget(self.id),
+                        ));
+                    }
+                }
+            }
+            LapceWorkbenchCommand::RenameTheme => {
+                self.ask_theme_file_name(ctx, ThemeFileAction::Rename);
+            }
+            LapceWorkbenchCommand::DuplicateTheme => {
+                self.ask_theme_file_name(ctx, ThemeFileAction::Duplicate);
+            }
+            LapceWorkbenchCommand::ApplyRenameTheme => {
+                if let Some(name) = data.as_ref().and_then(|data| data.as_str()) {
+                    self.save_theme_file_as(ctx, name, ThemeFileAction::Rename);
+                }
+            }
+            LapceWorkbenchCommand::ApplyDuplicateTheme => {
+                if let Some(name) = data.as_ref().and_then(|data| data.as_str()) {
+                    self.save_theme_file_as(ctx, name, ThemeFileAction::Duplicate);
+                }
+            }
+            LapceWorkbenchCommand::ReplaceThemeColor => {
+                match data.as_ref().and_then(|data| data.as_str()) {
+                    Some(input) => self.confirm_replace_theme_color(ctx, input),
+                    None => ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::RunPalette(Some(
+                            PaletteType::ThemeColorReplacement,
+                        )),
+                        Target::Widget(self.palette.widget_id),
+                    )),
+                }
+            }
+            LapceWorkbenchCommand::ApplyReplaceThemeColor => {
+                if let Some((old, new)) = data.and_then(|data| {
+                    serde_json::from_value::<(String, String)>(data).ok()
+                }) {
+                    self.replace_theme_color(ctx, &old, &new);
+                }
+            }
+            LapceWorkbenchCommand::SearchThemeColors => {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::RunPalette(Some(PaletteType::ThemeColors)),
+                    Target::Widget(self.id),
+                ));
+            }
+            LapceWorkbenchCommand::ResetAllSettings => {
+                self.confirm_reset_all_settings(ctx);
+            }
+            LapceWorkbenchCommand::ApplyResetAllSettings => {
+                self.reset_all_settings(ctx);
+            }
+            LapceWorkbenchCommand::RevertSettingsSession => {
+                self.confirm_revert_settings_session(ctx);
+            }
+            LapceWorkbenchCommand::ApplyRevertSettingsSession => {
+                self.revert_settings_session(ctx);
+            }
+            LapceWorkbenchCommand::RestoreSettingsFromBackup => {
+                if Config::settings_backups().is_empty() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ShowAlert(AlertContentData {
+                            title: "There are no settings backups".to_string(),
+                            msg: "Resetting all settings backs them up first."
+                                .to_string(),
+                            buttons: Vec::new(),
+                        }),
+                        Target::Widget(self.id),
+                    ));
+                } else {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::RunPalette(Some(
+                            PaletteType::SettingsBackup,
+                        )),
+                        Target::Widget(self.id),
+                    ));
+                }
+            }
+            LapceWorkbenchCommand::ToggleSetting => {
+                match data
+                    .and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    Some(id) => self.toggle_setting(ctx, &id),
+                    None => {
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::RunPalette(Some(
+                                PaletteType::ToggleSetting,
+                            )),
+                            Target::Widget(self.id),
+                        ));
+                    }
+                }
+            }
+            LapceWorkbenchCommand::ShowChangedDefaultSettings => {
+                if Config::changed_defaults().is_empty() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ShowAlert(AlertContentData {
+                            title: "No default settings changed".to_string(),
+                            msg: "The defaults are the same as the ones of the \
+                                  version run before."
+                                .to_string(),
+                            buttons: Vec::new(),
+                        }),
+                        Target::Widget(self.id),
+                    ));
+                } else {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::RunPalette(Some(
+                            PaletteType::ChangedDefaults,
+                        )),
+                        Target::Widget(self.id),
+                    ));
+                }
+            }
+            LapceWorkbenchCommand::PreviewSettingsBackup => {
+                if let Some(dir) = data
+                    .and_then(|data| serde_json::from_value::<PathBuf>(data).ok())
+                {
+                    self.preview_settings_backup(ctx, &dir);
+                }
+            }
+            LapceWorkbenchCommand::ApplySettingsBackup => {
+                let changes = data
+                    .and_then(|data| {
+                        serde_json::from_value::<Vec<SettingChange>>(data).ok()
+                    })
+                    .unwrap_or_default();
+                self.apply_settings_changes(ctx, changes);
+            }
+            LapceWorkbenchCommand::EditKeybinding => {
+                if let Some(command) =
+                    data.and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    self.main_split.open_settings(ctx, true, &self.config);
+                    let doc = self
+                        .main_split
+                        .local_docs
+                        .get_mut(&LocalBufferKind::Keymap)
+                        .unwrap();
+                    Arc::make_mut(doc)
+                        .reload(Rope::from(format!("command:{command}")), true);
+                }
+            }
+            LapceWorkbenchCommand::CopyKeybinding => {
+                if let Some(snippet) =
+                    data.and_then(|data| serde_json::from_value::<String>(data).ok())
+                {
+                    druid::Application::global().clipboard().put_string(snippet);
+                }
+            }
             LapceWorkbenchCommand::ExportCurrentThemeSettings => {
                 self.main_split.export_theme(ctx, &self.config);
             }
+            LapceWorkbenchCommand::ExportSettings => {
+                self.main_split.export_settings(ctx, &self.config);
+            }
+            LapceWorkbenchCommand::ExportSettingsDocs => {
+                self.main_split.export_settings_docs(ctx, &self.config);
+            }
+            LapceWorkbenchCommand::ExportKeybindings => {
+                self.main_split.export_keybindings(
+                    ctx,
+                    &self.keypress,
+                    &self.config,
+                    false,
+                );
+            }
+            LapceWorkbenchCommand::ExportChangedKeybindings => {
+                self.main_split.export_keybindings(
+                    ctx,
+                    &self.keypress,
+                    &self.config,
+                    true,
+                );
+            }
+            LapceWorkbenchCommand::ExportKeybindingsCheatSheet => {
+                self.main_split.export_keybindings_cheat_sheet(
+                    ctx,
+                    &self.keypress,
+                    &self.config,
+                );
+            }
             LapceWorkbenchCommand::InstallTheme => {
                 self.main_split.install_theme(ctx, &self.config);
             }
+            LapceWorkbenchCommand::ImportThemeFile => {
+                let options = FileDialogOptions::new()
+                    .allowed_types(vec![druid::FileSpec::new("TOML", &["toml"])])
+                    .accept_command(LAPCE_IMPORT_THEME);
+                ctx.submit_command(druid::commands::SHOW_OPEN_PANEL.with(options));
+            }
+            LapceWorkbenchCommand::ApplyThemeImport => {
+                if let Some(import) = data.and_then(|data| {
+                    serde_json::from_value::<ThemeImport>(data).ok()
+                }) {
+                    match import.maps {
+                        Some(maps) => {
+                            self.merge_theme_file(ctx, &import.path, &maps)
+                        }
+                        None => self.install_theme_file(ctx, &import.path),
+                    }
+                }
+            }
+            LapceWorkbenchCommand::CopyThemeAsJson => {
+                if let Ok(json) = serde_json::to_string_pretty(&self.config.theme) {
+                    druid::Application::global().clipboard().put_string(json);
+                }
+            }
+            LapceWorkbenchCommand::CopyThemeName => {
+                let name = self.config.lapce.color_theme.clone();
+                druid::Application::global()
+                    .clipboard()
+                    .put_string(name.clone());
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::ShowAlert(AlertContentData {
+                        title: "Copied the theme name".to_string(),
+                        msg: name,
+                        buttons: Vec::new(),
+                    }),
+                    Target::Widget(self.id),
+                ));
+            }
+            LapceWorkbenchCommand::PasteThemeFromJson => {
+                self.paste_theme_from_json(ctx);
+            }
             LapceWorkbenchCommand::ChangeFileLanguage => {
                 ctx.submit_command(Command::new(
                     LAPCE_UI_COMMAND,
@@ -2096,6 +3636,19 @@ pub fn active_editor(&self) -> Option<&LapceEditorData> {
         Some(self.editors.get(&id)?.as_ref())
     }
 
+    /// The settings table and key under the cursor of the active editor,
+    /// if it's editing a settings file
+    pub fn settings_key_at_cursor(&self) -> Option<(String, String)> {
+        let editor = self.active_editor()?;
+        match &editor.content {
+            BufferContent::File(path) if Config::is_settings_file(path) => {}
+            _ => return None,
+        }
+        let doc = self.content_doc(&editor.content);
+        let line = doc.buffer().line_of_offset(editor.cursor.offset());
+        settings_key_at_line(&doc.buffer().to_string(), line)
+    }
+
     pub fn content_doc(&self, content: &BufferContent) -> Arc<Document> {
         match content {
             BufferContent::File(path) => self.open_docs.get(path).unwrap().clone(),
@@ -2354,10 +3907,20 @@ fn editor_tab_new_settings(
         );
         let keymap_input_view_id = editor.view_id;
         self.editors.insert(editor.view_id, Arc::new(editor));
+        let editor = LapceEditorData::new(
+            None,
+            None,
+            None,
+            BufferContent::Local(LocalBufferKind::Settings),
+            config,
+        );
+        let settings_input_view_id = editor.view_id;
+        self.editors.insert(editor.view_id, Arc::new(editor));
         let child = EditorTabChild::Settings {
             settings_widget_id: WidgetId::next(),
             editor_tab_id,
             keymap_input_view_id,
+            settings_input_view_id,
         };
         editor_tab.children.push(child.clone());
         child.widget_id()
@@ -2618,7 +4181,7 @@ pub fn open_settings(
         ctx: &mut EventCtx,
         show_key_bindings: bool,
         config: &Config,
-    ) {
+    ) -> WidgetId {
         let widget_id = match *self.active_tab {
             Some(active) => {
                 let editor_tab =
@@ -2644,10 +4207,20 @@ pub fn open_settings(
                     );
                     let keymap_input_view_id = editor.view_id;
                     self.editors.insert(editor.view_id, Arc::new(editor));
+                    let editor = LapceEditorData::new(
+                        None,
+                        None,
+                        None,
+                        BufferContent::Local(LocalBufferKind::Settings),
+                        config,
+                    );
+                    let settings_input_view_id = editor.view_id;
+                    self.editors.insert(editor.view_id, Arc::new(editor));
                     let child = EditorTabChild::Settings {
                         settings_widget_id: WidgetId::next(),
                         editor_tab_id: editor_tab.widget_id,
                         keymap_input_view_id,
+                        settings_input_view_id,
                     };
                     editor_tab
                         .children
@@ -2687,6 +4260,7 @@ pub fn open_settings(
                 Target::Widget(widget_id),
             ));
         }
+        widget_id
     }
 
     pub fn jump_to_location<P: EditorPosition + Send + 'static>(
@@ -2798,11 +4372,10 @@ fn get_name_for_new_file(&self) -> String {
             .values()
             .filter_map(|doc| match doc.content() {
                 BufferContent::Scratch(_, existing_name) => {
-                    // The unwraps are safe because scratch docs are always
-                    // titled the same format and the user cannot change the name.
-                    let num_part = existing_name.strip_prefix(PREFIX).unwrap();
-                    let num = num_part.parse::<i32>().unwrap();
-                    Some(num)
+                    // Scratch docs opened by Lapce itself (e.g. the default
+                    // settings) don't follow the untitled format, so skip them.
+                    let num_part = existing_name.strip_prefix(PREFIX)?;
+                    num_part.parse::<i32>().ok()
                 }
                 _ => None,
             })
@@ -2843,12 +4416,136 @@ pub fn export_theme(&mut self, ctx: &mut EventCtx, config: &Config) {
         doc.reload(Rope::from(config.export_theme()), true);
     }
 
+    /// Show a stored setting in the other inputs that edit it, the ones which
+    /// already have the value are left as they are
+    pub fn sync_settings_value_docs(
+        &mut self,
+        parent: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) {
+        for doc in self.value_docs.values_mut() {
+            let text = match doc.content() {
+                BufferContent::SettingsValue(_, kind, p, k)
+                    if p == parent
+                        && k == key
+                        && kind != &SettingsValueKind::MapEntry
+                        && !matches!(
+                            parse_settings_value(kind, p, &doc.buffer().to_string()),
+                            Ok(v) if &v == value
+                        ) =>
+                {
+                    settings_value_text(kind, value)
+                }
+                _ => continue,
+            };
+            Arc::make_mut(doc).reload(Rope::from(text), true);
+        }
+    }
+
+    pub fn export_settings(&mut self, ctx: &mut EventCtx, config: &Config) {
+        let id = self.new_file(ctx, config);
+        let doc = self.scratch_docs.get_mut(&id).unwrap();
+        let doc = Arc::make_mut(doc);
+
+        #[cfg(feature = "lang-toml")]
+        doc.set_language(lapce_core::language::LapceLanguage::Toml);
+
+        doc.reload(Rope::from(config.export_settings()), true);
+    }
+
+    pub fn export_settings_docs(&mut self, ctx: &mut EventCtx, config: &Config) {
+        let id = self.new_file(ctx, config);
+        let doc = self.scratch_docs.get_mut(&id).unwrap();
+        let doc = Arc::make_mut(doc);
+
+        #[cfg(feature = "lang-markdown")]
+        doc.set_language(lapce_core::language::LapceLanguage::Markdown);
+
+        doc.reload(Rope::from(Config::settings_docs_markdown()), true);
+    }
+
+    /// Open the key bindings as a keymaps file, only the changes to the
+    /// defaults with `changed_only`
+    pub fn export_keybindings(
+        &mut self,
+        ctx: &mut EventCtx,
+        keypress: &KeyPressData,
+        config: &Config,
+        changed_only: bool,
+    ) {
+        let id = self.new_file(ctx, config);
+        let doc = self.scratch_docs.get_mut(&id).unwrap();
+        let doc = Arc::make_mut(doc);
+
+        #[cfg(feature = "lang-toml")]
+        doc.set_language(lapce_core::language::LapceLanguage::Toml);
+
+        doc.reload(
+            Rope::from(keypress.export_keymaps(config, changed_only)),
+            true,
+        );
+    }
+
+    pub fn export_keybindings_cheat_sheet(
+        &mut self,
+        ctx: &mut EventCtx,
+        keypress: &KeyPressData,
+        config: &Config,
+    ) {
+        let id = self.new_file(ctx, config);
+        let doc = self.scratch_docs.get_mut(&id).unwrap();
+        let doc = Arc::make_mut(doc);
+
+        #[cfg(feature = "lang-markdown")]
+        doc.set_language(lapce_core::language::LapceLanguage::Markdown);
+
+        doc.reload(Rope::from(keypress.cheat_sheet()), true);
+    }
+
+    pub fn open_default_settings(&mut self, ctx: &mut EventCtx, config: &Config) {
+        self.open_read_only_settings(
+            ctx,
+            "Default Settings (read-only)".to_string(),
+            Config::default_settings_text(),
+            config,
+        );
+    }
+
+    /// Open settings file contents in a read-only scratch buffer
+    pub fn open_read_only_settings(
+        &mut self,
+        ctx: &mut EventCtx,
+        name: String,
+        text: &str,
+        config: &Config,
+    ) {
+        let id = self.new_scratch_file(ctx, name, config);
+        let doc = self.scratch_docs.get_mut(&id).unwrap();
+        let doc = Arc::make_mut(doc);
+
+        #[cfg(feature = "lang-toml")]
+        doc.set_language(lapce_core::language::LapceLanguage::Toml);
+
+        doc.reload(Rope::from(text), true);
+        doc.set_read_only(true);
+    }
+
     pub fn new_file(&mut self, ctx: &mut EventCtx, config: &Config) -> BufferId {
+        let name = self.get_name_for_new_file();
+        self.new_scratch_file(ctx, name, config)
+    }
+
+    fn new_scratch_file(
+        &mut self,
+        ctx: &mut EventCtx,
+        name: String,
+        config: &Config,
+    ) -> BufferId {
         let tab_id = *self.tab_id;
         let proxy = self.proxy.clone();
         let buffer_id = BufferId::next();
-        let content =
-            BufferContent::Scratch(buffer_id, self.get_name_for_new_file());
+        let content = BufferContent::Scratch(buffer_id, name);
         let doc =
             Document::new(content.clone(), tab_id, ctx.get_external_handle(), proxy);
         self.scratch_docs.insert(buffer_id, Arc::new(doc));
@@ -2863,6 +4560,23 @@ pub fn new_file(&mut self, ctx: &mut EventCtx, config: &Config) -> BufferId {
         buffer_id
     }
 
+    /// Apply the settings read when a file is opened to the editors of the
+    /// open files, the cursors start over in the mode of `lapce.modal`
+    pub fn reload_open_files(&mut self, config: &Config) {
+        for editor in self.editors.values_mut() {
+            if !editor.content.is_file() {
+                continue;
+            }
+            let editor = Arc::make_mut(editor);
+            let offset = editor.cursor.offset();
+            editor.cursor = if config.lapce.modal {
+                Cursor::new(CursorMode::Normal(offset), None, None)
+            } else {
+                Cursor::new(CursorMode::Insert(Selection::caret(offset)), None, None)
+            };
+        }
+    }
+
     pub fn go_to_location<P: EditorPosition + Send + 'static>(
         &mut self,
         ctx: &mut EventCtx,
@@ -3662,6 +5376,15 @@ pub fn split_settings(
         );
         let keymap_input_view_id = editor.view_id;
         self.editors.insert(editor.view_id, Arc::new(editor));
+        let editor = LapceEditorData::new(
+            None,
+            None,
+            None,
+            BufferContent::Local(LocalBufferKind::Settings),
+            config,
+        );
+        let settings_input_view_id = editor.view_id;
+        self.editors.insert(editor.view_id, Arc::new(editor));
 
         let new_editor_tab_id = WidgetId::next();
         let mut new_editor_tab = LapceEditorTabData {
@@ -3672,6 +5395,7 @@ pub fn split_settings(
                 settings_widget_id: WidgetId::next(),
                 editor_tab_id: new_editor_tab_id,
                 keymap_input_view_id,
+                settings_input_view_id,
             }],
             layout_rect: Rc::new(RefCell::new(Rect::ZERO)),
             content_is_hot: Rc::new(RefCell::new(false)),
@@ -3801,6 +5525,7 @@ pub enum EditorTabChild {
         settings_widget_id: WidgetId,
         editor_tab_id: WidgetId,
         keymap_input_view_id: WidgetId,
+        settings_input_view_id: WidgetId,
     },
     Plugin {
         widget_id: WidgetId,
@@ -24,6 +24,10 @@
     document::{BufferContent, Document, LocalBufferKind},
     editor::EditorLocation,
     panel::{PanelData, PanelOrder},
+    settings::{
+        record_theme_change, RecentSettingsChange, SettingChange,
+        SettingsPanelPreferences,
+    },
     split::SplitDirection,
 };
 
@@ -176,11 +180,21 @@ pub fn to_data(
                 );
                 let keymap_input_view_id = editor.view_id;
                 data.editors.insert(editor.view_id, Arc::new(editor));
+                let editor = LapceEditorData::new(
+                    None,
+                    None,
+                    None,
+                    BufferContent::Local(LocalBufferKind::Settings),
+                    config,
+                );
+                let settings_input_view_id = editor.view_id;
+                data.editors.insert(editor.view_id, Arc::new(editor));
 
                 EditorTabChild::Settings {
                     settings_widget_id: WidgetId::next(),
                     editor_tab_id,
                     keymap_input_view_id,
+                    settings_input_view_id,
                 }
             }
             EditorTabChildInfo::Plugin { volt_id, volt_name } => {
@@ -597,6 +611,128 @@ pub fn save_panel_orders(&self, order: &PanelOrder) -> Result<()> {
         Ok(())
     }
 
+    pub fn get_settings_preferences(&self) -> Result<SettingsPanelPreferences> {
+        let sled_db = self.get_db()?;
+        let preferences = sled_db
+            .get("settings_panel_preferences")?
+            .ok_or_else(|| anyhow!("can't find settings panel preferences"))?;
+        let preferences = std::str::from_utf8(&preferences)?;
+        let preferences: SettingsPanelPreferences =
+            serde_json::from_str(preferences)?;
+        Ok(preferences)
+    }
+
+    pub fn save_settings_preferences(
+        &self,
+        preferences: &SettingsPanelPreferences,
+    ) -> Result<()> {
+        let info = serde_json::to_string(preferences)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("settings_panel_preferences", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
+    pub fn get_recent_settings(&self) -> Result<Vec<RecentSettingsChange>> {
+        let sled_db = self.get_db()?;
+        let changes = sled_db
+            .get("settings_recent_changes")?
+            .ok_or_else(|| anyhow!("can't find recently changed settings"))?;
+        let changes = std::str::from_utf8(&changes)?;
+        let changes: Vec<RecentSettingsChange> = serde_json::from_str(changes)?;
+        Ok(changes)
+    }
+
+    pub fn save_recent_settings(
+        &self,
+        changes: &[RecentSettingsChange],
+    ) -> Result<()> {
+        let info = serde_json::to_string(changes)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("settings_recent_changes", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
+    pub fn get_settings_change_times(&self) -> Result<im::HashMap<String, u64>> {
+        let sled_db = self.get_db()?;
+        let times = sled_db
+            .get("settings_change_times")?
+            .ok_or_else(|| anyhow!("can't find the settings change times"))?;
+        let times = std::str::from_utf8(&times)?;
+        let times: im::HashMap<String, u64> = serde_json::from_str(times)?;
+        Ok(times)
+    }
+
+    pub fn save_settings_change_times(
+        &self,
+        times: &im::HashMap<String, u64>,
+    ) -> Result<()> {
+        let info = serde_json::to_string(times)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("settings_change_times", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
+    pub fn get_settings_search_history(&self) -> Result<Vec<String>> {
+        let sled_db = self.get_db()?;
+        let history = sled_db
+            .get("settings_search_history")?
+            .ok_or_else(|| anyhow!("can't find the settings search history"))?;
+        let history = std::str::from_utf8(&history)?;
+        let history: Vec<String> = serde_json::from_str(history)?;
+        Ok(history)
+    }
+
+    pub fn save_settings_search_history(&self, history: &[String]) -> Result<()> {
+        let info = serde_json::to_string(history)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("settings_search_history", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
+    /// The settings applied by the last import, they stay until the next one
+    pub fn get_import_baseline(&self) -> Result<Vec<SettingChange>> {
+        let sled_db = self.get_db()?;
+        let changes = sled_db
+            .get("settings_import_baseline")?
+            .ok_or_else(|| anyhow!("can't find imported settings"))?;
+        let changes = std::str::from_utf8(&changes)?;
+        let changes: Vec<SettingChange> = serde_json::from_str(changes)?;
+        Ok(changes)
+    }
+
+    pub fn save_import_baseline(&self, changes: &[SettingChange]) -> Result<()> {
+        let info = serde_json::to_string(changes)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("settings_import_baseline", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
+    /// The themes used last, most recent first
+    pub fn get_recent_themes(&self) -> Result<Vec<String>> {
+        let sled_db = self.get_db()?;
+        let themes = sled_db
+            .get("recent_themes")?
+            .ok_or_else(|| anyhow!("can't find recent themes"))?;
+        let themes = std::str::from_utf8(&themes)?;
+        let themes: Vec<String> = serde_json::from_str(themes)?;
+        Ok(themes)
+    }
+
+    pub fn save_theme_change(&self, old: &str, new: &str) -> Result<()> {
+        let mut themes = self.get_recent_themes().unwrap_or_default();
+        record_theme_change(&mut themes, old, new);
+        let info = serde_json::to_string(&themes)?;
+        let sled_db = self.get_db()?;
+        sled_db.insert("recent_themes", info.as_str())?;
+        sled_db.flush()?;
+        Ok(())
+    }
+
     fn insert_workspace(
         &self,
         workspace: &LapceWorkspace,
@@ -41,7 +41,7 @@
 use smallvec::SmallVec;
 use xi_rope::{
     spans::{Spans, SpansBuilder},
-    Interval, Rope, RopeDelta, Transformer,
+    DeltaBuilder, Interval, Rope, RopeDelta, Transformer,
 };
 
 use crate::{
@@ -52,7 +52,7 @@
     find::{Find, FindProgress},
     history::DocumentHistory,
     proxy::LapceProxy,
-    settings::SettingsValueKind,
+    settings::{normalize_pasted_color, SettingsValueKind},
 };
 
 pub struct SystemClipboard {}
@@ -73,6 +73,27 @@ fn put_string(&mut self, s: impl AsRef<str>) {
     }
 }
 
+/// The system clipboard, normalizing the colors pasted into theme color
+/// inputs
+struct SettingsClipboard {
+    colors: bool,
+}
+
+impl Clipboard for SettingsClipboard {
+    fn get_string(&self) -> Option<String> {
+        let s = SystemClipboard::clipboard().get_string()?;
+        if self.colors {
+            Some(normalize_pasted_color(&s).unwrap_or(s))
+        } else {
+            Some(s)
+        }
+    }
+
+    fn put_string(&mut self, s: impl AsRef<str>) {
+        SystemClipboard::clipboard().put_string(s)
+    }
+}
+
 pub struct LineExtraStyle {
     pub bg_color: Option<Color>,
     pub under_line: Option<Color>,
@@ -420,6 +441,7 @@ pub struct Document {
     find_progress: Rc<RefCell<FindProgress>>,
     pub event_sink: ExtEventSink,
     pub proxy: Arc<LapceProxy>,
+    read_only: bool,
 }
 
 impl Document {
@@ -462,6 +484,7 @@ pub fn new(
             find_progress: Rc::new(RefCell::new(FindProgress::Ready)),
             event_sink,
             proxy,
+            read_only: false,
         }
     }
 
@@ -488,6 +511,15 @@ pub fn content(&self) -> &BufferContent {
         &self.content
     }
 
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Prevent any edits from being applied to the buffer, copying is still allowed
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
     pub fn rev(&self) -> u64 {
         self.buffer.rev()
     }
@@ -1073,6 +1105,9 @@ pub fn do_insert(
         cursor: &mut Cursor,
         s: &str,
     ) -> Vec<(RopeDelta, InvalLines)> {
+        if self.read_only {
+            return Vec::new();
+        }
         let old_cursor = cursor.mode.clone();
         let deltas =
             Editor::insert(cursor, &mut self.buffer, s, self.syntax.as_ref());
@@ -1087,6 +1122,14 @@ pub fn do_raw_edit(
         edits: &[(impl AsRef<Selection>, &str)],
         edit_type: EditType,
     ) -> (RopeDelta, InvalLines) {
+        if self.read_only {
+            let inval_lines = InvalLines {
+                start_line: 0,
+                inval_count: 0,
+                new_count: 0,
+            };
+            return (DeltaBuilder::new(self.buffer.len()).build(), inval_lines);
+        }
         let (delta, inval_lines) = self.buffer.edit(edits, edit_type);
         self.apply_deltas(&[(delta.clone(), inval_lines.clone())]);
         (delta, inval_lines)
@@ -1099,7 +1142,18 @@ pub fn do_edit(
         modal: bool,
         register: &mut Register,
     ) -> Vec<(RopeDelta, InvalLines)> {
-        let mut clipboard = SystemClipboard {};
+        if self.read_only
+            && !matches!(cmd, EditCommand::ClipboardCopy | EditCommand::Yank)
+        {
+            return Vec::new();
+        }
+        let mut clipboard = SettingsClipboard {
+            colors: matches!(
+                &self.content,
+                BufferContent::SettingsValue(_, SettingsValueKind::String, parent, _)
+                    if parent.starts_with("theme.")
+            ),
+        };
         let old_cursor = cursor.mode.clone();
         let deltas = Editor::do_edit(
             cursor,
@@ -1352,7 +1406,10 @@ pub fn do_motion_mode(
         register: &mut Register,
     ) {
         if let Some(m) = &cursor.motion_mode {
-            if m == &motion_mode {
+            // Yanking only copies, the other motions edit the buffer
+            if m == &motion_mode
+                && (!self.read_only || motion_mode == MotionMode::Yank)
+            {
                 let offset = cursor.offset();
                 let deltas = Editor::execute_motion_mode(
                     cursor,
@@ -1372,6 +1429,9 @@ pub fn do_motion_mode(
     }
 
     pub fn do_paste(&mut self, cursor: &mut Cursor, data: &RegisterData) {
+        if self.read_only {
+            return;
+        }
         let deltas = Editor::do_paste(cursor, &mut self.buffer, data);
         self.apply_deltas(&deltas)
     }
@@ -2695,3 +2755,46 @@ pub fn sticky_headers(&self, line: usize) -> Option<Vec<usize>> {
         lines
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use druid::{widget::Label, AppLauncher, WindowDesc};
+    use lapce_core::mode::VisualMode;
+
+    use super::*;
+
+    #[test]
+    fn test_read_only_edits() {
+        let event_sink =
+            AppLauncher::with_window(WindowDesc::new(Label::<()>::new("")))
+                .get_external_handle();
+        let tab_id = WidgetId::next();
+        let proxy = Arc::new(LapceProxy::detached(tab_id, event_sink.clone()));
+        let mut doc = Document::new(
+            BufferContent::Scratch(BufferId::next(), "read only".to_string()),
+            tab_id,
+            event_sink,
+            proxy,
+        );
+        doc.reload(Rope::from("abc"), true);
+        doc.set_read_only(true);
+
+        let mut cursor =
+            Cursor::new(CursorMode::Insert(Selection::caret(1)), None, None);
+        let data = RegisterData {
+            content: "xyz".to_string(),
+            mode: VisualMode::Normal,
+        };
+        doc.do_paste(&mut cursor, &data);
+        assert_eq!(doc.buffer().text().to_string(), "abc");
+
+        let mut register = Register::default();
+        doc.do_motion_mode(&mut cursor, MotionMode::Delete, &mut register);
+        doc.do_motion_mode(&mut cursor, MotionMode::Delete, &mut register);
+        assert_eq!(doc.buffer().text().to_string(), "abc");
+
+        let selection = Selection::region(0, 3);
+        doc.do_raw_edit(&[(&selection, "")], EditType::Delete);
+        assert_eq!(doc.buffer().text().to_string(), "abc");
+    }
+}
@@ -57,30 +57,29 @@ pub fn to_lowercase(&self) -> Self {
         }
     }
 
-    pub fn paint(
-        &self,
-        ctx: &mut PaintCtx,
-        origin: Point,
-        config: &Config,
-    ) -> (Point, Vec<(Option<Rect>, PietTextLayout, Point)>) {
-        let mut origin = origin;
+    /// The names of the keys pressed, modifiers first, as they're labeled on
+    /// the platform
+    pub fn labels(&self) -> Vec<String> {
         let mut keys = Vec::new();
+        // macOS labels its modifier keys with glyphs instead of names
+        let macos = std::env::consts::OS == "macos";
+        let shift = if macos { "⇧" } else { "Shift" };
         if self.mods.ctrl() {
-            keys.push("Ctrl".to_string());
+            keys.push(if macos { "⌃" } else { "Ctrl" }.to_string());
         }
         if self.mods.alt() {
-            keys.push("Alt".to_string());
+            keys.push(if macos { "⌥" } else { "Alt" }.to_string());
         }
         if self.mods.meta() {
             let keyname = match std::env::consts::OS {
-                "macos" => "Cmd",
+                "macos" => "⌘",
                 "windows" => "Win",
                 _ => "Meta",
             };
             keys.push(keyname.to_string());
         }
         if self.mods.shift() {
-            keys.push("Shift".to_string());
+            keys.push(shift.to_string());
         }
         match &self.key {
             druid::keyboard_types::Key::Character(c) => {
@@ -88,7 +87,7 @@ pub fn paint(
                     && c.to_lowercase() != c.to_uppercase()
                     && !self.mods.shift()
                 {
-                    keys.push("Shift".to_string());
+                    keys.push(shift.to_string());
                 }
                 keys.push(c.to_uppercase());
             }
@@ -96,7 +95,17 @@ pub fn paint(
                 keys.push(self.key.to_string());
             }
         }
+        keys
+    }
 
+    pub fn paint(
+        &self,
+        ctx: &mut PaintCtx,
+        origin: Point,
+        config: &Config,
+    ) -> (Point, Vec<(Option<Rect>, PietTextLayout, Point)>) {
+        let mut origin = origin;
+        let keys = self.labels();
         let mut items = Vec::new();
         let keys_len = keys.len();
         for (i, key) in keys.iter().enumerate() {
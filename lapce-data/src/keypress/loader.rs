@@ -21,6 +21,25 @@ pub fn load_from_str<'a>(
         &'a mut self,
         s: &str,
         modal: bool,
+    ) -> Result<&'a mut Self> {
+        self.load(s, modal, None)
+    }
+
+    /// Load the key bindings a plugin contributes, tagged with its id
+    pub fn load_plugin_keymaps<'a>(
+        &'a mut self,
+        s: &str,
+        modal: bool,
+        plugin_id: &str,
+    ) -> Result<&'a mut Self> {
+        self.load(s, modal, Some(plugin_id))
+    }
+
+    fn load<'a>(
+        &'a mut self,
+        s: &str,
+        modal: bool,
+        source: Option<&str>,
     ) -> Result<&'a mut Self> {
         let toml_keymaps: toml_edit::Document = s.parse()?;
         let toml_keymaps = toml_keymaps
@@ -29,7 +48,7 @@ pub fn load_from_str<'a>(
             .ok_or_else(|| anyhow!("no keymaps"))?;
 
         for toml_keymap in toml_keymaps {
-            let keymap = match Self::get_keymap(toml_keymap, modal) {
+            let mut keymap = match Self::get_keymap(toml_keymap, modal) {
                 Ok(Some(keymap)) => keymap,
                 Ok(None) => {
                     // Keymap ignored
@@ -41,6 +60,8 @@ pub fn load_from_str<'a>(
                 }
             };
 
+            keymap.source = source.map(|source| source.to_string());
+
             let (command, bind) = match keymap.command.strip_prefix('-') {
                 Some(cmd) => (cmd.to_string(), false),
                 None => (keymap.command.clone(), true),
@@ -122,6 +143,7 @@ fn get_keymap(
                 .and_then(|c| c.as_str())
                 .map(|w| w.trim().to_string())
                 .unwrap_or_else(|| "".to_string()),
+            source: None,
         }))
     }
 }
@@ -204,4 +226,51 @@ fn test_keymap() {
         let keypress = KeyPress::parse("shift+i");
         assert_eq!(keymaps.get(&keypress).unwrap().len(), 1);
     }
+
+    #[test]
+    fn test_keymap_toml_snippet() {
+        let keymap = KeyMap {
+            key: KeyPress::parse("ctrl+w l"),
+            modes: Modes::NORMAL | Modes::VISUAL,
+            when: Some("!list_focus".to_string()),
+            command: "right".to_string(),
+            source: None,
+        };
+        let mut loader = KeyMapLoader::new();
+        loader
+            .load_from_str(&keymap.to_toml_snippet(), true)
+            .unwrap();
+
+        let (keymaps, _) = loader.finalize();
+        assert_eq!(keymaps.get(&keymap.key), Some(&vec![keymap.clone()]));
+    }
+
+    #[test]
+    fn test_plugin_keymaps() {
+        let mut loader = KeyMapLoader::new();
+        loader
+            .load_from_str(
+                "[[keymaps]]\nkey = \"ctrl+k\"\ncommand = \"default\"\n",
+                false,
+            )
+            .unwrap()
+            .load_plugin_keymaps(
+                "[[keymaps]]\nkey = \"ctrl+k\"\ncommand = \"plugin\"\n",
+                false,
+                "lapce.plugin",
+            )
+            .unwrap()
+            .load_from_str(
+                "[[keymaps]]\nkey = \"ctrl+k\"\ncommand = \"-default\"\n",
+                false,
+            )
+            .unwrap();
+
+        let (keymaps, command_keymaps) = loader.finalize();
+        let bound = keymaps.get(&KeyPress::parse("ctrl+k")).unwrap();
+        assert_eq!(bound.len(), 1);
+        assert_eq!(bound[0].command, "plugin");
+        assert_eq!(bound[0].source.as_deref(), Some("lapce.plugin"));
+        assert!(command_keymaps.get("default").unwrap().is_empty());
+    }
 }
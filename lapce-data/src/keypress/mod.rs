@@ -1,5 +1,6 @@
 #![allow(clippy::module_inception)]
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -15,13 +16,15 @@
 use indexmap::IndexMap;
 use itertools::Itertools;
 use lapce_core::mode::{Mode, Modes};
+use lapce_proxy::plugin::wasi::find_all_volts;
 
 mod keypress;
 mod loader;
 
 use crate::command::{
-    lapce_internal_commands, CommandExecuted, CommandKind, LapceCommand,
-    LapceUICommand, LAPCE_COMMAND, LAPCE_UI_COMMAND,
+    lapce_internal_commands, toggle_setting_command, CommandExecuted, CommandKind,
+    LapceCommand, LapceUICommand, COMMAND_CATEGORIES, LAPCE_COMMAND,
+    LAPCE_UI_COMMAND,
 };
 use crate::config::{Config, LapceTheme};
 use crate::keypress::loader::KeyMapLoader;
@@ -35,6 +38,27 @@
 const DEFAULT_KEYMAPS_NONMACOS: &str =
     include_str!("../../../defaults/keymaps-nonmacos.toml");
 
+/// The names a "when" condition of a key binding is made of, each can be
+/// negated with `!` and they're combined with `&&` and `||`
+pub const CONDITIONS: &[&str] = &[
+    "completion_focus",
+    "diff_focus",
+    "editor_focus",
+    "global_search_focus",
+    "hover_focus",
+    "in_snippet",
+    "input_focus",
+    "list_focus",
+    "modal_focus",
+    "palette_focus",
+    "panel_focus",
+    "rename_focus",
+    "search_focus",
+    "settings_focus",
+    "source_control_focus",
+    "terminal_focus",
+];
+
 #[derive(PartialEq, Debug)]
 enum KeymapMatch {
     Full(String),
@@ -75,6 +99,42 @@ pub struct KeyMap {
     pub modes: Modes,
     pub when: Option<String>,
     pub command: String,
+    /// The id of the plugin that contributed the binding, `None` for the
+    /// default and user ones
+    pub source: Option<String>,
+}
+
+impl KeyMap {
+    /// The keymap as an entry of a keymaps file, which can be pasted into one
+    pub fn to_toml_snippet(&self) -> String {
+        let mut keymaps = toml_edit::ArrayOfTables::new();
+        keymaps.push(self.to_toml_table());
+        let mut document = toml_edit::Document::new();
+        document.insert("keymaps", toml_edit::Item::ArrayOfTables(keymaps));
+        document.to_string()
+    }
+
+    /// The keys of the keymap as they're labeled on the platform, e.g.
+    /// `Ctrl+K Ctrl+S`
+    pub fn label(&self) -> String {
+        self.key.iter().map(|k| k.labels().join("+")).join(" ")
+    }
+
+    fn to_toml_table(&self) -> toml_edit::Table {
+        let mut table = toml_edit::Table::new();
+        table.insert(
+            "key",
+            toml_edit::value(self.key.iter().map(|k| k.to_string()).join(" ")),
+        );
+        table.insert("command", toml_edit::value(self.command.clone()));
+        if !self.modes.is_empty() {
+            table.insert("mode", toml_edit::value(self.modes.to_string()));
+        }
+        if let Some(when) = self.when.as_ref() {
+            table.insert("when", toml_edit::value(when.clone()));
+        }
+        table
+    }
 }
 
 pub enum Alignment {
@@ -111,6 +171,12 @@ pub fn paint(
             if let Some(mut rect) = rect {
                 rect.x0 -= x_shift;
                 rect.x1 -= x_shift;
+                // Draw each key as a key cap
+                let rect = rect.to_rounded_rect(4.0);
+                ctx.fill(
+                    rect,
+                    config.get_color_unchecked(LapceTheme::EDITOR_BACKGROUND),
+                );
                 ctx.stroke(
                     rect,
                     config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
@@ -154,6 +220,10 @@ pub struct KeyPressData {
     pub filtered_commands_with_keymap: Arc<Vec<KeyMap>>,
     pub filtered_commands_without_keymap: Arc<Vec<LapceCommand>>,
     pub filter_pattern: String,
+    /// The bindings of keys another command is bound to as well
+    pub conflicting_keymaps: Arc<HashSet<KeyMap>>,
+    /// Whether the key bindings list only shows the conflicting bindings
+    pub conflicts_only: bool,
 
     count: Option<usize>,
 
@@ -174,6 +244,8 @@ pub fn new(config: &Config, event_sink: ExtEventSink) -> Self {
             filter_pattern: "".to_string(),
             filtered_commands_with_keymap: Arc::new(Vec::new()),
             filtered_commands_without_keymap: Arc::new(Vec::new()),
+            conflicting_keymaps: Arc::new(HashSet::new()),
+            conflicts_only: false,
             count: None,
             event_sink,
         };
@@ -206,6 +278,8 @@ fn load_commands(&mut self) {
             }
         }
 
+        self.conflicting_keymaps =
+            Arc::new(Self::conflicting_keymaps(&commands_with_keymap));
         self.commands_with_keymap = Arc::new(commands_with_keymap);
         self.commands_without_keymap = Arc::new(commands_without_keymap);
         if !self.filter_pattern.is_empty() {
@@ -213,6 +287,130 @@ fn load_commands(&mut self) {
         }
     }
 
+    /// The key bindings in effect as a Markdown cheat sheet, a table of the
+    /// commands and their keys for each category
+    pub fn cheat_sheet(&self) -> String {
+        Self::cheat_sheet_markdown(&self.commands, &self.commands_with_keymap)
+    }
+
+    /// The key bindings in effect as a keymaps file, with `changed_only`
+    /// only the bindings that aren't defaults and the unbinds of the defaults
+    /// that were removed. The bindings plugins add are left out.
+    pub fn export_keymaps(&self, config: &Config, changed_only: bool) -> String {
+        let current: Vec<KeyMap> = self
+            .command_keymaps
+            .values()
+            .flatten()
+            .filter(|keymap| keymap.source.is_none())
+            .cloned()
+            .collect();
+        let keymaps = if changed_only {
+            let mut loader = KeyMapLoader::new();
+            Self::load_default_keymaps(&mut loader, config.lapce.modal);
+            let (_, defaults) = loader.finalize();
+            let defaults: Vec<KeyMap> = defaults
+                .into_iter()
+                .flat_map(|(_, keymaps)| keymaps)
+                .collect();
+            Self::changed_keymaps(&defaults, &current)
+        } else {
+            current
+        };
+        Self::keymaps_toml(&keymaps)
+    }
+
+    /// The entries of a keymaps file turning the `defaults` into the
+    /// `current` bindings, the unbinds first
+    fn changed_keymaps(defaults: &[KeyMap], current: &[KeyMap]) -> Vec<KeyMap> {
+        let unbound = defaults
+            .iter()
+            .filter(|keymap| !current.contains(keymap))
+            .map(|keymap| KeyMap {
+                command: format!("-{}", keymap.command),
+                ..keymap.clone()
+            });
+        let bound = current
+            .iter()
+            .filter(|keymap| !defaults.contains(keymap))
+            .cloned();
+        unbound.chain(bound).collect()
+    }
+
+    fn keymaps_toml(keymaps: &[KeyMap]) -> String {
+        let mut array = toml_edit::ArrayOfTables::new();
+        for keymap in keymaps {
+            array.push(keymap.to_toml_table());
+        }
+        let mut document = toml_edit::Document::new();
+        document.insert("keymaps", toml_edit::Item::ArrayOfTables(array));
+        document.to_string()
+    }
+
+    fn cheat_sheet_markdown(
+        commands: &IndexMap<String, LapceCommand>,
+        keymaps: &[KeyMap],
+    ) -> String {
+        let mut text = "# Key Bindings\n".to_string();
+        for category in COMMAND_CATEGORIES {
+            let mut rows: IndexMap<String, Vec<String>> = IndexMap::new();
+            for keymap in keymaps {
+                let command = match commands.get(&keymap.command) {
+                    Some(command) if command.kind.category() == category => command,
+                    _ => continue,
+                };
+                let desc = command
+                    .kind
+                    .desc()
+                    .unwrap_or_else(|| command.kind.str())
+                    .to_string();
+                let keys = rows.entry(desc).or_default();
+                let label = keymap.label();
+                if !keys.contains(&label) {
+                    keys.push(label);
+                }
+            }
+            if rows.is_empty() {
+                continue;
+            }
+
+            text.push_str(&format!("\n## {category}\n\n"));
+            text.push_str("| Command | Keys |\n| --- | --- |\n");
+            for (desc, keys) in rows {
+                let escape = |s: &str| s.replace('|', "\\|");
+                text.push_str(&format!(
+                    "| {} | {} |\n",
+                    escape(&desc),
+                    keys.iter().map(|keys| escape(keys)).join(", ")
+                ));
+            }
+        }
+        text
+    }
+
+    /// The bindings of the same keys as a binding of another command, in a
+    /// mode they share and under the same condition
+    fn conflicting_keymaps(keymaps: &[KeyMap]) -> HashSet<KeyMap> {
+        let mut conflicts = HashSet::new();
+        for (_, keymaps) in keymaps.iter().into_group_map_by(|keymap| &keymap.key) {
+            for (i, a) in keymaps.iter().enumerate() {
+                for b in &keymaps[i + 1..] {
+                    let modes = a.modes.is_empty()
+                        || b.modes.is_empty()
+                        || a.modes.intersects(b.modes);
+                    if a.command != b.command
+                        && modes
+                        && a.when.as_deref().map(str::trim)
+                            == b.when.as_deref().map(str::trim)
+                    {
+                        conflicts.insert((*a).clone());
+                        conflicts.insert((*b).clone());
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
     fn run_command<T: KeyPressFocus>(
         &self,
         ctx: &mut EventCtx,
@@ -222,7 +420,12 @@ fn run_command<T: KeyPressFocus>(
         focus: &mut T,
         env: &Env,
     ) -> CommandExecuted {
-        if let Some(cmd) = self.commands.get(command) {
+        let cmd = self
+            .commands
+            .get(command)
+            .cloned()
+            .or_else(|| toggle_setting_command(command));
+        if let Some(cmd) = cmd.as_ref() {
             match cmd.kind {
                 CommandKind::Workbench(_) => {
                     if !focus.focus_only() {
@@ -276,7 +479,7 @@ fn handle_count<T: KeyPressFocus>(
         false
     }
 
-    fn get_key_modifiers(key_event: &KeyEvent) -> Modifiers {
+    pub fn get_key_modifiers(key_event: &KeyEvent) -> Modifiers {
         // We only care about some modifiers
         let mods = (Modifiers::ALT
             | Modifiers::CONTROL
@@ -412,15 +615,16 @@ pub fn key_down<T: KeyPressFocus>(
         false
     }
 
-    fn match_keymap<T: KeyPressFocus>(
+    /// The keymaps that apply in the context of `check` and start with the
+    /// key presses, the ones defined last take precedence
+    pub fn matching_keymaps<T: KeyPressFocus>(
         &self,
         keypresses: &[KeyPress],
         check: &T,
-    ) -> KeymapMatch {
+    ) -> Vec<&KeyMap> {
         let keypresses: Vec<KeyPress> =
             keypresses.iter().map(KeyPress::to_lowercase).collect();
-        let matches = self
-            .keymaps
+        self.keymaps
             .get(&keypresses)
             .map(|keymaps| {
                 keymaps
@@ -446,7 +650,17 @@ fn match_keymap<T: KeyPressFocus>(
                     })
                     .collect()
             })
-            .unwrap_or_else(Vec::new);
+            .unwrap_or_else(Vec::new)
+    }
+
+    fn match_keymap<T: KeyPressFocus>(
+        &self,
+        keypresses: &[KeyPress],
+        check: &T,
+    ) -> KeymapMatch {
+        let matches = self.matching_keymaps(keypresses, check);
+        let keypresses: Vec<KeyPress> =
+            keypresses.iter().map(KeyPress::to_lowercase).collect();
 
         if matches.is_empty() {
             KeymapMatch::None
@@ -493,6 +707,28 @@ fn check_one_condition<T: KeyPressFocus>(
         }
     }
 
+    /// Why `condition` isn't a valid "when" condition, if it isn't
+    pub fn condition_error(condition: &str) -> Option<String> {
+        fn one_condition_error(condition: &str) -> Option<String> {
+            let trimmed = condition.trim();
+            let name = trimmed.strip_prefix('!').unwrap_or(trimmed);
+            if name.is_empty() {
+                Some("Expected a condition".to_string())
+            } else if !CONDITIONS.contains(&name) {
+                Some(format!("Unknown condition {name}"))
+            } else {
+                None
+            }
+        }
+
+        match Condition::parse_first(condition) {
+            Condition::Single(condition) => one_condition_error(condition),
+            Condition::Or(left, right) | Condition::And(left, right) => {
+                one_condition_error(left).or_else(|| Self::condition_error(right))
+            }
+        }
+    }
+
     fn get_file_array() -> Option<toml_edit::ArrayOfTables> {
         let path = Self::file()?;
         let content = std::fs::read_to_string(path).ok()?;
@@ -514,14 +750,34 @@ pub fn filter_commands(&mut self, pattern: &str) {
 
         std::thread::spawn(move || {
             let matcher = SkimMatcherV2::default().ignore_case();
+            // "command:<id>" only matches the command with exactly that id
+            let command_id = pattern.strip_prefix("command:").map(|id| id.trim());
+            // "plugin:<id>" only matches the key bindings the plugin added
+            let plugin_id = pattern.strip_prefix("plugin:").map(|id| id.trim());
+            let score = |cmd: &LapceCommand| match command_id {
+                Some(id) => (cmd.kind.str() == id).then_some(0),
+                None => {
+                    let text = cmd.kind.desc().unwrap_or_else(|| cmd.kind.str());
+                    matcher.fuzzy_match(text, &pattern)
+                }
+            };
+            // A binding matches by its chord too, e.g. "ctrl+shift+p"
+            let chord_score = |keymap: &KeyMap| match command_id {
+                Some(_) => None,
+                None => matcher.fuzzy_match(
+                    &keymap.key.iter().map(|k| k.to_string()).join(" "),
+                    &pattern,
+                ),
+            };
 
             let filtered_commands_with_keymap: Vec<KeyMap> = commands_with_keymap
                 .iter()
                 .filter_map(|i| {
+                    if let Some(id) = plugin_id {
+                        return (i.source.as_deref() == Some(id)).then_some((i, 0));
+                    }
                     let cmd = commands.get(&i.command).unwrap();
-                    let text = cmd.kind.desc().unwrap_or_else(|| cmd.kind.str());
-
-                    matcher.fuzzy_match(text, &pattern).map(|score| (i, score))
+                    score(cmd).max(chord_score(i)).map(|score| (i, score))
                 })
                 .sorted_by_key(|(_i, score)| -*score)
                 .map(|(i, _)| i.clone())
@@ -530,11 +786,8 @@ pub fn filter_commands(&mut self, pattern: &str) {
             let filtered_commands_without_keymap: Vec<LapceCommand> =
                 commands_without_keymap
                     .iter()
-                    .filter_map(|i| {
-                        let text = i.kind.desc().unwrap_or_else(|| i.kind.str());
-
-                        matcher.fuzzy_match(text, &pattern).map(|score| (i, score))
-                    })
+                    .filter(|_| plugin_id.is_none())
+                    .filter_map(|i| score(i).map(|score| (i, score)))
                     .sorted_by_key(|(_i, score)| -*score)
                     .map(|(i, _)| i.clone())
                     .collect();
@@ -551,9 +804,12 @@ pub fn filter_commands(&mut self, pattern: &str) {
         });
     }
 
-    pub fn update_file(keymap: &KeyMap, keys: &[KeyPress]) -> Option<()> {
-        let mut array = Self::get_file_array().unwrap_or_default();
-        let index = array.iter().position(|value| {
+    /// The index of the entry of `keymap` in the keymaps file
+    fn file_position(
+        array: &toml_edit::ArrayOfTables,
+        keymap: &KeyMap,
+    ) -> Option<usize> {
+        array.iter().position(|value| {
             Some(keymap.command.as_str())
                 == value.get("command").and_then(|c| c.as_str())
                 && keymap.when.as_deref()
@@ -564,7 +820,12 @@ pub fn update_file(keymap: &KeyMap, keys: &[KeyPress]) -> Option<()> {
                         .get("key")
                         .and_then(|v| v.as_str())
                         .map(KeyPress::parse)
-        });
+        })
+    }
+
+    pub fn update_file(keymap: &KeyMap, keys: &[KeyPress]) -> Option<()> {
+        let mut array = Self::get_file_array().unwrap_or_default();
+        let index = Self::file_position(&array, keymap);
 
         if let Some(index) = index {
             if !keys.is_empty() {
@@ -633,21 +894,46 @@ pub fn update_file(keymap: &KeyMap, keys: &[KeyPress]) -> Option<()> {
         None
     }
 
+    /// Change the "when" condition of `keymap`, `None` removes it. A binding
+    /// that isn't in the keymaps file is unbound there and bound again with
+    /// the new condition.
+    pub fn update_condition_file(keymap: &KeyMap, when: Option<&str>) -> Option<()> {
+        let mut array = Self::get_file_array().unwrap_or_default();
+        if let Some(index) = Self::file_position(&array, keymap) {
+            let table = array.get_mut(index)?;
+            match when {
+                Some(when) => {
+                    table.insert("when", toml_edit::value(when.to_string()));
+                }
+                None => {
+                    table.remove("when");
+                }
+            }
+        } else {
+            let mut table = keymap.to_toml_table();
+            table
+                .insert("command", toml_edit::value(format!("-{}", keymap.command)));
+            array.push(table);
+            array.push(
+                KeyMap {
+                    when: when.map(|when| when.to_string()),
+                    ..keymap.clone()
+                }
+                .to_toml_table(),
+            );
+        }
+
+        let mut table = toml_edit::Document::new();
+        table.insert("keymaps", toml_edit::Item::ArrayOfTables(array));
+        let path = Self::file()?;
+        std::fs::write(path, table.to_string().as_bytes()).ok()
+    }
+
     pub fn file() -> Option<PathBuf> {
         Config::keymaps_file()
     }
 
-    #[allow(clippy::type_complexity)]
-    fn get_keymaps(
-        config: &Config,
-    ) -> Result<(
-        IndexMap<Vec<KeyPress>, Vec<KeyMap>>,
-        IndexMap<String, Vec<KeyMap>>,
-    )> {
-        let is_modal = config.lapce.modal;
-
-        let mut loader = KeyMapLoader::new();
-
+    fn load_default_keymaps(loader: &mut KeyMapLoader, is_modal: bool) {
         if let Err(err) = loader.load_from_str(DEFAULT_KEYMAPS_COMMON, is_modal) {
             log::error!("Failed to load common defaults: {err}");
         }
@@ -661,6 +947,33 @@ fn get_keymaps(
         if let Err(err) = loader.load_from_str(os_keymaps, is_modal) {
             log::error!("Failed to load OS defaults: {err}");
         }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn get_keymaps(
+        config: &Config,
+    ) -> Result<(
+        IndexMap<Vec<KeyPress>, Vec<KeyMap>>,
+        IndexMap<String, Vec<KeyMap>>,
+    )> {
+        let is_modal = config.lapce.modal;
+
+        let mut loader = KeyMapLoader::new();
+        Self::load_default_keymaps(&mut loader, is_modal);
+
+        // Between the defaults and the user's keymaps, so the user can still
+        // unbind what plugins add
+        for meta in find_all_volts() {
+            for path in meta.keymaps.iter().flatten() {
+                if let Ok(content) = std::fs::read_to_string(path) {
+                    if let Err(err) =
+                        loader.load_plugin_keymaps(&content, is_modal, &meta.id())
+                    {
+                        log::error!("Failed to load from {path:?}: {err}");
+                    }
+                }
+            }
+        }
 
         if let Some(path) = Self::file() {
             if let Ok(content) = std::fs::read_to_string(&path) {
@@ -740,8 +1053,14 @@ fn parse_first(condition: &'a str) -> Self {
 
 #[cfg(test)]
 mod test {
-    use crate::keypress::{Condition, KeyPressData, KeyPressFocus};
-    use lapce_core::mode::Mode;
+    use crate::command::{
+        lapce_internal_commands, toggle_setting_command, CommandKind,
+        LapceWorkbenchCommand, COMMAND_CATEGORIES,
+    };
+    use crate::keypress::{
+        Condition, KeyMap, KeyPress, KeyPressData, KeyPressFocus,
+    };
+    use lapce_core::mode::{Mode, Modes};
 
     struct MockFocus {
         accepted_conditions: &'static [&'static str],
@@ -822,4 +1141,139 @@ fn test_check_condition() {
             );
         }
     }
+
+    #[test]
+    fn test_cheat_sheet_markdown() {
+        let commands = lapce_internal_commands();
+        let keymap = |key: &str, command: &str, modes: Modes| KeyMap {
+            key: KeyPress::parse(key),
+            modes,
+            when: None,
+            command: command.to_string(),
+            source: None,
+        };
+        let keymaps = [
+            keymap("ctrl+c", "clipboard_copy", Modes::INSERT),
+            keymap("ctrl+c", "clipboard_copy", Modes::NORMAL),
+            keymap("ctrl+insert", "clipboard_copy", Modes::empty()),
+            keymap("ctrl+k ctrl+s", "open_keyboard_shortcuts", Modes::empty()),
+        ];
+        let text = KeyPressData::cheat_sheet_markdown(&commands, &keymaps);
+        // Commands without a description are listed by their name
+        assert!(text.contains("| clipboard_copy | Ctrl+C, Ctrl+Insert |"));
+        assert!(text.contains("| Open Keyboard Shortcuts | Ctrl+K Ctrl+S |"));
+        assert!(
+            text.find("## Editing").unwrap() < text.find("## Settings").unwrap()
+        );
+        assert!(!text.contains("## Terminal"));
+    }
+
+    #[test]
+    fn test_conflicting_keymaps() {
+        let keymap =
+            |key: &str, command: &str, modes: Modes, when: Option<&str>| KeyMap {
+                key: KeyPress::parse(key),
+                modes,
+                when: when.map(|when| when.to_string()),
+                command: command.to_string(),
+                source: None,
+            };
+        let keymaps = [
+            keymap("ctrl+a", "select_all", Modes::empty(), None),
+            keymap("ctrl+a", "go_to_line_default", Modes::empty(), None),
+            keymap("ctrl+b", "toggle_explorer_visual", Modes::NORMAL, None),
+            keymap("ctrl+b", "toggle_panel_visual", Modes::INSERT, None),
+            keymap("ctrl+c", "clipboard_copy", Modes::empty(), None),
+            keymap("ctrl+c", "list_select", Modes::empty(), Some("list_focus")),
+            keymap("ctrl+d", "duplicate", Modes::empty(), None),
+            keymap("ctrl+d", "duplicate", Modes::NORMAL, None),
+        ];
+        let conflicts = KeyPressData::conflicting_keymaps(&keymaps);
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.contains(&keymaps[0]));
+        assert!(conflicts.contains(&keymaps[1]));
+    }
+
+    #[test]
+    fn test_condition_error() {
+        assert_eq!(KeyPressData::condition_error("list_focus"), None);
+        assert_eq!(
+            KeyPressData::condition_error("!in_snippet && !completion_focus"),
+            None
+        );
+        assert_eq!(
+            KeyPressData::condition_error("list_focus || !modal_focus"),
+            None
+        );
+        assert_eq!(
+            KeyPressData::condition_error("list_focus && foo"),
+            Some("Unknown condition foo".to_string())
+        );
+        assert_eq!(
+            KeyPressData::condition_error("list_focus ||"),
+            Some("Expected a condition".to_string())
+        );
+        assert_eq!(
+            KeyPressData::condition_error("!"),
+            Some("Expected a condition".to_string())
+        );
+    }
+
+    #[test]
+    fn test_command_categories() {
+        let commands = lapce_internal_commands();
+        let category = |name: &str| commands.get(name).unwrap().kind.category();
+        assert_eq!(category("toggle_terminal_focus"), "Terminal");
+        assert_eq!(category("center_of_window"), "Navigation");
+        assert_eq!(category("next_window_tab"), "Layout");
+        assert_eq!(category("open_settings_file"), "Settings");
+        assert_eq!(category("format_document"), "Editing");
+        assert_eq!(category("quit"), "General");
+        assert!(commands
+            .values()
+            .all(|cmd| COMMAND_CATEGORIES.contains(&cmd.kind.category())));
+    }
+
+    #[test]
+    fn test_changed_keymaps() {
+        let keymap = |key: &str, command: &str| KeyMap {
+            key: KeyPress::parse(key),
+            modes: Modes::empty(),
+            when: None,
+            command: command.to_string(),
+            source: None,
+        };
+        let defaults = [
+            keymap("ctrl+c", "clipboard_copy"),
+            keymap("ctrl+s", "save"),
+            keymap("ctrl+p", "palette"),
+        ];
+        let current = [
+            keymap("ctrl+c", "clipboard_copy"),
+            keymap("ctrl+alt+s", "save"),
+            keymap("ctrl+p", "palette"),
+        ];
+        let changed = KeyPressData::changed_keymaps(&defaults, &current);
+        assert_eq!(
+            changed,
+            vec![keymap("ctrl+s", "-save"), keymap("ctrl+alt+s", "save")]
+        );
+        let text = KeyPressData::keymaps_toml(&changed);
+        assert_eq!(text.matches("[[keymaps]]").count(), 2);
+        assert!(text.find("\"-save\"").unwrap() < text.find("\"save\"").unwrap());
+        assert!(KeyPressData::changed_keymaps(&defaults, &defaults).is_empty());
+    }
+
+    #[test]
+    fn test_toggle_setting_command() {
+        let command =
+            toggle_setting_command("toggle_setting:editor.show-tab").unwrap();
+        assert_eq!(
+            command.kind,
+            CommandKind::Workbench(LapceWorkbenchCommand::ToggleSetting)
+        );
+        assert_eq!(command.data, Some(serde_json::json!("editor.show-tab")));
+        assert!(toggle_setting_command("toggle_setting").is_none());
+        assert!(toggle_setting_command("editor.show-tab").is_none());
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use druid::{Command, Data, EventCtx, Target, WidgetId};
+use druid::{Command, Data, EventCtx, Point, Target, WidgetId};
 use lapce_core::{command::FocusCommand, movement::Movement};
 
 use crate::{
@@ -35,6 +35,10 @@ pub struct ListData<T: Clone, D: Data> {
     /// Defaults to the editor line height if not set
     pub line_height: Option<usize>,
 
+    /// Whether hovering an item selects it, for the containing widget to
+    /// preview it
+    pub preview_on_hover: bool,
+
     // These should be filled whenever you call into the `List` widget
     pub config: Arc<Config>,
 }
@@ -51,6 +55,7 @@ pub fn new(
             selected_index: 0,
             max_displayed_items: 15,
             line_height: None,
+            preview_on_hover: false,
             config,
         }
     }
@@ -179,6 +184,14 @@ pub fn select(&self, ctx: &mut EventCtx) {
         ));
     }
 
+    pub fn context_menu(&self, ctx: &mut EventCtx, point: Point) {
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ListItemContextMenu(point),
+            Target::Widget(self.parent),
+        ));
+    }
+
     pub fn current_selected_item(&self) -> Option<&T> {
         self.items.get(self.selected_index)
     }
@@ -194,6 +207,7 @@ fn same(&self, other: &Self) -> bool {
             && self.selected_index.same(&other.selected_index)
             && self.max_displayed_items.same(&other.max_displayed_items)
             && self.line_height.same(&other.line_height)
+            && self.preview_on_hover.same(&other.preview_on_hover)
     }
 }
 impl<T: Clone + PartialEq + 'static, D: Data> GetConfig for ListData<T, D> {
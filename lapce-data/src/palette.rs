@@ -27,12 +27,13 @@
 use crate::{
     command::LAPCE_UI_COMMAND,
     command::{CommandExecuted, LAPCE_COMMAND},
-    command::{LapceCommand, LapceUICommand},
+    command::{LapceCommand, LapceUICommand, LapceWorkbenchCommand},
     config::Config,
     data::{FocusArea, LapceMainSplitData, LapceTabData},
     find::Find,
     keypress::{KeyPressData, KeyPressFocus},
     proxy::LapceProxy,
+    settings::SettingChange,
     terminal::TerminalSplitData,
 };
 
@@ -47,8 +48,28 @@ pub enum PaletteType {
     Command,
     Reference,
     Theme,
+    /// The themes with their colors, the applied theme highlighted
+    ThemeGallery,
     SshHost,
     Language,
+    /// Typing the new name of the current theme's file
+    ThemeName(ThemeFileAction),
+    /// Typing a theme color and the color to replace it with
+    ThemeColorReplacement,
+    /// The settings backups, newest first, picking one previews restoring it
+    SettingsBackup,
+    /// The settings whose defaults changed since the version run before
+    ChangedDefaults,
+    /// The boolean settings, picking one toggles it
+    ToggleSetting,
+    ThemeColors,
+}
+
+/// What's done with the file of the current theme once its new name is typed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeFileAction {
+    Rename,
+    Duplicate,
 }
 
 impl PaletteType {
@@ -63,8 +84,15 @@ fn string(&self) -> String {
             PaletteType::Command => ":".to_string(),
             PaletteType::Reference => "".to_string(),
             PaletteType::Theme => "".to_string(),
+            PaletteType::ThemeGallery => "".to_string(),
             PaletteType::SshHost => "".to_string(),
             PaletteType::Language => "".to_string(),
+            PaletteType::ThemeName(_) => "".to_string(),
+            PaletteType::ThemeColorReplacement => "".to_string(),
+            PaletteType::SettingsBackup => "".to_string(),
+            PaletteType::ChangedDefaults => "".to_string(),
+            PaletteType::ToggleSetting => "".to_string(),
+            PaletteType::ThemeColors => "".to_string(),
         }
     }
 
@@ -86,7 +114,14 @@ fn get_palette_type(current_type: &PaletteType, input: &str) -> PaletteType {
             PaletteType::Reference
             | PaletteType::SshHost
             | PaletteType::Theme
-            | PaletteType::Language => {
+            | PaletteType::ThemeGallery
+            | PaletteType::Language
+            | PaletteType::ThemeName(_)
+            | PaletteType::ThemeColorReplacement
+            | PaletteType::SettingsBackup
+            | PaletteType::ChangedDefaults
+            | PaletteType::ToggleSetting
+            | PaletteType::ThemeColors => {
                 return current_type.clone();
             }
             _ => (),
@@ -143,9 +178,35 @@ pub enum PaletteItemContent {
     Command(LapceCommand),
     Theme(String),
     Language(String),
+    /// A backup folder, with the time it was made
+    SettingsBackup(PathBuf, String),
+    /// A setting whose default changed, with the value the settings file
+    /// keeps it at instead of the new default
+    ChangedDefault(SettingChange, Option<serde_json::Value>),
+    /// A boolean setting as "table.key", with its value
+    ToggleSetting(String, bool),
+    /// A theme color, with its kind like "theme.ui" and the value it's set to
+    ThemeColor {
+        kind: String,
+        key: String,
+        value: String,
+    },
 }
 
 impl PaletteItemContent {
+    /// The group the item is listed in, the filtered items keep the order of
+    /// the groups
+    fn group(&self) -> usize {
+        match self {
+            PaletteItemContent::ThemeColor { kind, .. } => match kind.as_str() {
+                "theme.base" => 0,
+                "theme.syntax" => 1,
+                _ => 2,
+            },
+            _ => 0,
+        }
+    }
+
     fn select(
         &self,
         ctx: &mut EventCtx,
@@ -244,6 +305,62 @@ fn select(
                     ))
                 }
             }
+            PaletteItemContent::SettingsBackup(path, _) => {
+                if !preview {
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::PreviewSettingsBackup,
+                            ),
+                            data: Some(serde_json::json!(path)),
+                        },
+                        Target::Auto,
+                    ));
+                }
+            }
+            PaletteItemContent::ChangedDefault(change, Some(_)) => {
+                // Adopting the new default is no longer setting the value
+                if !preview {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ResetSettingsFile(
+                            change.table.clone(),
+                            change.key.clone(),
+                        ),
+                        Target::Auto,
+                    ));
+                }
+            }
+            PaletteItemContent::ChangedDefault(_, None) => {}
+            PaletteItemContent::ToggleSetting(id, _) => {
+                if !preview {
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::ToggleSetting,
+                            ),
+                            data: Some(serde_json::json!(id)),
+                        },
+                        Target::Auto,
+                    ));
+                }
+            }
+            PaletteItemContent::ThemeColor { kind, key, .. } => {
+                if !preview {
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::RevealSettingInPanel,
+                            ),
+                            data: Some(serde_json::json!((kind, key))),
+                        },
+                        Target::Auto,
+                    ));
+                }
+            }
             PaletteItemContent::Command(command) => {
                 if !preview {
                     ctx.submit_command(Command::new(
@@ -338,6 +455,9 @@ pub struct PaletteListData {
     /// Should only be `None` when it hasn't been updated initially  
     /// We need this just for some rendering, and not editing it.
     pub workspace: Option<Arc<LapceWorkspace>>,
+    /// The theme that's applied while the theme gallery is open, the theme
+    /// items show their colors and this one is highlighted
+    pub theme_gallery: Option<String>,
 }
 
 #[derive(Clone)]
@@ -359,6 +479,9 @@ pub struct PaletteData {
     pub total_items: im::Vector<PaletteItem>,
     pub preview_editor: WidgetId,
     pub input_editor: WidgetId,
+    /// The theme in use when the theme palette was opened, the themes it
+    /// previews are reverted to it unless one is picked
+    pub theme_before_preview: Option<String>,
 }
 
 impl KeyPressFocus for PaletteViewData {
@@ -422,8 +545,14 @@ pub fn new(config: Arc<Config>, proxy: Arc<LapceProxy>) -> Self {
         let widget_id = WidgetId::next();
         let scroll_id = WidgetId::next();
         let preview_editor = WidgetId::next();
-        let mut list_data =
-            ListData::new(config, widget_id, PaletteListData { workspace: None });
+        let mut list_data = ListData::new(
+            config,
+            widget_id,
+            PaletteListData {
+                workspace: None,
+                theme_gallery: None,
+            },
+        );
         // TODO: Make these configurable
         list_data.line_height = Some(25);
         list_data.max_displayed_items = 15;
@@ -443,6 +572,7 @@ pub fn new(config: Arc<Config>, proxy: Arc<LapceProxy>) -> Self {
             total_items: im::Vector::new(),
             preview_editor,
             input_editor: WidgetId::next(),
+            theme_before_preview: None,
         }
     }
 
@@ -473,8 +603,15 @@ pub fn get_input(&self) -> &str {
             PaletteType::File => &self.input,
             PaletteType::Reference => &self.input,
             PaletteType::Theme => &self.input,
+            PaletteType::ThemeGallery => &self.input,
             PaletteType::Language => &self.input,
             PaletteType::SshHost => &self.input,
+            PaletteType::ThemeName(_) => &self.input,
+            PaletteType::ThemeColorReplacement => &self.input,
+            PaletteType::SettingsBackup => &self.input,
+            PaletteType::ChangedDefaults => &self.input,
+            PaletteType::ToggleSetting => &self.input,
+            PaletteType::ThemeColors => &self.input,
             PaletteType::Line => &self.input[1..],
             PaletteType::DocumentSymbol => &self.input[1..],
             PaletteType::WorkspaceSymbol => &self.input[1..],
@@ -494,6 +631,17 @@ pub fn cancel(&mut self, ctx: &mut EventCtx) {
         palette.palette_type = PaletteType::File;
         palette.total_items.clear();
         palette.list_data.clear_items();
+        palette.list_data.preview_on_hover = false;
+        palette.list_data.data.theme_gallery = None;
+        if let Some(theme) = palette.theme_before_preview.take() {
+            if theme != self.config.lapce.color_theme {
+                ctx.submit_command(Command::new(
+                    LAPCE_UI_COMMAND,
+                    LapceUICommand::SetTheme(theme, true),
+                    Target::Auto,
+                ));
+            }
+        }
         if let Some(active) = *self.main_split.active_tab {
             ctx.submit_command(Command::new(
                 LAPCE_UI_COMMAND,
@@ -560,6 +708,17 @@ pub fn run(
         palette.list_data.clear_items();
         palette.run_id = Uuid::new_v4().to_string();
         palette.cursor = palette.input.len();
+        // Hovering a theme previews it like selecting it does
+        let themes = matches!(
+            palette.palette_type,
+            PaletteType::Theme | PaletteType::ThemeGallery
+        );
+        palette.list_data.preview_on_hover = themes;
+        palette.theme_before_preview =
+            themes.then(|| self.config.lapce.color_theme.clone());
+        palette.list_data.data.theme_gallery = (palette.palette_type
+            == PaletteType::ThemeGallery)
+            .then(|| self.config.lapce.color_theme.clone());
 
         if let Some(active_editor_content) =
             self.main_split.active_editor().map(|e| e.content.clone())
@@ -591,6 +750,20 @@ pub fn run(
                 self.get_workspaces(ctx);
             }
             PaletteType::Reference => {}
+            PaletteType::ThemeName(_) => {}
+            PaletteType::ThemeColorReplacement => {}
+            PaletteType::SettingsBackup => {
+                self.get_settings_backups(ctx);
+            }
+            PaletteType::ChangedDefaults => {
+                self.get_changed_defaults(ctx);
+            }
+            PaletteType::ToggleSetting => {
+                self.get_boolean_settings(ctx);
+            }
+            PaletteType::ThemeColors => {
+                self.get_theme_colors(ctx);
+            }
             PaletteType::SshHost => {
                 self.get_ssh_hosts(ctx);
             }
@@ -600,7 +773,7 @@ pub fn run(
             PaletteType::Command => {
                 self.get_commands(ctx);
             }
-            PaletteType::Theme => {
+            PaletteType::Theme | PaletteType::ThemeGallery => {
                 let config = self.config.clone();
                 self.get_themes(ctx, &config);
                 self.preselect_matching(ctx, &config.theme.name);
@@ -649,8 +822,15 @@ pub fn delete_to_beginning_of_line(&mut self, ctx: &mut EventCtx) {
             PaletteType::File => 0,
             PaletteType::Reference => 0,
             PaletteType::Theme => 0,
+            PaletteType::ThemeGallery => 0,
             PaletteType::Language => 0,
             PaletteType::SshHost => 0,
+            PaletteType::ThemeName(_) => 0,
+            PaletteType::ThemeColorReplacement => 0,
+            PaletteType::SettingsBackup => 0,
+            PaletteType::ChangedDefaults => 0,
+            PaletteType::ToggleSetting => 0,
+            PaletteType::ThemeColors => 0,
             PaletteType::Line => 1,
             PaletteType::DocumentSymbol => 1,
             PaletteType::WorkspaceSymbol => 1,
@@ -697,11 +877,45 @@ pub fn select(&mut self, ctx: &mut EventCtx) {
             ));
         }
         let palette = Arc::make_mut(&mut self.palette);
+        // The picked theme stays, the previewed ones aren't reverted
+        if palette.list_data.current_selected_item().is_some() {
+            palette.theme_before_preview = None;
+        }
         if let Some(item) = palette.list_data.current_selected_item() {
             if item.content.select(ctx, false, palette.preview_editor) {
                 self.cancel(ctx);
             }
         } else {
+            if let PaletteType::ThemeName(action) = self.palette.palette_type {
+                let command = match action {
+                    ThemeFileAction::Rename => {
+                        LapceWorkbenchCommand::ApplyRenameTheme
+                    }
+                    ThemeFileAction::Duplicate => {
+                        LapceWorkbenchCommand::ApplyDuplicateTheme
+                    }
+                };
+                ctx.submit_command(Command::new(
+                    LAPCE_COMMAND,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(command),
+                        data: Some(serde_json::json!(self.palette.get_input())),
+                    },
+                    Target::Auto,
+                ));
+            }
+            if self.palette.palette_type == PaletteType::ThemeColorReplacement {
+                ctx.submit_command(Command::new(
+                    LAPCE_COMMAND,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::ReplaceThemeColor,
+                        ),
+                        data: Some(serde_json::json!(self.palette.get_input())),
+                    },
+                    Target::Auto,
+                ));
+            }
             if self.palette.palette_type == PaletteType::SshHost {
                 let input = self.palette.get_input();
                 let splits = input.split('@').collect::<Vec<&str>>();
@@ -883,6 +1097,82 @@ fn get_themes(&mut self, _ctx: &mut EventCtx, config: &Config) {
             .collect();
     }
 
+    fn get_settings_backups(&mut self, _ctx: &mut EventCtx) {
+        let palette = Arc::make_mut(&mut self.palette);
+        palette.total_items = Config::settings_backups()
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?.to_string();
+                let time =
+                    chrono::NaiveDateTime::parse_from_str(&name, "%Y%m%d-%H%M%S")
+                        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or(name);
+                Some(PaletteItem {
+                    content: PaletteItemContent::SettingsBackup(path, time.clone()),
+                    filter_text: time,
+                    score: 0,
+                    indices: vec![],
+                })
+            })
+            .collect();
+    }
+
+    fn get_changed_defaults(&mut self, _ctx: &mut EventCtx) {
+        let settings_file = Config::settings_file();
+        let palette = Arc::make_mut(&mut self.palette);
+        palette.total_items = Config::changed_defaults()
+            .into_iter()
+            .map(|change| {
+                let name = format!("{}.{}", change.table, change.key);
+                let value = settings_file.as_ref().and_then(|path| {
+                    Config::file_setting_value_at(path, &change.table, &change.key)
+                });
+                PaletteItem {
+                    content: PaletteItemContent::ChangedDefault(change, value),
+                    filter_text: name,
+                    score: 0,
+                    indices: vec![],
+                }
+            })
+            .collect();
+    }
+
+    fn get_boolean_settings(&mut self, _ctx: &mut EventCtx) {
+        let config = self.config.clone();
+        let palette = Arc::make_mut(&mut self.palette);
+        palette.total_items = Config::setting_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let (table, key) = id.split_once('.')?;
+                if config.locked_by_policy(table, key) {
+                    return None;
+                }
+                let value = config.setting_value(table, key)?.as_bool()?;
+                Some(PaletteItem {
+                    content: PaletteItemContent::ToggleSetting(id.clone(), value),
+                    filter_text: id,
+                    score: 0,
+                    indices: vec![],
+                })
+            })
+            .collect();
+    }
+
+    fn get_theme_colors(&mut self, _ctx: &mut EventCtx) {
+        let config = self.config.clone();
+        let palette = Arc::make_mut(&mut self.palette);
+        palette.total_items = config
+            .theme_color_index()
+            .into_iter()
+            .map(|(kind, key, value)| PaletteItem {
+                filter_text: key.clone(),
+                content: PaletteItemContent::ThemeColor { kind, key, value },
+                score: 0,
+                indices: vec![],
+            })
+            .collect();
+    }
+
     fn get_languages(&mut self, _ctx: &mut EventCtx) {
         let palette = Arc::make_mut(&mut self.palette);
         let mut langs = LapceLanguage::languages();
@@ -1200,8 +1490,14 @@ fn filter_items(
                 }
             })
             .collect();
-        items
-            .sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Less));
+        // The theme colors stay grouped by kind, the best matches first in
+        // each group
+        items.sort_by(|a, b| {
+            a.content
+                .group()
+                .cmp(&b.content.group())
+                .then(b.score.partial_cmp(&a.score).unwrap_or(Ordering::Less))
+        });
         items
     }
 }
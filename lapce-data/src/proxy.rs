@@ -281,6 +281,20 @@ pub fn new(
         proxy
     }
 
+    /// A proxy that isn't connected to anything, for the tests of the
+    /// documents that never reach the proxy
+    #[cfg(test)]
+    pub(crate) fn detached(tab_id: WidgetId, event_sink: ExtEventSink) -> Self {
+        let (term_tx, _) = crossbeam_channel::unbounded();
+        Self {
+            tab_id,
+            proxy_rpc: ProxyRpcHandler::new(),
+            core_rpc: CoreRpcHandler::new(),
+            term_tx,
+            event_sink,
+        }
+    }
+
     fn start(
         &self,
         workspace: LapceWorkspace,
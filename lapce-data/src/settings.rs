@@ -1,6 +1,14 @@
-use std::sync::Arc;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use druid::{Command, Env, EventCtx, Modifiers, Target, WidgetId};
+use druid::{
+    Color, Command, Env, EventCtx, ExtEventSink, Modifiers, Target, Vec2, WidgetId,
+};
+use itertools::Itertools;
 use lapce_core::{
     command::{EditCommand, FocusCommand, MoveCommand},
     mode::Mode,
@@ -8,8 +16,11 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    command::{CommandExecuted, CommandKind, LapceUICommand, LAPCE_UI_COMMAND},
-    config::Config,
+    command::{
+        CommandExecuted, CommandKind, LapceUICommand, LAPCE_COMMAND,
+        LAPCE_UI_COMMAND,
+    },
+    config::{eval_color, Config},
     data::LapceMainSplitData,
     keypress::KeyPressFocus,
     split::SplitDirection,
@@ -21,6 +32,879 @@ pub enum SettingsValueKind {
     Integer,
     Float,
     Bool,
+    /// Any value, written as JSON
+    Json,
+    /// The key or the value of an entry of a string map, the entries are
+    /// stored together as the map
+    MapEntry,
+}
+
+impl SettingsValueKind {
+    /// The kind of input a setting holding the value is edited with
+    pub fn of(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Number(n) if n.is_f64() => SettingsValueKind::Float,
+            serde_json::Value::Number(_) => SettingsValueKind::Integer,
+            serde_json::Value::String(_) => SettingsValueKind::String,
+            serde_json::Value::Bool(_) => SettingsValueKind::Bool,
+            _ => SettingsValueKind::Json,
+        }
+    }
+}
+
+/// Parse the raw input of a settings value into what gets written to the
+/// settings file, or a short message explaining why it was rejected.
+pub fn parse_settings_value(
+    kind: &SettingsValueKind,
+    parent: &str,
+    content: &str,
+) -> Result<serde_json::Value, String> {
+    match kind {
+        SettingsValueKind::String => {
+            if parent.starts_with("theme.") {
+                // Colors typed as RGB or HSL are stored as hex colors
+                if let Some(color) = parse_color_components(content) {
+                    return color.map(|color| serde_json::json!(color));
+                }
+                validate_color(parent, content)?;
+            }
+            Ok(serde_json::json!(content))
+        }
+        SettingsValueKind::Integer => content
+            .trim()
+            .parse::<i64>()
+            .map(|n| serde_json::json!(n))
+            .map_err(|_| match rounded_settings_value(kind, content) {
+                Some(n) => format!(
+                    "Expected a whole number, {} would be rounded to {n}",
+                    content.trim()
+                ),
+                None => "Expected a whole number".to_string(),
+            }),
+        SettingsValueKind::Float => match content.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(serde_json::json!(n)),
+            Ok(_) => Err("The number is too large".to_string()),
+            Err(_) => Err("Expected a number".to_string()),
+        },
+        SettingsValueKind::Bool => content
+            .trim()
+            .parse::<bool>()
+            .map(|b| serde_json::json!(b))
+            .map_err(|_| "Expected true or false".to_string()),
+        SettingsValueKind::Json => {
+            serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {e}"))
+        }
+        SettingsValueKind::MapEntry => Ok(serde_json::json!(content)),
+    }
+}
+
+/// Parse the text typed into the input of the setting `key`, like
+/// `parse_settings_value` but the value must also pass the checks of
+/// `Config::check_setting_value`
+pub fn parse_setting_input(
+    config: &Config,
+    kind: &SettingsValueKind,
+    parent: &str,
+    key: &str,
+    content: &str,
+) -> Result<serde_json::Value, String> {
+    let value = parse_settings_value(kind, parent, content)?;
+    config.check_setting_value(parent, key, &value)?;
+    Ok(value)
+}
+
+/// The value a setting is written to the settings file as, the hex theme
+/// colors are all written as #RRGGBBAA if the settings ask for it
+pub fn stored_settings_value(
+    config: &Config,
+    parent: &str,
+    value: &serde_json::Value,
+) -> serde_json::Value {
+    value
+        .as_str()
+        .filter(|_| {
+            parent.starts_with("theme.") && config.ui.normalize_theme_colors()
+        })
+        .and_then(normalize_pasted_color)
+        .map(serde_json::Value::String)
+        .unwrap_or_else(|| value.clone())
+}
+
+/// Whether the text typed into the input of the setting `key` is stored as
+/// it settles. A map entry is stored as part of the whole map by its item,
+/// and the theme name input renames the theme file on Enter.
+pub fn stores_settled_input(
+    kind: &SettingsValueKind,
+    parent: &str,
+    key: &str,
+) -> bool {
+    kind != &SettingsValueKind::MapEntry && !(parent == "theme" && key == "name")
+}
+
+/// The live status of the input of a numeric setting, what the text is
+/// stored as or why it isn't a number the setting takes
+pub fn numeric_input_status(
+    kind: &SettingsValueKind,
+    parent: &str,
+    key: &str,
+    content: &str,
+) -> Result<String, String> {
+    let value = parse_settings_value(kind, parent, content)?;
+    let n = value
+        .as_f64()
+        .ok_or_else(|| "Expected a number".to_string())?;
+    if let Some(err) = Config::setting_range_error(parent, key, n) {
+        return Err(err);
+    }
+    Ok(match Config::setting_unit(parent, key) {
+        Some(unit) => format!("{value} {unit}"),
+        None => value.to_string(),
+    })
+}
+
+/// The map a string map setting is stored as, from the (key, value) of its
+/// entries. Entries without a key or a value are left out, as they're being
+/// added.
+pub fn settings_map_value(
+    entries: &[(String, String)],
+) -> Result<serde_json::Value, String> {
+    let mut map = serde_json::Map::new();
+    for (key, value) in entries {
+        let key = key.trim();
+        if key.is_empty() {
+            if value.is_empty() {
+                continue;
+            }
+            return Err(format!("The entry with the value {value} needs a key"));
+        }
+        if map
+            .insert(key.to_string(), serde_json::json!(value))
+            .is_some()
+        {
+            return Err(format!("The key {key} is used more than once"));
+        }
+    }
+    Ok(serde_json::Value::Object(map))
+}
+
+/// The whole number a fractional input of an integer setting rounds to, it's
+/// offered instead of coercing the input
+pub fn rounded_settings_value(
+    kind: &SettingsValueKind,
+    content: &str,
+) -> Option<i64> {
+    if kind != &SettingsValueKind::Integer {
+        return None;
+    }
+    let n = content.trim().parse::<f64>().ok()?;
+    let rounded = n.round();
+    (rounded.is_finite() && rounded >= i64::MIN as f64 && rounded <= i64::MAX as f64)
+        .then_some(rounded as i64)
+}
+
+/// The raw input a settings value is edited as, the reverse of
+/// `parse_settings_value`
+pub fn settings_value_text(
+    kind: &SettingsValueKind,
+    value: &serde_json::Value,
+) -> String {
+    match (kind, value) {
+        (SettingsValueKind::Json, value) => value.to_string(),
+        (_, serde_json::Value::String(s)) => s.to_string(),
+        (_, value) => value.to_string(),
+    }
+}
+
+/// The note under a deprecated setting, naming the setting `replacement`
+/// replacing it unless it's ""
+pub fn deprecation_note(replacement: &str) -> String {
+    if replacement.is_empty() {
+        "Deprecated, this setting will be removed".to_string()
+    } else {
+        format!("Deprecated, use {replacement} instead")
+    }
+}
+
+/// What resetting a setting does, told with its default and the unit the
+/// setting is measured in
+pub fn reset_tooltip_text(
+    default: &serde_json::Value,
+    unit: Option<&str>,
+) -> String {
+    let default = match default {
+        serde_json::Value::Null => return "Reset to unset".to_string(),
+        serde_json::Value::String(s) => format!("\"{s}\""),
+        serde_json::Value::Number(n) => match unit {
+            Some(unit) => format!("{n} {unit}"),
+            None => n.to_string(),
+        },
+        value => value.to_string(),
+    };
+    format!("Reset to {default}")
+}
+
+fn validate_color(parent: &str, content: &str) -> Result<(), String> {
+    if let Some(base) = content.strip_prefix('$') {
+        if parent == "theme.base" {
+            return Err("Base colors can't reference other colors".to_string());
+        }
+        if base.is_empty() {
+            return Err("Expected the name of a base color after $".to_string());
+        }
+        return Ok(());
+    }
+    if content.is_empty() && parent == "theme.syntax" {
+        return Ok(());
+    }
+    if parent == "theme.base" {
+        return Color::from_hex_str(content).map(|_| ()).map_err(|_| {
+            if content.contains('(') {
+                "Base colors can't be derived from other colors".to_string()
+            } else {
+                "Expected a color like #RRGGBB or #RRGGBBAA".to_string()
+            }
+        });
+    }
+    // Which base colors there are is up to the theme, any name is accepted
+    eval_color(content, &|_| Some(Color::BLACK)).map(|_| ())
+}
+
+/// The color pasted into a theme color input, written as `#RRGGBBAA` so it
+/// reads like the other theme colors. Anything that isn't a hex color is
+/// pasted as it is and left to the input's validation.
+pub fn normalize_pasted_color(text: &str) -> Option<String> {
+    let text = text.trim();
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut hex = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    }
+    .to_ascii_uppercase();
+    if hex.len() == 6 {
+        hex.push_str("FF");
+    }
+    Some(format!("#{hex}"))
+}
+
+/// The color to replace and its replacement typed as `#E06C75 #FF0000`,
+/// both written as `#RRGGBBAA`
+pub fn parse_color_replacement(input: &str) -> Result<(String, String), String> {
+    let colors: Vec<&str> = input.split_whitespace().collect();
+    match colors.as_slice() {
+        [old, new] => {
+            match (normalize_pasted_color(old), normalize_pasted_color(new)) {
+                (Some(old), Some(new)) => Ok((old, new)),
+                (None, _) => Err(format!("{old} isn't a hex color")),
+                (_, None) => Err(format!("{new} isn't a hex color")),
+            }
+        }
+        _ => Err("Expected the color to replace and its replacement, like \
+             #E06C75 #FF0000"
+            .to_string()),
+    }
+}
+
+/// The (hue in degrees, saturation, lightness) of an RGB color, each
+/// channel from 0 to 1
+fn rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+/// The RGB channels, from 0 to 1, of an HSL color
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let h = h.rem_euclid(360.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |v: f64| (v + m).clamp(0.0, 1.0);
+    (channel(r), channel(g), channel(b))
+}
+
+/// An RGBA color with channels from 0 to 1, written as `#RRGGBBAA`
+fn hex_color(r: f64, g: f64, b: f64, a: f64) -> String {
+    let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02X}{:02X}{:02X}{:02X}",
+        byte(r),
+        byte(g),
+        byte(b),
+        byte(a)
+    )
+}
+
+/// The color with its hue turned by `hue` degrees and `lightness` added to
+/// its HSL lightness, from 0 to 1, written as `#RRGGBBAA`
+pub fn adjust_color(color: &Color, hue: f64, lightness: f64) -> String {
+    let (r, g, b, a) = color.as_rgba();
+    let (h, s, l) = rgb_to_hsl(r, g, b);
+    let (r, g, b) = hsl_to_rgb(h + hue, s, (l + lightness).clamp(0.0, 1.0));
+    hex_color(r, g, b, a)
+}
+
+/// A color typed as `rgb(224, 108, 117)` or `hsl(355, 65%, 65%)`, written as
+/// `#RRGGBBAA`, or why it isn't a color. `None` if it's typed another way.
+pub fn parse_color_components(text: &str) -> Option<Result<String, String>> {
+    let (function, args) = text.trim().strip_suffix(')')?.split_once('(')?;
+    let function = function.trim();
+    if function != "rgb" && function != "hsl" {
+        return None;
+    }
+    let args: Vec<&str> = args.split(',').map(str::trim).collect();
+    let number = |arg: &str, max: f64, percent: bool| -> Result<f64, String> {
+        let n = if percent {
+            arg.strip_suffix('%').unwrap_or(arg)
+        } else {
+            arg
+        };
+        n.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| (0.0..=max).contains(n))
+            .ok_or_else(|| {
+                let unit = if percent { "%" } else { "" };
+                format!("Expected a number from 0{unit} to {max}{unit}, not {arg}")
+            })
+    };
+    let color = || match (function, args.as_slice()) {
+        ("rgb", [r, g, b]) => Ok(hex_color(
+            number(r, 255.0, false)? / 255.0,
+            number(g, 255.0, false)? / 255.0,
+            number(b, 255.0, false)? / 255.0,
+            1.0,
+        )),
+        ("hsl", [h, s, l]) => {
+            let (r, g, b) = hsl_to_rgb(
+                number(h, 360.0, false)?,
+                number(s, 100.0, true)? / 100.0,
+                number(l, 100.0, true)? / 100.0,
+            );
+            Ok(hex_color(r, g, b, 1.0))
+        }
+        ("rgb", _) => Err("Expected rgb(red, green, blue)".to_string()),
+        _ => Err("Expected hsl(hue, saturation%, lightness%)".to_string()),
+    };
+    Some(color())
+}
+
+/// The RGB and HSL of a color, written the way they can be typed into a
+/// theme color input
+pub fn color_components(color: &Color) -> String {
+    let (r, g, b, _) = color.as_rgba();
+    let (h, s, l) = rgb_to_hsl(r, g, b);
+    let byte = |v: f64| (v * 255.0).round();
+    format!(
+        "rgb({}, {}, {})  hsl({}, {}%, {}%)",
+        byte(r),
+        byte(g),
+        byte(b),
+        h.round(),
+        (s * 100.0).round(),
+        (l * 100.0).round()
+    )
+}
+
+/// Find the settings table and key that are set on `line` of a settings file,
+/// e.g. `font-size = 14` under `[editor]` gives `("editor", "font-size")`.
+pub fn settings_key_at_line(text: &str, line: usize) -> Option<(String, String)> {
+    fn unquote(s: &str) -> &str {
+        s.trim().trim_matches('"').trim_matches('\'')
+    }
+
+    fn split_key(key: &str) -> Vec<&str> {
+        let key = key.trim();
+        if key.starts_with('"') || key.starts_with('\'') {
+            vec![unquote(key)]
+        } else {
+            key.split('.').map(unquote).collect()
+        }
+    }
+
+    let mut table = String::new();
+    for current in text.lines().take(line) {
+        let current = current.trim();
+        if let Some(header) = current.strip_prefix('[') {
+            let header = header.split(']').next().unwrap_or("");
+            table = split_key(header).join(".");
+        }
+    }
+
+    let current = text.lines().nth(line)?.trim();
+    if current.starts_with('#') || current.starts_with('[') {
+        return None;
+    }
+    let (key, _) = current.split_once('=')?;
+    let mut parts = split_key(key);
+    let key = parts.pop()?;
+    if !parts.is_empty() {
+        let prefix = parts.join(".");
+        table = if table.is_empty() {
+            prefix
+        } else {
+            format!("{table}.{prefix}")
+        };
+    }
+    if table.is_empty() || key.is_empty() {
+        return None;
+    }
+
+    let key = if table.starts_with("theme") {
+        key.to_string()
+    } else {
+        key.replace('_', "-")
+    };
+    Some((table, key))
+}
+
+/// Find the line of a settings file where the key of a settings table is set
+pub fn settings_key_line(text: &str, table: &str, key: &str) -> Option<usize> {
+    (0..text.lines().count()).find(|line| {
+        settings_key_at_line(text, *line)
+            .map(|(t, k)| t == table && k == key)
+            .unwrap_or(false)
+    })
+}
+
+/// Settings that only apply when a boolean setting is turned on, as
+/// ((table, key), (table, key) of the setting they depend on)
+const SETTINGS_DEPENDENCIES: &[((&str, &str), (&str, &str))] = &[
+    (
+        ("editor", "inlay-hint-font-family"),
+        ("editor", "enable-inlay-hints"),
+    ),
+    (
+        ("editor", "inlay-hint-font-size"),
+        ("editor", "enable-inlay-hints"),
+    ),
+    (
+        ("editor", "error-lens-end-of-line"),
+        ("editor", "enable-error-lens"),
+    ),
+    (
+        ("editor", "error-lens-font-family"),
+        ("editor", "enable-error-lens"),
+    ),
+    (
+        ("editor", "error-lens-font-size"),
+        ("editor", "enable-error-lens"),
+    ),
+    (
+        ("editor", "modal-mode-relative-line-numbers"),
+        ("lapce", "modal"),
+    ),
+];
+
+/// The setting that has to be turned on for a setting to apply, if it's off
+pub fn unmet_settings_dependency(
+    config: &Config,
+    table: &str,
+    key: &str,
+) -> Option<(&'static str, &'static str)> {
+    SETTINGS_DEPENDENCIES
+        .iter()
+        .filter(|(setting, _)| *setting == (table, key))
+        .map(|(_, dependency)| *dependency)
+        .find(|(table, key)| {
+            config.setting_value(table, key) == Some(serde_json::Value::Bool(false))
+        })
+}
+
+/// The VS Code settings that have a Lapce equivalent, as
+/// (VS Code key, settings table, settings key)
+const VSCODE_SETTINGS: &[(&str, &str, &str)] = &[
+    ("editor.fontFamily", "editor", "font-family"),
+    ("editor.fontSize", "editor", "font-size"),
+    ("editor.codeLensFontSize", "editor", "code-lens-font-size"),
+    ("editor.tabSize", "editor", "tab-width"),
+    (
+        "editor.scrollBeyondLastLine",
+        "editor",
+        "scroll-beyond-last-line",
+    ),
+    ("editor.stickyScroll.enabled", "editor", "sticky-header"),
+    ("editor.hover.delay", "editor", "hover-delay"),
+    ("editor.formatOnSave", "editor", "format-on-save"),
+    ("editor.inlayHints.enabled", "editor", "enable-inlay-hints"),
+    (
+        "editor.inlayHints.fontFamily",
+        "editor",
+        "inlay-hint-font-family",
+    ),
+    (
+        "editor.inlayHints.fontSize",
+        "editor",
+        "inlay-hint-font-size",
+    ),
+    ("editor.renderWhitespace", "editor", "render-whitespace"),
+    ("breadcrumbs.enabled", "editor", "show-bread-crumbs"),
+    ("terminal.integrated.fontFamily", "terminal", "font-family"),
+    ("terminal.integrated.fontSize", "terminal", "font-size"),
+];
+
+/// A setting that changes from one value to another, when importing VS Code
+/// settings, restoring a backup or following a changed default
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SettingChange {
+    pub table: String,
+    pub key: String,
+    pub old: Option<serde_json::Value>,
+    pub new: serde_json::Value,
+    /// Whether the change removes the setting from the settings file, `new`
+    /// being the default it goes back to, so it keeps following the default
+    #[serde(default)]
+    pub unset: bool,
+}
+
+/// The outcome of reading a VS Code settings.json, before it's applied
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VSCodeSettingsImport {
+    pub changes: Vec<SettingChange>,
+    /// The VS Code settings that have no Lapce equivalent
+    pub unmapped: Vec<String>,
+}
+
+impl VSCodeSettingsImport {
+    /// Read the settings of a VS Code settings.json and map them onto the
+    /// Lapce settings, the settings that already have the value are skipped.
+    pub fn parse(text: &str, config: &Config) -> Result<Self, String> {
+        let settings: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&strip_jsonc(text))
+                .map_err(|e| format!("Can't read the VS Code settings: {e}"))?;
+
+        let mut import = Self::default();
+        for (vscode_key, value) in settings.iter() {
+            let change = VSCODE_SETTINGS
+                .iter()
+                .find(|(key, _, _)| key == vscode_key)
+                .and_then(|(_, table, key)| {
+                    let old = config.setting_value(table, key);
+                    let new =
+                        convert_vscode_value(vscode_key, value, old.as_ref()?)?;
+                    Some(SettingChange {
+                        table: table.to_string(),
+                        key: key.to_string(),
+                        old,
+                        new,
+                        unset: false,
+                    })
+                });
+            match change {
+                Some(change) if change.old.as_ref() == Some(&change.new) => {}
+                Some(change) => import.changes.push(change),
+                None => import.unmapped.push(vscode_key.to_string()),
+            }
+        }
+        import.changes.sort_by(|a, b| a.table.cmp(&b.table));
+        Ok(import)
+    }
+
+    /// What the import changes, in the form of a settings file
+    pub fn preview(&self) -> String {
+        let mut text = "# The settings that change when importing the VS Code \
+                        settings\n"
+            .to_string();
+        for (table, changes) in &self.changes.iter().group_by(|c| &c.table) {
+            text.push_str(&format!("\n[{table}]\n"));
+            for change in changes {
+                text.push_str(&format!("{} = {}", change.key, change.new));
+                if let Some(old) = change.old.as_ref() {
+                    text.push_str(&format!(" # was {old}"));
+                }
+                text.push('\n');
+            }
+        }
+        if !self.unmapped.is_empty() {
+            text.push_str("\n# Not imported, these have no equivalent in Lapce:\n");
+            for key in self.unmapped.iter() {
+                text.push_str(&format!("# {key}\n"));
+            }
+        }
+        text
+    }
+}
+
+/// The settings that change when restoring the backup folder `dir`, with
+/// the values they have now in comments
+pub fn backup_restore_preview(dir: &Path, changes: &[SettingChange]) -> String {
+    let mut text = format!(
+        "# The settings that change when restoring the backup in\n# {}\n",
+        dir.display()
+    );
+    for (table, changes) in &changes
+        .iter()
+        .sorted_by(|a, b| a.table.cmp(&b.table))
+        .group_by(|c| &c.table)
+    {
+        text.push_str(&format!("\n[{table}]\n"));
+        for change in changes {
+            if change.unset {
+                text.push_str("# unset, back to the default ");
+            }
+            text.push_str(&format!("{} = {}", change.key, change.new));
+            match change.old.as_ref() {
+                Some(old) => text.push_str(&format!(" # now {old}")),
+                None => text.push_str(" # now unset"),
+            }
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// The color maps of a theme, which an imported theme can be applied by
+pub const THEME_MAPS: [&str; 3] = ["base", "ui", "syntax"];
+
+/// A theme file picked to import, with the color maps to merge into the
+/// current theme, or none to install it as a theme of its own
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeImport {
+    pub path: PathBuf,
+    pub maps: Option<Vec<String>>,
+}
+
+/// Read a theme copied as JSON, returning the colors that differ from the
+/// current theme as (table, key, color). Colors the current theme doesn't
+/// have are skipped.
+pub fn theme_json_changes(
+    text: &str,
+    config: &Config,
+) -> Result<Vec<(String, String, String)>, String> {
+    let theme: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(text)
+            .map_err(|e| format!("The clipboard doesn't contain a theme: {e}"))?;
+    theme_map_changes(&theme, &THEME_MAPS, config)
+        .ok_or_else(|| "The clipboard doesn't contain any theme colors".to_string())
+}
+
+/// Read the [theme] of the theme file at `path`, returning the colors of the
+/// `maps` that differ from the current theme, like [`theme_json_changes`]
+pub fn theme_file_changes(
+    path: &Path,
+    maps: &[&str],
+    config: &Config,
+) -> Result<Vec<(String, String, String)>, String> {
+    let file: serde_json::Value = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can't read {}: {e}", path.display()))
+        .and_then(|text| {
+            toml_edit::easy::from_str(&text)
+                .map_err(|e| format!("Can't parse {}: {e}", path.display()))
+        })?;
+    let theme = file
+        .get("theme")
+        .and_then(|theme| theme.as_object())
+        .ok_or_else(|| format!("{} doesn't have a [theme]", path.display()))?;
+    theme_map_changes(theme, maps, config).ok_or_else(|| {
+        format!(
+            "{} doesn't have any {} colors",
+            path.display(),
+            maps.join(" or ")
+        )
+    })
+}
+
+/// The colors of the `maps` of a serialized theme that differ from the
+/// current theme, none when the theme has none of the maps
+fn theme_map_changes(
+    theme: &serde_json::Map<String, serde_json::Value>,
+    maps: &[&str],
+    config: &Config,
+) -> Option<Vec<(String, String, String)>> {
+    let mut found = false;
+    let mut changes = Vec::new();
+    for kind in maps.iter().copied() {
+        let colors = match theme.get(kind).and_then(|c| c.as_object()) {
+            Some(colors) => colors,
+            None => continue,
+        };
+        found = true;
+        for (key, color) in colors.iter() {
+            let color = match color.as_str() {
+                Some(color) => color,
+                None => continue,
+            };
+            let current = match kind {
+                "base" => config.theme.base.get(key),
+                "ui" => config.theme.ui.get(key),
+                _ => config.theme.syntax.get(key),
+            };
+            if let Some(current) = current {
+                if current != color {
+                    changes.push((
+                        format!("theme.{kind}"),
+                        key.to_string(),
+                        color.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    found.then_some(changes)
+}
+
+/// How long ago a setting was changed at `time`, with both in seconds since
+/// the unix epoch, e.g. "3 hours ago"
+pub fn settings_change_age(time: u64, now: u64) -> String {
+    let age = now.saturating_sub(time);
+    let (count, unit) = match age {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (age / 60, "minute"),
+        3_600..=86_399 => (age / 3_600, "hour"),
+        86_400..=2_591_999 => (age / 86_400, "day"),
+        2_592_000..=31_535_999 => (age / 2_592_000, "month"),
+        _ => (age / 31_536_000, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural} ago")
+}
+
+/// The settings going back to their values as "table.key back to value" lines,
+/// the ones the settings file didn't set go back to their defaults
+pub fn settings_revert_summary(
+    reverts: &[(String, String, Option<serde_json::Value>)],
+) -> String {
+    reverts
+        .iter()
+        .map(|(kind, name, value)| match value {
+            Some(value) => format!("{kind}.{name} back to {value}"),
+            None => format!("{kind}.{name} back to its default"),
+        })
+        .join("\n")
+}
+
+/// The size of the text of a string input, e.g. "120 characters, 18 words,
+/// 3 lines"
+pub fn text_size_readout(text: &str) -> String {
+    let counts = [
+        (text.chars().count(), "character"),
+        (text.split_whitespace().count(), "word"),
+        (text.split('\n').count(), "line"),
+    ];
+    counts
+        .iter()
+        .map(|(count, unit)| {
+            let plural = if *count == 1 { "" } else { "s" };
+            format!("{count} {unit}{plural}")
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Convert a VS Code setting value to the type of the Lapce setting
+fn convert_vscode_value(
+    vscode_key: &str,
+    value: &serde_json::Value,
+    current: &serde_json::Value,
+) -> Option<serde_json::Value> {
+    use serde_json::Value;
+
+    let value = match (vscode_key, value) {
+        // "on", "off", "onUnlessPressed" or "offUnlessPressed"
+        ("editor.inlayHints.enabled", Value::String(s)) => {
+            Value::Bool(s.starts_with("on"))
+        }
+        ("editor.renderWhitespace", Value::String(s))
+            if !matches!(s.as_str(), "none" | "all" | "boundary" | "trailing") =>
+        {
+            return None;
+        }
+        _ => value.clone(),
+    };
+
+    match (current, &value) {
+        (Value::Bool(_), Value::Bool(_)) | (Value::String(_), Value::String(_)) => {
+            Some(value)
+        }
+        (Value::Number(current), Value::Number(n)) => {
+            if current.is_f64() {
+                Some(value)
+            } else {
+                let n = n.as_f64()?;
+                (n >= 0.0).then(|| serde_json::json!(n.round() as u64))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Strip the comments and trailing commas that VS Code allows in its json
+fn strip_jsonc(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            match c {
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        result.push(c);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(c);
+            }
+            ('/', Some(&'/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some(&'*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ('}' | ']', _) => {
+                let trimmed = result.trim_end().len();
+                if result[..trimmed].ends_with(',') {
+                    result.truncate(trimmed - 1);
+                }
+                result.push(c);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
 }
 
 pub enum LapceSettingsKind {
@@ -28,6 +912,250 @@ pub enum LapceSettingsKind {
     Editor,
 }
 
+/// Record a theme change from `old` to `new` in the recently used themes,
+/// most recent first. The two kept are the ones switched between.
+pub fn record_theme_change(recent: &mut Vec<String>, old: &str, new: &str) {
+    // The settings file may have been changed by hand in between
+    if !matches!(recent.first(), Some(theme) if theme.eq_ignore_ascii_case(old)) {
+        recent.retain(|theme| !theme.eq_ignore_ascii_case(old));
+        recent.insert(0, old.to_string());
+    }
+    recent.retain(|theme| !theme.eq_ignore_ascii_case(new));
+    recent.insert(0, new.to_string());
+    recent.truncate(2);
+}
+
+/// Reads and changes settings without the settings panel, a change is
+/// validated like an edit in the panel and written to the settings file the
+/// same way
+pub struct SettingsController {
+    config: Config,
+    path: PathBuf,
+    /// The tab the changes go through to be written like the ones of the
+    /// settings panel, which tells the plugins and records them, instead of
+    /// being written straight to `path`
+    tab: Option<(ExtEventSink, WidgetId)>,
+}
+
+impl SettingsController {
+    /// A controller of the user settings file, validating against `config`,
+    /// its changes are written by the tab `tab_id`
+    pub fn new(
+        config: Config,
+        event_sink: ExtEventSink,
+        tab_id: WidgetId,
+    ) -> Option<Self> {
+        let mut controller = Self::with_file(config, Config::settings_file()?);
+        controller.tab = Some((event_sink, tab_id));
+        Some(controller)
+    }
+
+    /// A controller writing to the settings file at `path` itself
+    pub fn with_file(config: Config, path: PathBuf) -> Self {
+        Self {
+            config,
+            path,
+            tab: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The value of the setting, from the settings file if it's set there
+    pub fn get(&self, kind: &str, name: &str) -> Option<serde_json::Value> {
+        self.file_value(kind, name)
+            .or_else(|| self.config.setting_value(kind, name))
+    }
+
+    fn file_value(&self, kind: &str, name: &str) -> Option<serde_json::Value> {
+        Config::file_setting_value_at(&self.path, kind, name)
+    }
+
+    /// Store a setting, a null value unsets it
+    pub fn set(
+        &mut self,
+        kind: &str,
+        name: &str,
+        value: serde_json::Value,
+    ) -> Result<(), String> {
+        let current = self.check(kind, name)?;
+        if value.is_null() {
+            return self.reset(kind, name);
+        }
+        match (&current, &value) {
+            // An unset value doesn't tell which type the setting is
+            (serde_json::Value::Null, _) => {}
+            (serde_json::Value::Number(current), serde_json::Value::Number(n))
+                if (current.is_i64() || current.is_u64())
+                    && !(n.is_i64() || n.is_u64()) =>
+            {
+                return Err("Expected a whole number".to_string());
+            }
+            (current, value)
+                if std::mem::discriminant(current)
+                    != std::mem::discriminant(value) =>
+            {
+                return Err(format!("Expected {}", json_type_name(current)));
+            }
+            _ => {}
+        }
+        // The checks of the panel's inputs, on the value typed as text
+        let input_kind = SettingsValueKind::of(&value);
+        let text = settings_value_text(&input_kind, &value);
+        let value =
+            parse_setting_input(&self.config, &input_kind, kind, name, &text)?;
+        if let Some((event_sink, tab_id)) = self.tab.as_ref() {
+            return Self::submit(
+                event_sink,
+                *tab_id,
+                LapceUICommand::UpdateSettingsFile(
+                    kind.to_string(),
+                    name.to_string(),
+                    value,
+                ),
+            );
+        }
+        let value = stored_settings_value(&self.config, kind, &value);
+        let toml_value = toml_edit::ser::to_item(&value)
+            .ok()
+            .and_then(|item| item.into_value().ok())
+            .ok_or_else(|| "The value can't be written to TOML".to_string())?;
+        Config::update_file_at(&self.path, kind, name, toml_value)
+            .ok_or_else(|| format!("Can't write {}", self.path.display()))
+    }
+
+    /// Remove a setting from the settings file, the default takes effect
+    pub fn reset(&mut self, kind: &str, name: &str) -> Result<(), String> {
+        self.check(kind, name)?;
+        if let Some((event_sink, tab_id)) = self.tab.as_ref() {
+            return Self::submit(
+                event_sink,
+                *tab_id,
+                LapceUICommand::ResetSettingsFile(
+                    kind.to_string(),
+                    name.to_string(),
+                ),
+            );
+        }
+        Config::reset_setting_at(&self.path, kind, name)
+            .ok_or_else(|| format!("Can't write {}", self.path.display()))
+    }
+
+    fn submit(
+        event_sink: &ExtEventSink,
+        tab_id: WidgetId,
+        command: LapceUICommand,
+    ) -> Result<(), String> {
+        event_sink
+            .submit_command(LAPCE_UI_COMMAND, command, Target::Widget(tab_id))
+            .map_err(|_| "Lapce isn't running".to_string())
+    }
+
+    /// The current value of a setting which can be changed
+    fn check(&self, kind: &str, name: &str) -> Result<serde_json::Value, String> {
+        if self.config.locked_by_policy(kind, name) {
+            return Err(format!("{kind}.{name} is locked by the workspace policy"));
+        }
+        self.config
+            .setting_value(kind, name)
+            .ok_or_else(|| format!("There is no setting {kind}.{name}"))
+    }
+}
+
+pub(crate) fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "nothing",
+        serde_json::Value::Bool(_) => "true or false",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "a list",
+        serde_json::Value::Object(_) => "a table",
+    }
+}
+
+/// User preferences of the settings panel, persisted across sessions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsPanelPreferences {
+    /// List the settings of each section by name instead of declaration order
+    pub sort_alphabetically: bool,
+    /// The width of the section switcher, the content takes the rest
+    pub switcher_width: u32,
+    /// Name the settings by their settings file key, e.g. `editor.font-size`,
+    /// instead of a title
+    pub show_raw_keys: bool,
+    /// Show the section switcher as icons only, naming them on hover
+    pub switcher_collapsed: bool,
+    /// List the settings flagged as advanced too
+    pub show_advanced: bool,
+    /// List the changed settings of each section before the ones at their
+    /// default, under a header each
+    pub group_by_changed: bool,
+    /// List the deprecated settings too
+    pub show_deprecated: bool,
+    /// Show the settings in effect as JSON next to the settings
+    pub show_effective_config: bool,
+    /// The settings tables of the sections showing the descriptions of all
+    /// their settings in full
+    pub expanded_sections: Vec<String>,
+}
+
+impl SettingsPanelPreferences {
+    pub const MIN_SWITCHER_WIDTH: u32 = 100;
+    pub const MAX_SWITCHER_WIDTH: u32 = 400;
+
+    /// Whether the panel lists the setting, the advanced and deprecated
+    /// settings are only listed while they're shown
+    pub fn lists_setting(&self, table: &str, key: &str) -> bool {
+        (self.show_advanced || !Config::advanced_setting(table, key))
+            && (self.show_deprecated
+                || Config::deprecated_setting(table, key).is_none())
+    }
+
+    /// Whether the section of the table shows its descriptions in full
+    pub fn descriptions_expanded(&self, table: &str) -> bool {
+        self.expanded_sections.iter().any(|t| t == table)
+    }
+
+    /// Expand the descriptions of the section of the table, or collapse
+    /// them if they're expanded
+    pub fn toggle_descriptions(&mut self, table: &str) {
+        if self.descriptions_expanded(table) {
+            self.expanded_sections.retain(|t| t != table);
+        } else {
+            self.expanded_sections.push(table.to_string());
+        }
+    }
+}
+
+impl Default for SettingsPanelPreferences {
+    fn default() -> Self {
+        Self {
+            sort_alphabetically: false,
+            switcher_width: 150,
+            show_raw_keys: false,
+            switcher_collapsed: false,
+            show_advanced: false,
+            group_by_changed: false,
+            show_deprecated: false,
+            show_effective_config: false,
+            expanded_sections: Vec::new(),
+        }
+    }
+}
+
+/// A setting that was changed from the settings panel
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentSettingsChange {
+    /// The settings file table of the setting, e.g. "editor"
+    pub kind: String,
+    pub name: String,
+    /// When the setting was changed, in seconds since the unix epoch
+    pub time: u64,
+}
+
 #[derive(Clone)]
 pub struct LapceSettingsPanelData {
     pub panel_widget_id: WidgetId,
@@ -39,6 +1167,52 @@ pub struct LapceSettingsPanelData {
     pub settings_widget_id: WidgetId,
     pub settings_view_id: WidgetId,
     pub settings_split_id: WidgetId,
+
+    /// Validation errors of the settings inputs, keyed by the value doc name
+    pub value_errors: im::HashMap<String, String>,
+    /// Scroll offsets of the settings sections, keyed by their settings table
+    pub scroll_offsets: im::HashMap<String, Vec2>,
+    pub preferences: SettingsPanelPreferences,
+    /// The settings changed most recently, newest first
+    pub recent_changes: Vec<RecentSettingsChange>,
+    /// When each setting was last changed from Lapce, in seconds since the
+    /// unix epoch, keyed by "{table}.{key}"
+    pub change_times: im::HashMap<String, u64>,
+    /// The recent searches of the settings search input, the latest first
+    pub search_history: Vec<String>,
+    /// The settings toggled or picked in the panel that are waiting to be
+    /// stored as (table, key, value), keyed by "{table}.{key}"
+    pub pending_changes: im::HashMap<String, (String, String, serde_json::Value)>,
+    /// Counts the flushes of the pending settings, the inputs drop the save
+    /// timers they were waiting for when it changes
+    pub flush_id: u64,
+    /// The theme colors typed while theme changes are confirmed, waiting to
+    /// be applied as (table, key, value), keyed by the value doc name
+    pub buffered_theme_changes:
+        im::HashMap<String, (String, String, serde_json::Value)>,
+    /// The text typed in the settings search input
+    pub filter_pattern: String,
+    /// The searches of the settings sections not shown, keyed by their
+    /// settings table, the input gets a section's search back when it's shown
+    pub section_filters: im::HashMap<String, String>,
+    /// The setting the breadcrumb shows, as (section table, table, key), from
+    /// the last selected, revealed or scrolled to item
+    pub current_setting: Option<(String, String, String)>,
+    /// The bulk change being stored, as what it does and when it started,
+    /// until the config reloads
+    pub reloading: Option<(String, Instant)>,
+    /// The setting that couldn't be stored as (table, key, reason), shown in
+    /// a banner until it's dismissed or a setting is stored
+    pub write_error: Option<(String, String, String)>,
+    /// Why the settings file couldn't be copied to the `settings-mirror`
+    /// path after the last change, shown in the same banner
+    pub mirror_error: Option<String>,
+    /// The last changed setting the open files don't follow until they're
+    /// reloaded, as "table.key"
+    pub reload_pending: Option<String>,
+    /// The settings changed since the panel was opened as (table, key, value),
+    /// with the value the settings file had before their first change
+    pub session_changes: im::Vector<(String, String, Option<serde_json::Value>)>,
 }
 
 impl KeyPressFocus for LapceSettingsPanelData {
@@ -78,7 +1252,22 @@ fn run_command(
 }
 
 impl LapceSettingsPanelData {
-    pub fn new() -> Self {
+    /// The number of recently changed settings that are remembered
+    pub const RECENT_CHANGES_LIMIT: usize = 20;
+    /// The number of searches kept in the search history
+    pub const SEARCH_HISTORY_LIMIT: usize = 10;
+    /// How long a bulk change runs before the panel shows it's in progress
+    pub const RELOADING_THRESHOLD: Duration = Duration::from_millis(300);
+    /// The panel stops showing a bulk change that didn't reload the config
+    /// after this
+    pub const RELOADING_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(
+        preferences: SettingsPanelPreferences,
+        recent_changes: Vec<RecentSettingsChange>,
+        change_times: im::HashMap<String, u64>,
+        search_history: Vec<String>,
+    ) -> Self {
         Self {
             panel_widget_id: WidgetId::next(),
             keymap_widget_id: WidgetId::next(),
@@ -87,13 +1276,147 @@ pub fn new() -> Self {
             settings_widget_id: WidgetId::next(),
             settings_view_id: WidgetId::next(),
             settings_split_id: WidgetId::next(),
+            value_errors: im::HashMap::new(),
+            scroll_offsets: im::HashMap::new(),
+            preferences,
+            recent_changes,
+            change_times,
+            search_history,
+            pending_changes: im::HashMap::new(),
+            flush_id: 0,
+            buffered_theme_changes: im::HashMap::new(),
+            filter_pattern: String::new(),
+            section_filters: im::HashMap::new(),
+            current_setting: None,
+            reloading: None,
+            write_error: None,
+            mirror_error: None,
+            reload_pending: None,
+            session_changes: im::Vector::new(),
+        }
+    }
+
+    /// Remember the value the settings file has for a setting that's about
+    /// to be changed, unless it was changed before since the panel opened
+    pub fn capture_session_change(&mut self, kind: &str, name: &str) {
+        if self
+            .session_changes
+            .iter()
+            .any(|(k, n, _)| k == kind && n == name)
+        {
+            return;
         }
+        let original = Config::settings_file()
+            .and_then(|path| Config::file_setting_value_at(&path, kind, name));
+        self.session_changes.push_back((
+            kind.to_string(),
+            name.to_string(),
+            original,
+        ));
+    }
+
+    /// The settings the settings file sets to something else than before
+    /// the panel opened, with the values they go back to
+    pub fn session_reverts(
+        &self,
+    ) -> Vec<(String, String, Option<serde_json::Value>)> {
+        let path = Config::settings_file();
+        self.session_changes
+            .iter()
+            .filter(|(kind, name, original)| {
+                let current = path.as_ref().and_then(|path| {
+                    Config::file_setting_value_at(path, kind, name)
+                });
+                &current != original
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Show the bulk change `what` in the panel until the config reloads,
+    /// e.g. "Resetting the settings"
+    pub fn start_reloading(&mut self, what: &str) {
+        self.reloading = Some((what.to_string(), Instant::now()));
+    }
+
+    /// The bulk change the panel shows as in progress, once it took longer
+    /// than `RELOADING_THRESHOLD`
+    pub fn reloading_label(&self) -> Option<&str> {
+        let (what, started) = self.reloading.as_ref()?;
+        let elapsed = started.elapsed();
+        (elapsed >= Self::RELOADING_THRESHOLD && elapsed < Self::RELOADING_TIMEOUT)
+            .then_some(what.as_str())
+    }
+
+    /// Whether the panel is still waiting for a bulk change to reload the
+    /// config
+    pub fn is_reloading(&self) -> bool {
+        self.reloading
+            .as_ref()
+            .map(|(_, started)| started.elapsed() < Self::RELOADING_TIMEOUT)
+            .unwrap_or(false)
+    }
+
+    /// Keep a theme color typed into the value doc `name` until the theme
+    /// changes are applied, dropping it once it's back to the `current` value
+    pub fn buffer_theme_change(
+        &mut self,
+        name: &str,
+        change: (String, String, serde_json::Value),
+        current: Option<&serde_json::Value>,
+    ) {
+        if current == Some(&change.2) {
+            self.buffered_theme_changes.remove(name);
+        } else {
+            self.buffered_theme_changes.insert(name.to_string(), change);
+        }
+    }
+
+    /// Move the setting to the front of the recently changed settings, and
+    /// remember when it was changed
+    pub fn record_change(&mut self, kind: &str, name: &str) {
+        self.recent_changes
+            .retain(|change| change.kind != kind || change.name != name);
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.recent_changes.insert(
+            0,
+            RecentSettingsChange {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                time,
+            },
+        );
+        self.recent_changes.truncate(Self::RECENT_CHANGES_LIMIT);
+        self.change_times.insert(format!("{kind}.{name}"), time);
+    }
+
+    /// Move the search to the front of the search history, returning whether
+    /// the history changed
+    pub fn record_search(&mut self, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+        if pattern.is_empty()
+            || self.search_history.first().map(|s| s.as_str()) == Some(pattern)
+        {
+            return false;
+        }
+        self.search_history.retain(|search| search != pattern);
+        self.search_history.insert(0, pattern.to_string());
+        self.search_history.truncate(Self::SEARCH_HISTORY_LIMIT);
+        true
     }
 }
 
 impl Default for LapceSettingsPanelData {
     fn default() -> Self {
-        Self::new()
+        Self::new(
+            SettingsPanelPreferences::default(),
+            Vec::new(),
+            im::HashMap::new(),
+            Vec::new(),
+        )
     }
 }
 
@@ -110,8 +1433,8 @@ fn get_mode(&self) -> Mode {
         Mode::Insert
     }
 
-    fn check_condition(&self, _condition: &str) -> bool {
-        false
+    fn check_condition(&self, condition: &str) -> bool {
+        matches!(condition, "settings_focus")
     }
 
     fn run_command(
@@ -124,6 +1447,16 @@ fn run_command(
     ) -> CommandExecuted {
         match &command.kind {
             CommandKind::Focus(cmd) => match cmd {
+                FocusCommand::NextSettingsSection
+                | FocusCommand::PreviousSettingsSection
+                | FocusCommand::ToggleSettingsDescriptions => {
+                    // The panel holds the active section
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        command.clone(),
+                        Target::Widget(self.widget_id),
+                    ));
+                }
                 FocusCommand::SplitVertical => {
                     self.main_split.split_settings(
                         ctx,
@@ -241,3 +1574,536 @@ fn receive_char(&mut self, _ctx: &mut EventCtx, c: &str) {
         self.cursor += c.len();
     }
 }
+
+/// The byte ranges of `text` matching the settings search pattern, ignoring
+/// ASCII case.
+pub fn settings_filter_matches(text: &str, pattern: &str) -> Vec<Range<usize>> {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    text.to_ascii_lowercase()
+        .match_indices(&pattern)
+        .map(|(start, m)| start..start + m.len())
+        .collect()
+}
+
+/// What Tab completes the settings search to, the longest common prefix of
+/// the setting identifiers starting with the search, or of their keys when
+/// the search has no table. Nothing when that doesn't add to the search.
+pub fn complete_setting_id(pattern: &str, ids: &[String]) -> Option<String> {
+    let pattern = pattern.to_ascii_lowercase();
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut candidates = ids
+        .iter()
+        .map(|id| {
+            if pattern.contains('.') {
+                id.as_str()
+            } else {
+                id.split_once('.').map(|(_, key)| key).unwrap_or(id)
+            }
+        })
+        .filter(|id| id.starts_with(&pattern));
+    let mut prefix = candidates.next()?.to_string();
+    for id in candidates {
+        let common = prefix
+            .chars()
+            .zip(id.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        prefix.truncate(prefix.chars().take(common).map(char::len_utf8).sum());
+    }
+    (prefix.len() > pattern.len()).then_some(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_tooltip_text() {
+        assert_eq!(
+            reset_tooltip_text(&serde_json::json!(13), Some("px")),
+            "Reset to 13 px"
+        );
+        assert_eq!(
+            reset_tooltip_text(&serde_json::json!(1.5), None),
+            "Reset to 1.5"
+        );
+        assert_eq!(
+            reset_tooltip_text(&serde_json::json!("Cascadia Code"), Some("px")),
+            "Reset to \"Cascadia Code\""
+        );
+        assert_eq!(
+            reset_tooltip_text(&serde_json::json!(""), None),
+            "Reset to \"\""
+        );
+        assert_eq!(
+            reset_tooltip_text(&serde_json::json!(true), None),
+            "Reset to true"
+        );
+        assert_eq!(
+            reset_tooltip_text(&serde_json::Value::Null, None),
+            "Reset to unset"
+        );
+    }
+
+    #[test]
+    fn test_complete_setting_id() {
+        let ids = [
+            "editor.font-family".to_string(),
+            "editor.font-size".to_string(),
+            "editor.tab-width".to_string(),
+            "terminal.font-size".to_string(),
+        ];
+        assert_eq!(
+            complete_setting_id("editor.f", &ids),
+            Some("editor.font-".to_string())
+        );
+        assert_eq!(
+            complete_setting_id("Editor.T", &ids),
+            Some("editor.tab-width".to_string())
+        );
+        assert_eq!(
+            complete_setting_id("font-s", &ids),
+            Some("font-size".to_string())
+        );
+        assert_eq!(complete_setting_id("editor.font-", &ids), None);
+        assert_eq!(complete_setting_id("tabs", &ids), None);
+        assert_eq!(complete_setting_id("", &ids), None);
+    }
+
+    #[test]
+    fn test_normalize_pasted_color() {
+        assert_eq!(
+            normalize_pasted_color("#abc"),
+            Some("#AABBCCFF".to_string())
+        );
+        assert_eq!(
+            normalize_pasted_color(" 1a2b3c\n"),
+            Some("#1A2B3CFF".to_string())
+        );
+        assert_eq!(
+            normalize_pasted_color("#1a2b3c80"),
+            Some("#1A2B3C80".to_string())
+        );
+        assert_eq!(normalize_pasted_color("$blue"), None);
+        assert_eq!(normalize_pasted_color("#12345"), None);
+        assert_eq!(normalize_pasted_color("red"), None);
+    }
+
+    #[test]
+    fn test_color_replacement() {
+        assert_eq!(
+            parse_color_replacement(" #e06c75  fff "),
+            Ok(("#E06C75FF".to_string(), "#FFFFFFFF".to_string()))
+        );
+        assert!(parse_color_replacement("#e06c75").is_err());
+        assert!(parse_color_replacement("#e06c75 $red").is_err());
+        assert!(parse_color_replacement("#e06c75 #fff #000").is_err());
+    }
+
+    #[test]
+    fn test_adjust_color() {
+        let red = Color::rgb8(255, 0, 0);
+        assert_eq!(adjust_color(&red, 0.0, 0.0), "#FF0000FF");
+        assert_eq!(adjust_color(&red, 120.0, 0.0), "#00FF00FF");
+        assert_eq!(adjust_color(&red, -120.0, 0.0), "#0000FFFF");
+        assert_eq!(adjust_color(&red, 0.0, 0.25), "#FF8080FF");
+        assert_eq!(adjust_color(&Color::WHITE, 0.0, 0.1), "#FFFFFFFF");
+        assert_eq!(
+            adjust_color(&Color::rgba8(128, 128, 128, 128), 45.0, -0.6),
+            "#00000080"
+        );
+    }
+
+    #[test]
+    fn test_parse_color_components() {
+        assert_eq!(
+            parse_color_components("rgb(224, 108, 117)"),
+            Some(Ok("#E06C75FF".to_string()))
+        );
+        assert_eq!(
+            parse_color_components(" hsl(120, 100%, 25%) "),
+            Some(Ok("#008000FF".to_string()))
+        );
+        assert!(matches!(
+            parse_color_components("rgb(256, 0, 0)"),
+            Some(Err(_))
+        ));
+        assert!(matches!(parse_color_components("hsl(0, 0%)"), Some(Err(_))));
+        assert_eq!(parse_color_components("#E06C75"), None);
+        assert_eq!(parse_color_components("lighten($red, 10%)"), None);
+        assert_eq!(
+            parse_settings_value(
+                &SettingsValueKind::String,
+                "theme.base",
+                "rgb(0, 0, 255)"
+            ),
+            Ok(serde_json::json!("#0000FFFF"))
+        );
+    }
+
+    #[test]
+    fn test_validate_color() {
+        assert!(validate_color("theme.ui", "lighten($blue, 10%)").is_ok());
+        assert!(validate_color("theme.base", "lighten(#000000, 10%)").is_err());
+    }
+
+    #[test]
+    fn test_color_components() {
+        assert_eq!(
+            color_components(&Color::rgb8(224, 108, 117)),
+            "rgb(224, 108, 117)  hsl(355, 65%, 65%)"
+        );
+        assert_eq!(
+            color_components(&Color::rgb8(0, 128, 0)),
+            "rgb(0, 128, 0)  hsl(120, 100%, 25%)"
+        );
+    }
+
+    #[test]
+    fn test_settings_controller() {
+        let path = std::env::temp_dir().join(format!(
+            "lapce-settings-controller-{}.toml",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut controller =
+            SettingsController::with_file(Config::default(), path.clone());
+
+        assert!(controller
+            .set("editor", "font-size", serde_json::json!(14))
+            .is_ok());
+        assert_eq!(
+            controller.get("editor", "font-size"),
+            Some(serde_json::json!(14))
+        );
+        assert!(controller
+            .set("editor", "font-size", serde_json::json!(1.5))
+            .is_err());
+        assert!(controller
+            .set("editor", "font-size", serde_json::json!("14"))
+            .is_err());
+        assert!(controller
+            .set("editor", "no-such-setting", serde_json::json!(1))
+            .is_err());
+        // Out of the range Lapce uses, or empty while it's required
+        assert!(controller
+            .set("editor", "font-size", serde_json::json!(0))
+            .is_err());
+        assert!(controller
+            .set("lapce", "color-theme", serde_json::json!(" "))
+            .is_err());
+
+        assert!(controller
+            .set("editor", "font-size", serde_json::Value::Null)
+            .is_ok());
+        assert_eq!(
+            controller.get("editor", "font-size"),
+            Config::default().setting_value("editor", "font-size")
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_numeric_input_status() {
+        let status = |kind, parent, key, content| {
+            numeric_input_status(&kind, parent, key, content)
+        };
+        assert_eq!(
+            status(SettingsValueKind::Integer, "ui", "font-size", "14"),
+            Ok("14 px".to_string())
+        );
+        assert_eq!(
+            status(SettingsValueKind::Integer, "ui", "font-size", "40"),
+            Err("Lapce uses 32 or less".to_string())
+        );
+        assert_eq!(
+            status(SettingsValueKind::Integer, "editor", "tab-width", "0"),
+            Err("Lapce uses 1 or more".to_string())
+        );
+        assert!(
+            status(SettingsValueKind::Float, "editor", "line-height", "x").is_err()
+        );
+    }
+
+    #[test]
+    fn test_settings_map_value() {
+        let entries = |entries: &[(&str, &str)]| -> Vec<(String, String)> {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            settings_map_value(&entries(&[("*.h", "c"), (" ", ""), ("*.tpp", "")])),
+            Ok(serde_json::json!({"*.h": "c", "*.tpp": ""}))
+        );
+        assert!(settings_map_value(&entries(&[("", "c")])).is_err());
+        assert!(
+            settings_map_value(&entries(&[("*.h", "c"), ("*.h ", "cpp")])).is_err()
+        );
+    }
+
+    #[test]
+    fn test_stores_settled_input() {
+        let kind = SettingsValueKind::String;
+        assert!(stores_settled_input(&kind, "editor", "font-family"));
+        assert!(!stores_settled_input(&kind, "theme", "name"));
+        let kind = SettingsValueKind::MapEntry;
+        assert!(!stores_settled_input(&kind, "editor", "font-family"));
+    }
+
+    #[test]
+    fn test_parse_setting_input() {
+        let mut config = Config::default();
+        config.available_themes.insert(
+            "lapce light".to_string(),
+            ("Lapce Light".to_string(), ::config::Config::default()),
+        );
+
+        let kind = SettingsValueKind::String;
+        assert!(
+            parse_setting_input(&config, &kind, "lapce", "color-theme", " ")
+                .is_err()
+        );
+        assert_eq!(
+            parse_setting_input(
+                &config,
+                &kind,
+                "lapce",
+                "color-theme",
+                "Lapce Light"
+            ),
+            Ok(serde_json::json!("Lapce Light"))
+        );
+        assert_eq!(
+            parse_setting_input(&config, &kind, "lapce", "color-theme", "Nope"),
+            Err("There's no theme named Nope".to_string())
+        );
+        assert_eq!(
+            parse_setting_input(&config, &kind, "terminal", "shell", ""),
+            Ok(serde_json::json!(""))
+        );
+        assert_eq!(
+            parse_setting_input(&config, &kind, "editor", "render-whitespace", "x"),
+            Err("Expected one of none, all, boundary, trailing".to_string())
+        );
+
+        let kind = SettingsValueKind::Integer;
+        assert_eq!(
+            parse_setting_input(&config, &kind, "editor", "font-size", "0"),
+            Err(Config::setting_range_error("editor", "font-size", 0.0).unwrap())
+        );
+
+        let kind = SettingsValueKind::Json;
+        assert_eq!(
+            parse_setting_input(
+                &config,
+                &kind,
+                "ui",
+                "settings-sections",
+                "\"abc\""
+            ),
+            Err("Expected a list".to_string())
+        );
+        assert!(
+            parse_setting_input(&config, &kind, "ui", "settings-sections", "3")
+                .is_err()
+        );
+        assert_eq!(
+            parse_setting_input(
+                &config,
+                &kind,
+                "ui",
+                "settings-sections",
+                "[\"core\"]"
+            ),
+            Ok(serde_json::json!(["core"]))
+        );
+    }
+
+    #[test]
+    fn test_backup_restore_preview() {
+        let change = |table: &str, key: &str, old, new| SettingChange {
+            table: table.to_string(),
+            key: key.to_string(),
+            old,
+            new,
+            unset: false,
+        };
+        let changes = [
+            change("ui", "font-size", None, serde_json::json!(14)),
+            change(
+                "editor",
+                "tab-width",
+                Some(serde_json::json!(2)),
+                serde_json::json!(4),
+            ),
+            SettingChange {
+                unset: true,
+                ..change(
+                    "editor",
+                    "font-size",
+                    Some(serde_json::json!(16)),
+                    serde_json::json!(13),
+                )
+            },
+        ];
+        let text = backup_restore_preview(Path::new("backups/1"), &changes);
+        assert!(text.contains("[editor]\ntab-width = 4 # now 2\n"));
+        assert!(
+            text.contains("# unset, back to the default font-size = 13 # now 16\n")
+        );
+        assert!(text.contains("[ui]\nfont-size = 14 # now unset\n"));
+        assert!(text.find("[editor]") < text.find("[ui]"));
+    }
+
+    #[test]
+    fn test_theme_map_changes() {
+        let mut config = Config::default();
+        config
+            .theme
+            .ui
+            .insert("background".to_string(), "#000000".to_string());
+        config
+            .theme
+            .syntax
+            .insert("keyword".to_string(), "#000000".to_string());
+        let theme = serde_json::json!({
+            "ui": { "background": "#ffffff", "unknown": "#ffffff" },
+            "syntax": { "keyword": "#ffffff" },
+        });
+        let theme = theme.as_object().unwrap();
+
+        assert_eq!(
+            theme_map_changes(theme, &["syntax"], &config),
+            Some(vec![(
+                "theme.syntax".to_string(),
+                "keyword".to_string(),
+                "#ffffff".to_string()
+            )])
+        );
+        assert_eq!(
+            theme_map_changes(theme, &THEME_MAPS, &config)
+                .unwrap()
+                .len(),
+            2
+        );
+        // A theme without the maps is told apart from one without changes
+        assert_eq!(theme_map_changes(theme, &["base"], &config), None);
+    }
+
+    #[test]
+    fn test_settings_change_age() {
+        let now = 1_700_000_000;
+        assert_eq!(settings_change_age(now - 5, now), "just now");
+        assert_eq!(settings_change_age(now - 60, now), "1 minute ago");
+        assert_eq!(
+            settings_change_age(now - 3 * 3_600 - 10, now),
+            "3 hours ago"
+        );
+        assert_eq!(settings_change_age(now - 86_400 * 40, now), "1 month ago");
+        // A clock set back doesn't put changes in the future
+        assert_eq!(settings_change_age(now + 100, now), "just now");
+    }
+
+    #[test]
+    fn test_settings_revert_summary() {
+        let reverts = [
+            (
+                "editor".to_string(),
+                "font-size".to_string(),
+                Some(serde_json::json!(13)),
+            ),
+            ("ui".to_string(), "scale".to_string(), None),
+        ];
+        assert_eq!(
+            settings_revert_summary(&reverts),
+            "editor.font-size back to 13\nui.scale back to its default"
+        );
+        assert_eq!(settings_revert_summary(&[]), "");
+    }
+
+    #[test]
+    fn test_lists_setting() {
+        let mut preferences = SettingsPanelPreferences::default();
+        assert!(preferences.lists_setting("editor", "show-breadcrumbs"));
+        assert!(!preferences.lists_setting("editor", "show-bread-crumbs"));
+        assert!(!preferences.lists_setting("editor", "blink-interval"));
+        preferences.show_deprecated = true;
+        assert!(preferences.lists_setting("editor", "show-bread-crumbs"));
+        assert!(!preferences.lists_setting("editor", "blink-interval"));
+    }
+
+    #[test]
+    fn test_deprecation_note() {
+        assert_eq!(
+            deprecation_note("editor.show-breadcrumbs"),
+            "Deprecated, use editor.show-breadcrumbs instead"
+        );
+        assert_eq!(
+            deprecation_note(""),
+            "Deprecated, this setting will be removed"
+        );
+    }
+
+    #[test]
+    fn test_toggle_descriptions() {
+        let mut preferences = SettingsPanelPreferences::default();
+        assert!(!preferences.descriptions_expanded("editor"));
+        preferences.toggle_descriptions("editor");
+        preferences.toggle_descriptions("ui");
+        assert!(preferences.descriptions_expanded("editor"));
+        assert!(preferences.descriptions_expanded("ui"));
+        preferences.toggle_descriptions("editor");
+        assert!(!preferences.descriptions_expanded("editor"));
+        assert!(preferences.descriptions_expanded("ui"));
+    }
+
+    #[test]
+    fn test_text_size_readout() {
+        assert_eq!(
+            text_size_readout("fn main() {\n    $0\n}"),
+            "20 characters, 5 words, 3 lines"
+        );
+        assert_eq!(text_size_readout("one"), "3 characters, 1 word, 1 line");
+    }
+
+    #[test]
+    fn test_record_search() {
+        let mut settings = LapceSettingsPanelData::default();
+        assert!(settings.record_search("font"));
+        assert!(settings.record_search(" tab "));
+        assert!(!settings.record_search("tab"));
+        assert!(!settings.record_search("  "));
+        assert!(settings.record_search("font"));
+        assert_eq!(settings.search_history, vec!["font", "tab"]);
+        for i in 0..LapceSettingsPanelData::SEARCH_HISTORY_LIMIT {
+            settings.record_search(&i.to_string());
+        }
+        assert_eq!(
+            settings.search_history.len(),
+            LapceSettingsPanelData::SEARCH_HISTORY_LIMIT
+        );
+        assert_eq!(settings.search_history[0], "9");
+    }
+
+    #[test]
+    fn test_record_theme_change() {
+        let mut recent = Vec::new();
+        record_theme_change(&mut recent, "Lapce Dark", "Lapce Light");
+        assert_eq!(recent, vec!["Lapce Light", "Lapce Dark"]);
+        record_theme_change(&mut recent, "Lapce Light", "Lapce Dark");
+        assert_eq!(recent, vec!["Lapce Dark", "Lapce Light"]);
+        record_theme_change(&mut recent, "lapce dark", "Solarized");
+        assert_eq!(recent, vec!["Solarized", "Lapce Dark"]);
+        // A theme set in the settings file by hand is what's switched back to
+        record_theme_change(&mut recent, "Nord", "Solarized");
+        assert_eq!(recent, vec!["Solarized", "Nord"]);
+    }
+}
@@ -195,6 +195,9 @@ fn handle_notification(&mut self, rpc: ProxyNotification) {
             EnableVolt { volt } => {
                 let _ = self.catalog_rpc.start_volt(volt);
             }
+            SettingsChanged { change } => {
+                self.catalog_rpc.settings_changed(change);
+            }
             GitCommit { message, diffs } => {
                 if let Some(workspace) = self.workspace.as_ref() {
                     match git_commit(workspace, &message, diffs) {
@@ -235,6 +235,7 @@ fn new(
             workspace.clone(),
             pwd,
             volt_id,
+            false,
             document_selector,
             server_rpc.clone(),
             plugin_rpc.clone(),
@@ -7,7 +7,7 @@
 use crossbeam_channel::{Receiver, Sender};
 use dyn_clone::DynClone;
 use lapce_rpc::core::CoreRpcHandler;
-use lapce_rpc::plugin::{PluginId, VoltInfo, VoltMetadata};
+use lapce_rpc::plugin::{PluginId, SettingsChange, VoltInfo, VoltMetadata};
 use lapce_rpc::proxy::ProxyRpcHandler;
 use lapce_rpc::style::LineStyle;
 use lapce_rpc::{RequestId, RpcError};
@@ -92,6 +92,14 @@ pub enum PluginCatalogRpc {
     Shutdown,
 }
 
+/// Notifies plugins that a setting was changed from the settings panel
+pub enum SettingsChanged {}
+
+impl Notification for SettingsChanged {
+    type Params = SettingsChange;
+    const METHOD: &'static str = "lapce/settingsChanged";
+}
+
 pub enum PluginCatalogNotification {
     PluginServerLoaded(PluginServerRpcHandler),
     InstallVolt(VoltInfo),
@@ -820,6 +828,10 @@ pub fn plugin_server_loaded(
         ))
     }
 
+    pub fn settings_changed(&self, change: SettingsChange) {
+        self.server_notification(SettingsChanged::METHOD, change, None, None);
+    }
+
     pub fn install_volt(&self, volt: VoltInfo) -> Result<()> {
         self.catalog_notification(PluginCatalogNotification::InstallVolt(volt))
     }
@@ -898,18 +910,16 @@ pub fn download_volt(volt: VoltInfo, wasm: bool) -> Result<VoltMetadata> {
             std::io::copy(&mut resp, &mut file)?;
         }
     }
-    if let Some(themes) = meta.themes.as_ref() {
-        for theme in themes {
-            let url = url.join(theme)?;
-            {
-                let mut resp = reqwest::blocking::get(url)?;
-                let mut file = std::fs::OpenOptions::new()
-                    .create(true)
-                    .truncate(true)
-                    .write(true)
-                    .open(path.join(&theme))?;
-                std::io::copy(&mut resp, &mut file)?;
-            }
+    for file_name in meta.themes.iter().chain(meta.keymaps.iter()).flatten() {
+        let url = url.join(file_name)?;
+        {
+            let mut resp = reqwest::blocking::get(url)?;
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(true)
+                .write(true)
+                .open(path.join(&file_name))?;
+            std::io::copy(&mut resp, &mut file)?;
         }
     }
 
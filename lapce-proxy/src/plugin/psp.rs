@@ -48,7 +48,7 @@
 
 use super::{
     lsp::{DocumentFilter, LspClient},
-    PluginCatalogRpcHandler,
+    PluginCatalogRpcHandler, SettingsChanged,
 };
 
 pub enum ResponseHandler<Resp, Error> {
@@ -526,6 +526,9 @@ struct ServerRegistrations {
 
 pub struct PluginHostHandler {
     volt_id: String,
+    /// Whether the handler belongs to a wasm plugin rather than a language
+    /// server, only wasm plugins handle Lapce's own notifications
+    wasm: bool,
     pwd: Option<PathBuf>,
     pub(crate) workspace: Option<PathBuf>,
     document_selector: Vec<DocumentFilter>,
@@ -540,6 +543,7 @@ pub fn new(
         workspace: Option<PathBuf>,
         pwd: Option<PathBuf>,
         volt_id: String,
+        wasm: bool,
         document_selector: DocumentSelector,
         server_rpc: PluginServerRpcHandler,
         catalog_rpc: PluginCatalogRpcHandler,
@@ -552,6 +556,7 @@ pub fn new(
             pwd,
             workspace,
             volt_id,
+            wasm,
             document_selector,
             catalog_rpc,
             server_rpc,
@@ -591,6 +596,7 @@ pub fn method_registered(&mut self, method: &'static str) -> bool {
         match method {
             Initialize::METHOD => true,
             Initialized::METHOD => true,
+            SettingsChanged::METHOD => self.wasm,
             Completion::METHOD => {
                 self.server_capabilities.completion_provider.is_some()
             }
@@ -257,13 +257,13 @@ pub fn load_volt(path: &Path) -> Result<VoltMetadata> {
                 .to_string(),
         )
     });
-    meta.themes = meta.themes.as_ref().map(|themes| {
-        themes
+    let resolve = |files: &Vec<String>| -> Vec<String> {
+        files
             .iter()
-            .filter_map(|theme| {
+            .filter_map(|file| {
                 Some(
                     path.parent()?
-                        .join(theme)
+                        .join(file)
                         .canonicalize()
                         .ok()?
                         .to_str()?
@@ -271,7 +271,9 @@ pub fn load_volt(path: &Path) -> Result<VoltMetadata> {
                 )
             })
             .collect()
-    });
+    };
+    meta.themes = meta.themes.as_ref().map(resolve);
+    meta.keymaps = meta.keymaps.as_ref().map(resolve);
     Ok(meta)
 }
 
@@ -409,6 +411,7 @@ pub fn start_volt(
             workspace,
             meta.dir.clone(),
             meta.id(),
+            true,
             Vec::new(),
             rpc.clone(),
             plugin_rpc.clone(),
@@ -23,6 +23,22 @@ pub struct PluginConfiguration {
     pub description: String,
 }
 
+/// A setting that was changed from the settings panel, sent to plugins
+/// with the `lapce/settingsChanged` notification.
+///
+/// Every update or reset of a single setting fires one notification, so
+/// resetting a selection of theme colors fires one per color.
+#[derive(Deserialize, Clone, Debug, Serialize)]
+pub struct SettingsChange {
+    /// The settings table, e.g. `editor` or `theme.ui`
+    pub kind: String,
+    pub name: String,
+    /// The value in effect before the change
+    pub old: Option<Value>,
+    /// The new value, `None` if the setting was reset to its default
+    pub new: Option<Value>,
+}
+
 #[derive(Deserialize, Clone, Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct VoltInfo {
@@ -50,6 +66,9 @@ pub struct VoltMetadata {
     pub description: String,
     pub wasm: Option<String>,
     pub themes: Option<Vec<String>>,
+    /// Keymaps files contributing key bindings, in the format of the user's
+    /// keymaps.toml
+    pub keymaps: Option<Vec<String>>,
     pub dir: Option<PathBuf>,
 }
 
@@ -21,7 +21,7 @@
 use crate::{
     buffer::BufferId,
     file::FileNodeItem,
-    plugin::{PluginId, VoltInfo, VoltMetadata},
+    plugin::{PluginId, SettingsChange, VoltInfo, VoltMetadata},
     source_control::FileDiff,
     style::SemanticStyles,
     terminal::TermId,
@@ -182,6 +182,9 @@ pub enum ProxyNotification {
     EnableVolt {
         volt: VoltInfo,
     },
+    SettingsChanged {
+        change: SettingsChange,
+    },
     GitCommit {
         message: String,
         diffs: Vec<FileDiff>,
@@ -428,6 +431,10 @@ pub fn enable_volt(&self, volt: VoltInfo) {
         self.notification(ProxyNotification::EnableVolt { volt });
     }
 
+    pub fn settings_changed(&self, change: SettingsChange) {
+        self.notification(ProxyNotification::SettingsChanged { change });
+    }
+
     pub fn shutdown(&self) {
         self.notification(ProxyNotification::Shutdown {});
         let _ = self.tx.send(ProxyRpc::Shutdown);
@@ -34,6 +34,7 @@
     data::LapceTabData,
     editor::{LapceEditorBufferData, Syntax},
     menu::MenuItem,
+    settings::settings_key_at_line,
 };
 use lsp_types::{CodeActionOrCommand, DiagnosticSeverity};
 
@@ -282,7 +283,7 @@ fn right_click(
             editor_data.single_click(ctx, mouse_event, config);
         }
 
-        let menu_items = vec![
+        let mut menu_items = vec![
             MenuKind::Item(MenuItem {
                 desc: None,
                 command: LapceCommand {
@@ -337,6 +338,31 @@ fn right_click(
             }),
         ];
 
+        if let BufferContent::File(path) = &editor_data.editor.content {
+            if Config::is_settings_file(path) {
+                let buffer = editor_data.doc.buffer();
+                if let Some(field) = settings_key_at_line(
+                    &buffer.to_string(),
+                    buffer.line_of_offset(offset),
+                ) {
+                    menu_items.insert(
+                        0,
+                        MenuKind::Item(MenuItem {
+                            desc: None,
+                            command: LapceCommand {
+                                kind: CommandKind::Workbench(
+                                    LapceWorkbenchCommand::RevealSettingInPanel,
+                                ),
+                                data: Some(serde_json::json!(field)),
+                            },
+                            enabled: true,
+                        }),
+                    );
+                    menu_items.insert(1, MenuKind::Separator);
+                }
+            }
+        }
+
         ctx.submit_command(Command::new(
             LAPCE_UI_COMMAND,
             LapceUICommand::ShowMenu(
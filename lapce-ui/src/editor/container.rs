@@ -50,7 +50,7 @@ pub fn new(view_id: WidgetId, editor_id: WidgetId) -> Self {
     }
 
     fn show_bread_crumbs(&self, data: &LapceTabData) -> bool {
-        if !data.config.editor.show_bread_crumbs {
+        if !data.config.editor.show_breadcrumbs() {
             return false;
         }
 
@@ -24,7 +24,7 @@
     keypress::KeyPressFocus,
     palette::PaletteStatus,
     panel::{PanelData, PanelKind},
-    settings::SettingsValueKind,
+    settings::{parse_setting_input, stores_settled_input},
 };
 
 use crate::{
@@ -59,11 +59,13 @@ pub fn editor_tab_child_widget(
             settings_widget_id,
             editor_tab_id,
             keymap_input_view_id,
+            settings_input_view_id,
         } => LapceSettingsPanel::new(
             data,
             *settings_widget_id,
             *editor_tab_id,
             *keymap_input_view_id,
+            *settings_input_view_id,
         )
         .boxed(),
         EditorTabChild::Plugin {
@@ -606,33 +608,58 @@ fn event(
             }
             Event::Timer(id) if self.last_idle_timer == *id => {
                 ctx.set_handled();
+                self.last_idle_timer = TimerToken::INVALID;
                 let editor_data = data.editor_view_content(self.view_id);
-                if let BufferContent::SettingsValue(_, kind, parent, key) =
+                if let BufferContent::SettingsValue(name, kind, parent, key) =
                     &editor_data.editor.content
                 {
                     let content = editor_data.doc.buffer().to_string();
-                    let new_value = match kind {
-                        SettingsValueKind::String => {
-                            Some(serde_json::json!(content))
-                        }
-                        SettingsValueKind::Integer => {
-                            content.parse::<i64>().ok().map(|n| serde_json::json!(n))
+                    match parse_setting_input(
+                        &data.config,
+                        kind,
+                        parent,
+                        key,
+                        &content,
+                    ) {
+                        Ok(_) if !stores_settled_input(kind, parent, key) => {}
+                        Ok(new_value) => {
+                            if data.settings.value_errors.contains_key(name) {
+                                Arc::make_mut(&mut data.settings)
+                                    .value_errors
+                                    .remove(name);
+                            }
+                            // Typed theme colors wait for the Apply link
+                            if data.config.ui.confirm_theme_changes()
+                                && parent.starts_with("theme.")
+                            {
+                                let current = data.config.setting_value(parent, key);
+                                Arc::make_mut(&mut data.settings)
+                                    .buffer_theme_change(
+                                        name,
+                                        (
+                                            parent.to_string(),
+                                            key.to_string(),
+                                            new_value,
+                                        ),
+                                        current.as_ref(),
+                                    );
+                            } else {
+                                ctx.submit_command(Command::new(
+                                    LAPCE_UI_COMMAND,
+                                    LapceUICommand::UpdateSettingsFile(
+                                        parent.to_string(),
+                                        key.to_string(),
+                                        new_value,
+                                    ),
+                                    Target::Widget(data.id),
+                                ));
+                            }
                         }
-                        SettingsValueKind::Float => {
-                            content.parse::<f64>().ok().map(|n| serde_json::json!(n))
+                        Err(err) => {
+                            Arc::make_mut(&mut data.settings)
+                                .value_errors
+                                .insert(name.to_string(), err);
                         }
-                        SettingsValueKind::Bool => None,
-                    };
-                    if let Some(new_value) = new_value {
-                        ctx.submit_command(Command::new(
-                            LAPCE_UI_COMMAND,
-                            LapceUICommand::UpdateSettingsFile(
-                                parent.to_string(),
-                                key.to_string(),
-                                new_value,
-                            ),
-                            Target::Widget(data.id),
-                        ));
                     }
                 }
             }
@@ -774,6 +801,20 @@ fn lifecycle(
                                 Target::Auto,
                             ));
                         }
+                        BufferContent::SettingsValue(..)
+                            if data.config.ui.save_settings_on_focus_loss() =>
+                        {
+                            ctx.submit_command(Command::new(
+                                LAPCE_COMMAND,
+                                LapceCommand {
+                                    kind: CommandKind::Workbench(
+                                        LapceWorkbenchCommand::FlushSettings,
+                                    ),
+                                    data: None,
+                                },
+                                Target::Auto,
+                            ));
+                        }
                         _ => {}
                     }
                 } else if editor.content.is_palette()
@@ -825,7 +866,12 @@ fn update(
         let editor_data = data.editor_view_content(self.view_id);
 
         if let BufferContent::SettingsValue(..) = &editor_data.editor.content {
-            if !editor_data.doc.buffer().is_pristine()
+            // The flush stored the typed value already
+            if data.settings.flush_id != old_data.settings.flush_id {
+                self.last_idle_timer = TimerToken::INVALID;
+            }
+            if !data.config.ui.save_settings_on_focus_loss()
+                && !editor_data.doc.buffer().is_pristine()
                 && (editor_data.doc.buffer().len()
                     != old_editor_data.doc.buffer().len()
                     || editor_data.doc.buffer().text().slice_to_cow(..)
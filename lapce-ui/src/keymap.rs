@@ -1,37 +1,65 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use druid::{
     kurbo::Line,
     piet::{Text, TextAttribute, TextLayout, TextLayoutBuilder},
-    BoxConstraints, Command, Data, Env, Event, EventCtx, FontWeight, LayoutCtx,
-    LifeCycle, LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Size, Target,
-    UpdateCtx, Widget, WidgetExt, WidgetId,
+    BoxConstraints, Command, Data, Env, Event, EventCtx, FontWeight, KbKey,
+    KeyEvent, LayoutCtx, LifeCycle, LifeCycleCtx, MouseButton, PaintCtx, Point,
+    Rect, RenderContext, Size, Target, UpdateCtx, Widget, WidgetExt, WidgetId,
 };
 use lapce_core::mode::Modes;
 use lapce_data::{
-    command::{LapceUICommand, LAPCE_UI_COMMAND},
+    command::{
+        CommandKind, LapceCommand, LapceUICommand, LapceWorkbenchCommand,
+        COMMAND_CATEGORIES, LAPCE_UI_COMMAND,
+    },
     config::LapceTheme,
     data::LapceTabData,
     keypress::{
         paint_key, Alignment, DefaultKeyPressHandler, KeyMap, KeyPress, KeyPressData,
     },
+    menu::{MenuItem, MenuKind},
+};
+
+use crate::{
+    editor::view::LapceEditorView, scroll::LapceScroll, split::LapceSplit,
+    svg::get_svg,
 };
 
-use crate::{editor::view::LapceEditorView, scroll::LapceScroll, split::LapceSplit};
+/// A row of the key bindings list
+enum KeymapRow {
+    /// The header of a category, with the number of commands in it
+    Header(&'static str, usize),
+    Keymap(KeyMap),
+    Command(LapceCommand),
+}
 
 pub struct LapceKeymap {
     widget_id: WidgetId,
     active_keymap: Option<(KeyMap, Vec<KeyPress>)>,
+    /// The binding whose "when" condition is being typed, with the text typed
+    /// so far
+    active_condition: Option<(KeyMap, String)>,
+    /// The categories whose commands are hidden under their header
+    collapsed: HashSet<&'static str>,
+    /// The rows of the list, rebuilt when the search, the conflicts-only
+    /// toggle, the bindings or the collapsed categories change
+    rows: Vec<KeymapRow>,
     keymap_confirm: Rect,
     keymap_cancel: Rect,
     line_height: f64,
 }
 
 impl LapceKeymap {
+    const KEYPRESS_WIDTH: f64 = 200.0;
+
     pub fn new_split(keymap_input_view_id: WidgetId) -> LapceSplit {
         let keymap = Self {
             widget_id: WidgetId::next(),
             active_keymap: None,
+            active_condition: None,
+            collapsed: HashSet::new(),
+            rows: Vec::new(),
             line_height: 35.0,
             keymap_confirm: Rect::ZERO,
             keymap_cancel: Rect::ZERO,
@@ -47,6 +75,7 @@ pub fn new_split(keymap_input_view_id: WidgetId) -> LapceSplit {
         let split = LapceSplit::new(WidgetId::next())
             .horizontal()
             .with_child(input.boxed(), None, 100.0)
+            .with_child(LapceKeymapTester::new().boxed(), None, 100.0)
             .with_child(header.boxed(), None, 100.0)
             .with_flex_child(keymap.boxed(), None, 1.0, false);
 
@@ -70,35 +99,330 @@ fn mouse_down(&mut self, ctx: &mut EventCtx, pos: Point, data: &LapceTabData) {
             }
             return;
         }
-        let commands_with_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_with_keymap
+        let i = (pos.y / self.line_height).floor() as usize;
+        self.active_condition = None;
+        // Clicking the condition of a binding types a new one
+        if let Some(KeymapRow::Keymap(keymap)) = self.rows.get(i) {
+            if pos.x >= Self::condition_x(ctx.size().width, data) {
+                self.active_condition =
+                    Some((keymap.clone(), keymap.when.clone().unwrap_or_default()));
+                return;
+            }
+        }
+        let mut toggled = false;
+        let active_keymap = match self.rows.get(i) {
+            Some(KeymapRow::Header(category, _)) => {
+                // While searching, every category with matches is expanded
+                if data.keypress.filter_pattern.is_empty() {
+                    if !self.collapsed.remove(category) {
+                        self.collapsed.insert(*category);
+                    }
+                    toggled = true;
+                }
+                None
+            }
+            Some(KeymapRow::Keymap(keymap)) => Some(keymap.clone()),
+            Some(KeymapRow::Command(command)) => Some(KeyMap {
+                command: command.kind.str().to_string(),
+                key: Vec::new(),
+                modes: Modes::empty(),
+                when: None,
+                source: None,
+            }),
+            None => None,
+        };
+        if toggled {
+            self.rows = self.build_rows(data);
+            ctx.request_layout();
+        }
+        if let Some(keymap) = active_keymap {
+            self.active_keymap = Some((keymap, Vec::new()));
+        }
+    }
+
+    /// The rows of the list: the commands matching the search, or all of
+    /// them, grouped by category under a header. The bound commands come
+    /// first in each category, and a collapsed category only shows its
+    /// header unless searching. Only the conflicting bindings are listed
+    /// when that's toggled on in the header.
+    fn build_rows(&self, data: &LapceTabData) -> Vec<KeymapRow> {
+        let filtering = !data.keypress.filter_pattern.is_empty();
+        let conflicts_only = data.keypress.conflicts_only;
+        let (commands_with_keymap, commands_without_keymap) = if filtering {
+            (
+                &data.keypress.filtered_commands_with_keymap,
+                &data.keypress.filtered_commands_without_keymap,
+            )
         } else {
-            &data.keypress.filtered_commands_with_keymap
+            (
+                &data.keypress.commands_with_keymap,
+                &data.keypress.commands_without_keymap,
+            )
         };
 
-        let commands_without_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_without_keymap
+        let mut rows = Vec::new();
+        for category in COMMAND_CATEGORIES {
+            let keymaps: Vec<&KeyMap> = commands_with_keymap
+                .iter()
+                .filter(|keymap| {
+                    data.keypress
+                        .commands
+                        .get(&keymap.command)
+                        .map(|cmd| cmd.kind.category())
+                        == Some(category)
+                })
+                .filter(|keymap| {
+                    !conflicts_only
+                        || data.keypress.conflicting_keymaps.contains(*keymap)
+                })
+                .collect();
+            let commands: Vec<&LapceCommand> = commands_without_keymap
+                .iter()
+                .filter(|cmd| !conflicts_only && cmd.kind.category() == category)
+                .collect();
+            let count = keymaps.len() + commands.len();
+            if count == 0 {
+                continue;
+            }
+            rows.push(KeymapRow::Header(category, count));
+            if filtering || !self.collapsed.contains(category) {
+                rows.extend(keymaps.into_iter().cloned().map(KeymapRow::Keymap));
+                rows.extend(commands.into_iter().cloned().map(KeymapRow::Command));
+            }
+        }
+        rows
+    }
+
+    /// Paint the header of a category on row `i`, with whether it's collapsed
+    /// and the number of commands in it
+    fn paint_header(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        i: usize,
+        category: &str,
+        count: usize,
+    ) {
+        let collapsed = data.keypress.filter_pattern.is_empty()
+            && self.collapsed.contains(category);
+        let y = i as f64 * self.line_height;
+        let icon_size = 12.0;
+        let icon_rect = Size::new(icon_size, icon_size)
+            .to_rect()
+            .with_origin(Point::new(10.0, y + (self.line_height - icon_size) / 2.0));
+        let svg = if collapsed {
+            get_svg("chevron-right.svg")
         } else {
-            &data.keypress.filtered_commands_without_keymap
+            get_svg("chevron-down.svg")
         };
+        if let Some(svg) = svg {
+            ctx.draw_svg(
+                &svg,
+                icon_rect,
+                Some(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
+                ),
+            );
+        }
+        let text_layout = ctx
+            .text()
+            .new_text_layout(format!("{category} ({count})"))
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        ctx.draw_text(
+            &text_layout,
+            Point::new(
+                icon_rect.x1 + 8.0,
+                y + text_layout.y_offset(self.line_height),
+            ),
+        );
+    }
 
+    /// Offer copying the binding of the row at `pos` to paste it into a
+    /// keymaps file
+    fn context_menu(&self, ctx: &mut EventCtx, pos: Point, data: &LapceTabData) {
         let i = (pos.y / self.line_height).floor() as usize;
-        if i < commands_with_keymap.len() {
-            let keymap = commands_with_keymap[i].clone();
-            self.active_keymap = Some((keymap, Vec::new()));
-        } else {
-            let j = i - commands_with_keymap.len();
-            if let Some(command) = commands_without_keymap.get(j) {
-                self.active_keymap = Some((
-                    KeyMap {
-                        command: command.kind.str().to_string(),
-                        key: Vec::new(),
-                        modes: Modes::empty(),
-                        when: None,
-                    },
-                    Vec::new(),
-                ));
+        if let Some(KeymapRow::Keymap(keymap)) = self.rows.get(i) {
+            let item = MenuItem {
+                desc: Some("Copy Keybinding as TOML".to_string()),
+                command: LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::CopyKeybinding,
+                    ),
+                    data: Some(serde_json::json!(keymap.to_toml_snippet())),
+                },
+                enabled: true,
+            };
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::ShowMenu(
+                    ctx.to_window(pos),
+                    Arc::new(vec![MenuKind::Item(item)]),
+                ),
+                Target::Widget(data.id),
+            ));
+        }
+    }
+
+    /// Where the "when" conditions start, after the modes in modal editing
+    fn condition_x(width: f64, data: &LapceTabData) -> f64 {
+        width / 2.0
+            + 10.0
+            + if data.config.lapce.modal {
+                Self::KEYPRESS_WIDTH
+            } else {
+                0.0
+            }
+    }
+
+    /// Type the "when" condition being edited, Enter saves it if it's valid
+    /// and Escape stops editing it
+    fn condition_key_down(
+        &mut self,
+        ctx: &mut EventCtx,
+        key_event: &KeyEvent,
+        data: &LapceTabData,
+    ) {
+        let (keymap, text) = match self.active_condition.as_mut() {
+            Some(condition) => condition,
+            None => return,
+        };
+        match &key_event.key {
+            KbKey::Escape => self.active_condition = None,
+            KbKey::Enter => {
+                let when = text.trim();
+                if when.is_empty() || KeyPressData::condition_error(when).is_none() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::UpdateKeymapCondition(
+                            keymap.clone(),
+                            (!when.is_empty()).then(|| when.to_string()),
+                        ),
+                        Target::Widget(data.id),
+                    ));
+                    self.active_condition = None;
+                }
+            }
+            KbKey::Backspace => {
+                text.pop();
             }
+            KbKey::Character(c)
+                if !key_event.mods.ctrl()
+                    && !key_event.mods.alt()
+                    && !key_event.mods.meta() =>
+            {
+                text.push_str(c);
+            }
+            _ => return,
+        }
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    /// Paint the input of the "when" condition being typed on row `i`, in the
+    /// error color with the error if it isn't a valid condition
+    fn paint_condition_input(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        i: usize,
+        text: &str,
+    ) {
+        let size = ctx.size();
+        let y = i as f64 * self.line_height;
+        let x = Self::condition_x(size.width, data);
+        let rect = Rect::new(
+            x - 5.0,
+            y + 5.0,
+            size.width - 10.0,
+            y + self.line_height - 5.0,
+        );
+        let error = if text.trim().is_empty() {
+            None
+        } else {
+            KeyPressData::condition_error(text)
+        };
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::EDITOR_BACKGROUND),
+        );
+        ctx.stroke(
+            rect,
+            data.config.get_color_unchecked(if error.is_some() {
+                LapceTheme::LAPCE_ERROR
+            } else {
+                LapceTheme::EDITOR_CARET
+            }),
+            1.0,
+        );
+
+        let (content, color) = if text.is_empty() {
+            ("No condition", LapceTheme::EDITOR_DIM)
+        } else {
+            (text, LapceTheme::EDITOR_FOREGROUND)
+        };
+        let text_layout = ctx
+            .text()
+            .new_text_layout(content.to_string())
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(data.config.get_color_unchecked(color).clone())
+            .build()
+            .unwrap();
+        ctx.draw_text(
+            &text_layout,
+            Point::new(x, y + text_layout.y_offset(self.line_height)),
+        );
+        let caret_x = if text.is_empty() {
+            x
+        } else {
+            x + text_layout.size().width
+        };
+        ctx.stroke(
+            Line::new(
+                Point::new(caret_x, rect.y0 + 4.0),
+                Point::new(caret_x, rect.y1 - 4.0),
+            ),
+            data.config.get_color_unchecked(LapceTheme::EDITOR_CARET),
+            1.0,
+        );
+
+        if let Some(error) = error {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(error)
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::LAPCE_ERROR)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                Point::new(
+                    rect.x1 - 5.0 - text_layout.size().width,
+                    y + text_layout.y_offset(self.line_height),
+                ),
+            );
         }
     }
 
@@ -130,12 +454,23 @@ fn event(
             Event::MouseMove(_mouse_event) => {
                 ctx.set_handled();
             }
+            Event::MouseDown(mouse_event)
+                if mouse_event.button == MouseButton::Right
+                    && self.active_keymap.is_none() =>
+            {
+                ctx.set_handled();
+                self.request_focus(ctx, data);
+                self.context_menu(ctx, mouse_event.pos, data);
+            }
             Event::MouseDown(mouse_event) => {
                 ctx.set_handled();
                 self.request_focus(ctx, data);
                 self.mouse_down(ctx, mouse_event.pos, data);
                 ctx.request_paint();
             }
+            Event::KeyDown(key_event) if self.active_condition.is_some() => {
+                self.condition_key_down(ctx, key_event, data);
+            }
             Event::KeyDown(key_event) => {
                 if let Some((_keymap, keys)) = self.active_keymap.as_mut() {
                     if let Some(keypress) = KeyPressData::keypress(key_event) {
@@ -163,10 +498,13 @@ fn event(
     fn lifecycle(
         &mut self,
         _ctx: &mut LifeCycleCtx,
-        _event: &LifeCycle,
-        _data: &LapceTabData,
+        event: &LifeCycle,
+        data: &LapceTabData,
         _env: &Env,
     ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rows = self.build_rows(data);
+        }
     }
 
     fn update(
@@ -176,10 +514,15 @@ fn update(
         data: &LapceTabData,
         _env: &Env,
     ) {
-        if !data
-            .keypress
-            .commands_with_keymap
-            .same(&old_data.keypress.commands_with_keymap)
+        if !data.keypress.commands.same(&old_data.keypress.commands)
+            || !data
+                .keypress
+                .conflicting_keymaps
+                .same(&old_data.keypress.conflicting_keymaps)
+            || !data
+                .keypress
+                .commands_with_keymap
+                .same(&old_data.keypress.commands_with_keymap)
             || !data
                 .keypress
                 .commands_without_keymap
@@ -193,7 +536,9 @@ fn update(
                 .keypress
                 .filtered_commands_without_keymap
                 .same(&old_data.keypress.filtered_commands_without_keymap)
+            || data.keypress.conflicts_only != old_data.keypress.conflicts_only
         {
+            self.rows = self.build_rows(data);
             ctx.request_layout();
         }
     }
@@ -202,27 +547,12 @@ fn layout(
         &mut self,
         _ctx: &mut LayoutCtx,
         bc: &BoxConstraints,
-        data: &LapceTabData,
+        _data: &LapceTabData,
         _env: &Env,
     ) -> Size {
-        let commands_with_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_with_keymap
-        } else {
-            &data.keypress.filtered_commands_with_keymap
-        };
-
-        let commands_without_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_without_keymap
-        } else {
-            &data.keypress.filtered_commands_without_keymap
-        };
-
         Size::new(
             bc.max().width,
-            (self.line_height
-                * (commands_with_keymap.len() + commands_without_keymap.len())
-                    as f64)
-                .max(bc.max().height),
+            (self.line_height * self.rows.len() as f64).max(bc.max().height),
         )
     }
 
@@ -231,21 +561,9 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
         let rect = ctx.region().bounding_box();
         let start = (rect.y0 / self.line_height).floor() as usize;
         let end = (rect.y1 / self.line_height).ceil() as usize;
-        let keypress_width = 200.0;
-
-        let commands_with_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_with_keymap
-        } else {
-            &data.keypress.filtered_commands_with_keymap
-        };
+        let keypress_width = Self::KEYPRESS_WIDTH;
 
-        let commands_without_keymap = if data.keypress.filter_pattern.is_empty() {
-            &data.keypress.commands_without_keymap
-        } else {
-            &data.keypress.filtered_commands_without_keymap
-        };
-
-        let commands_with_keymap_len = commands_with_keymap.len();
+        let rows = &self.rows;
         for i in start..end + 1 {
             if i % 2 == 0 {
                 ctx.fill(
@@ -259,30 +577,62 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
                         .get_color_unchecked(LapceTheme::EDITOR_CURRENT_LINE),
                 );
             }
-            if i < commands_with_keymap_len {
-                let keymap = &commands_with_keymap[i];
-                if let Some(cmd) = data.keypress.commands.get(&keymap.command) {
-                    ctx.with_save(|ctx| {
-                        ctx.clip(Rect::new(
-                            0.0,
-                            i as f64 * self.line_height,
-                            size.width / 2.0 - keypress_width,
-                            (i + 1) as f64 * self.line_height,
-                        ));
+            match rows.get(i) {
+                Some(KeymapRow::Header(category, count)) => {
+                    self.paint_header(ctx, data, i, category, *count);
+                }
+                Some(KeymapRow::Keymap(keymap)) => {
+                    if let Some(cmd) = data.keypress.commands.get(&keymap.command) {
+                        ctx.with_save(|ctx| {
+                            ctx.clip(Rect::new(
+                                0.0,
+                                i as f64 * self.line_height,
+                                size.width / 2.0 - keypress_width,
+                                (i + 1) as f64 * self.line_height,
+                            ));
+                            let text_layout = ctx
+                                .text()
+                                .new_text_layout(
+                                    cmd.kind
+                                        .desc()
+                                        .unwrap_or_else(|| cmd.kind.str()),
+                                )
+                                .font(
+                                    data.config.ui.font_family(),
+                                    data.config.ui.font_size() as f64,
+                                )
+                                .text_color(
+                                    data.config
+                                        .get_color_unchecked(
+                                            LapceTheme::EDITOR_FOREGROUND,
+                                        )
+                                        .clone(),
+                                )
+                                .build()
+                                .unwrap();
+                            ctx.draw_text(
+                                &text_layout,
+                                Point::new(
+                                    10.0,
+                                    i as f64 * self.line_height
+                                        + text_layout.y_offset(self.line_height),
+                                ),
+                            );
+                        });
+                    }
+
+                    // Tag the bindings plugins added with the plugin
+                    if let Some(source) = keymap.source.as_ref() {
                         let text_layout = ctx
                             .text()
-                            .new_text_layout(
-                                cmd.kind.desc().unwrap_or_else(|| cmd.kind.str()),
-                            )
+                            .new_text_layout(source.to_string())
                             .font(
                                 data.config.ui.font_family(),
                                 data.config.ui.font_size() as f64,
                             )
                             .text_color(
                                 data.config
-                                    .get_color_unchecked(
-                                        LapceTheme::EDITOR_FOREGROUND,
-                                    )
+                                    .get_color_unchecked(LapceTheme::EDITOR_DIM)
                                     .clone(),
                             )
                             .build()
@@ -290,80 +640,109 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
                         ctx.draw_text(
                             &text_layout,
                             Point::new(
-                                10.0,
+                                size.width / 2.0
+                                    - keypress_width
+                                    - text_layout.size().width
+                                    - 10.0,
                                 i as f64 * self.line_height
                                     + text_layout.y_offset(self.line_height),
                             ),
                         );
-                    });
-                }
-
-                let origin = Point::new(
-                    size.width / 2.0 - keypress_width + 10.0,
-                    i as f64 * self.line_height + self.line_height / 2.0,
-                );
-                keymap.paint(ctx, origin, Alignment::Left, &data.config);
-
-                if let Some(condition) = keymap.when.as_ref() {
-                    let text_layout = ctx
-                        .text()
-                        .new_text_layout(condition.to_string())
-                        .font(
-                            data.config.ui.font_family(),
-                            data.config.ui.font_size() as f64,
-                        )
-                        .text_color(
-                            data.config
-                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                                .clone(),
-                        )
-                        .build()
-                        .unwrap();
-                    ctx.draw_text(
-                        &text_layout,
-                        Point::new(
-                            size.width / 2.0
-                                + 10.0
-                                + if data.config.lapce.modal {
-                                    keypress_width
-                                } else {
-                                    0.0
-                                },
-                            i as f64 * self.line_height
-                                + text_layout.y_offset(self.line_height),
-                        ),
-                    )
-                }
+                    }
 
-                if data.config.lapce.modal && !keymap.modes.is_empty() {
-                    let mut origin = Point::new(
-                        size.width / 2.0 + 10.0,
+                    let origin = Point::new(
+                        size.width / 2.0 - keypress_width + 10.0,
                         i as f64 * self.line_height + self.line_height / 2.0,
                     );
-                    let bits = [
-                        (Modes::INSERT, "Insert"),
-                        (Modes::NORMAL, "Normal"),
-                        (Modes::VISUAL, "Visual"),
-                        (Modes::TERMINAL, "Terminal"),
-                    ];
-                    for (bit, mode) in bits {
-                        if keymap.modes.contains(bit) {
-                            let (rect, text_layout, text_layout_pos) =
-                                paint_key(ctx, mode, origin, &data.config);
-                            ctx.draw_text(&text_layout, text_layout_pos);
-                            ctx.stroke(
-                                rect,
-                                data.config
-                                    .get_color_unchecked(LapceTheme::LAPCE_BORDER),
-                                1.0,
+                    keymap.paint(ctx, origin, Alignment::Left, &data.config);
+
+                    // Flag the keys another command is bound to as well
+                    if data.keypress.conflicting_keymaps.contains(keymap) {
+                        if let Some(svg) = get_svg("warning.svg") {
+                            let icon_size = 14.0;
+                            let icon_rect = Size::new(icon_size, icon_size)
+                                .to_rect()
+                                .with_origin(Point::new(
+                                    size.width / 2.0 - icon_size - 10.0,
+                                    i as f64 * self.line_height
+                                        + (self.line_height - icon_size) / 2.0,
+                                ));
+                            ctx.draw_svg(
+                                &svg,
+                                icon_rect,
+                                Some(
+                                    data.config
+                                        .get_color_unchecked(LapceTheme::LAPCE_WARN),
+                                ),
                             );
-                            origin += (rect.width() + 5.0, 0.0);
+                        }
+                    }
+
+                    if let Some((_, text)) = self
+                        .active_condition
+                        .as_ref()
+                        .filter(|(active, _)| active == keymap)
+                    {
+                        self.paint_condition_input(ctx, data, i, text);
+                    } else if let Some(condition) = keymap.when.as_ref() {
+                        // A condition that can't hold is flagged
+                        let color =
+                            if KeyPressData::condition_error(condition).is_some() {
+                                LapceTheme::LAPCE_ERROR
+                            } else {
+                                LapceTheme::EDITOR_FOREGROUND
+                            };
+                        let text_layout = ctx
+                            .text()
+                            .new_text_layout(condition.to_string())
+                            .font(
+                                data.config.ui.font_family(),
+                                data.config.ui.font_size() as f64,
+                            )
+                            .text_color(
+                                data.config.get_color_unchecked(color).clone(),
+                            )
+                            .build()
+                            .unwrap();
+                        ctx.draw_text(
+                            &text_layout,
+                            Point::new(
+                                Self::condition_x(size.width, data),
+                                i as f64 * self.line_height
+                                    + text_layout.y_offset(self.line_height),
+                            ),
+                        )
+                    }
+
+                    if data.config.lapce.modal && !keymap.modes.is_empty() {
+                        let mut origin = Point::new(
+                            size.width / 2.0 + 10.0,
+                            i as f64 * self.line_height + self.line_height / 2.0,
+                        );
+                        let bits = [
+                            (Modes::INSERT, "Insert"),
+                            (Modes::NORMAL, "Normal"),
+                            (Modes::VISUAL, "Visual"),
+                            (Modes::TERMINAL, "Terminal"),
+                        ];
+                        for (bit, mode) in bits {
+                            if keymap.modes.contains(bit) {
+                                let (rect, text_layout, text_layout_pos) =
+                                    paint_key(ctx, mode, origin, &data.config);
+                                ctx.draw_text(&text_layout, text_layout_pos);
+                                ctx.stroke(
+                                    rect,
+                                    data.config.get_color_unchecked(
+                                        LapceTheme::LAPCE_BORDER,
+                                    ),
+                                    1.0,
+                                );
+                                origin += (rect.width() + 5.0, 0.0);
+                            }
                         }
                     }
                 }
-            } else {
-                let j = i - commands_with_keymap_len;
-                if let Some(command) = commands_without_keymap.get(j) {
+                Some(KeymapRow::Command(command)) => {
                     ctx.with_save(|ctx| {
                         ctx.clip(Rect::new(
                             0.0,
@@ -403,6 +782,7 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
                         );
                     });
                 }
+                None => {}
             }
         }
 
@@ -473,6 +853,7 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
                 modes: keymap.modes,
                 when: keymap.when.clone(),
                 command: keymap.command.clone(),
+                source: keymap.source.clone(),
             }
             .paint(ctx, rect.center(), Alignment::Center, &data.config);
 
@@ -589,11 +970,259 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
     }
 }
 
-struct LapceKeymapHeader {}
+/// A scratch area that shows which command a key binding runs in the context
+/// of the active editor, to find out which binding shadows another one
+struct LapceKeymapTester {
+    widget_id: WidgetId,
+    keys: Vec<KeyPress>,
+}
+
+impl LapceKeymapTester {
+    pub fn new() -> Self {
+        Self {
+            widget_id: WidgetId::next(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// The keymaps that start with the pressed keys, the first one wins
+    fn matches<'a>(&self, data: &'a LapceTabData) -> Vec<&'a KeyMap> {
+        let mut matches = match data.main_split.active_editor() {
+            Some(editor) => {
+                let editor_data = data.editor_view_content(editor.view_id);
+                data.keypress.matching_keymaps(&self.keys, &editor_data)
+            }
+            None => data
+                .keypress
+                .matching_keymaps(&self.keys, &DefaultKeyPressHandler {}),
+        };
+        matches.reverse();
+        matches
+    }
+
+    /// Whether the pressed keys are only the start of key bindings
+    fn is_prefix(&self, data: &LapceTabData) -> bool {
+        let matches = self.matches(data);
+        !matches.is_empty()
+            && matches
+                .iter()
+                .all(|keymap| keymap.key.len() > self.keys.len())
+    }
+}
+
+impl Widget<LapceTabData> for LapceKeymapTester {
+    fn id(&self) -> Option<WidgetId> {
+        Some(self.widget_id)
+    }
+
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
+        _env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(_) => {
+                ctx.set_handled();
+                data.focus = Arc::new(self.widget_id);
+                ctx.request_focus();
+            }
+            Event::KeyDown(key_event) if ctx.is_focused() => {
+                ctx.set_handled();
+                if key_event.key == KbKey::Escape {
+                    self.keys.clear();
+                } else if let Some(keypress) = KeyPressData::keypress(key_event) {
+                    if !self.is_prefix(data) {
+                        self.keys.clear();
+                    }
+                    self.keys.push(keypress);
+                }
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &LapceTabData,
+        _env: &Env,
+    ) {
+        if let LifeCycle::FocusChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx,
+        _old_data: &LapceTabData,
+        _data: &LapceTabData,
+        _env: &Env,
+    ) {
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &LapceTabData,
+        _env: &Env,
+    ) -> Size {
+        Size::new(bc.max().width, 40.0)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
+        let size = ctx.size();
+        let keypress_width = LapceKeymap::KEYPRESS_WIDTH;
+
+        if ctx.is_focused() {
+            ctx.stroke(
+                size.to_rect().inset(-1.0),
+                data.config.get_color_unchecked(LapceTheme::EDITOR_CARET),
+                1.0,
+            );
+        }
+
+        let matches = self.matches(data);
+        let full_matches: Vec<&KeyMap> = matches
+            .iter()
+            .filter(|keymap| keymap.key.len() == self.keys.len())
+            .copied()
+            .collect();
+        let (result, color) = if self.keys.is_empty() {
+            (
+                if ctx.is_focused() {
+                    "Press a key binding to see which command it runs".to_string()
+                } else {
+                    "Click here to test a key binding in the active editor"
+                        .to_string()
+                },
+                LapceTheme::EDITOR_DIM,
+            )
+        } else if let Some(keymap) = full_matches.first() {
+            let desc = data
+                .keypress
+                .commands
+                .get(&keymap.command)
+                .and_then(|cmd| cmd.kind.desc())
+                .unwrap_or("");
+            (
+                format!("{desc} ({})", keymap.command),
+                LapceTheme::EDITOR_FOREGROUND,
+            )
+        } else if !matches.is_empty() {
+            (
+                format!(
+                    "Waiting for the next key of {} key bindings",
+                    matches.len()
+                ),
+                LapceTheme::EDITOR_DIM,
+            )
+        } else {
+            ("No command runs".to_string(), LapceTheme::EDITOR_DIM)
+        };
+
+        let text_layout = ctx
+            .text()
+            .new_text_layout(result)
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(data.config.get_color_unchecked(color).clone())
+            .build()
+            .unwrap();
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(
+                0.0,
+                0.0,
+                size.width / 2.0 - keypress_width,
+                size.height,
+            ));
+            ctx.draw_text(
+                &text_layout,
+                Point::new(10.0, text_layout.y_offset(size.height)),
+            );
+        });
+
+        if self.keys.is_empty() {
+            return;
+        }
+
+        KeyMap {
+            key: self.keys.clone(),
+            modes: Modes::empty(),
+            when: None,
+            command: String::new(),
+            source: None,
+        }
+        .paint(
+            ctx,
+            Point::new(size.width / 2.0 - keypress_width + 10.0, size.height / 2.0),
+            Alignment::Left,
+            &data.config,
+        );
+
+        if let Some(keymap) = full_matches.first() {
+            let mut binding = keymap
+                .when
+                .as_ref()
+                .map(|when| format!("when {when}"))
+                .unwrap_or_default();
+            if full_matches.len() > 1 {
+                if !binding.is_empty() {
+                    binding.push_str(", ");
+                }
+                binding.push_str(&format!(
+                    "shadows {} other key bindings",
+                    full_matches.len() - 1
+                ));
+            }
+            let text_layout = ctx
+                .text()
+                .new_text_layout(binding)
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                Point::new(
+                    size.width / 2.0
+                        + 10.0
+                        + if data.config.lapce.modal {
+                            keypress_width
+                        } else {
+                            0.0
+                        },
+                    text_layout.y_offset(size.height),
+                ),
+            );
+        }
+    }
+}
+
+struct LapceKeymapHeader {
+    /// The toggle of only listing the conflicting bindings
+    conflicts_rect: Rect,
+}
 
 impl LapceKeymapHeader {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            conflicts_rect: Rect::ZERO,
+        }
     }
 }
 
@@ -606,11 +1235,28 @@ fn default() -> Self {
 impl Widget<LapceTabData> for LapceKeymapHeader {
     fn event(
         &mut self,
-        _ctx: &mut EventCtx,
-        _event: &Event,
-        _data: &mut LapceTabData,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
         _env: &Env,
     ) {
+        match event {
+            Event::MouseMove(mouse_event) => {
+                if self.conflicts_rect.contains(mouse_event.pos) {
+                    ctx.set_cursor(&druid::Cursor::Pointer);
+                } else {
+                    ctx.clear_cursor();
+                }
+            }
+            Event::MouseDown(mouse_event)
+                if self.conflicts_rect.contains(mouse_event.pos) =>
+            {
+                ctx.set_handled();
+                let keypress = Arc::make_mut(&mut data.keypress);
+                keypress.conflicts_only = !keypress.conflicts_only;
+            }
+            _ => {}
+        }
     }
 
     fn lifecycle(
@@ -624,11 +1270,19 @@ fn lifecycle(
 
     fn update(
         &mut self,
-        _ctx: &mut UpdateCtx,
-        _old_data: &LapceTabData,
-        _data: &LapceTabData,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
         _env: &Env,
     ) {
+        if data.keypress.conflicts_only != old_data.keypress.conflicts_only
+            || !data
+                .keypress
+                .conflicting_keymaps
+                .same(&old_data.keypress.conflicting_keymaps)
+        {
+            ctx.request_paint();
+        }
     }
 
     fn layout(
@@ -643,7 +1297,7 @@ fn layout(
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
         let size = ctx.size();
-        let keypress_width = 200.0;
+        let keypress_width = LapceKeymap::KEYPRESS_WIDTH;
 
         let text_layout = ctx
             .text()
@@ -766,5 +1420,35 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
                 1.0,
             );
         }
+
+        let text = if data.keypress.conflicts_only {
+            "Show All".to_string()
+        } else {
+            format!(
+                "Show Conflicts Only ({})",
+                data.keypress.conflicting_keymaps.len()
+            )
+        };
+        let text_layout = ctx
+            .text()
+            .new_text_layout(text)
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_size = text_layout.size();
+        let origin = Point::new(
+            size.width - text_size.width - 10.0,
+            (size.height - text_size.height) / 2.0,
+        );
+        self.conflicts_rect = text_size.to_rect().with_origin(origin);
+        ctx.draw_text(&text_layout, origin);
     }
 }
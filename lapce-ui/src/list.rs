@@ -1,11 +1,15 @@
 use std::marker::PhantomData;
 
 use druid::{
-    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget, WidgetId,
-    WidgetPod,
+    BoxConstraints, Command, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, MouseButton, PaintCtx, Point, Rect, RenderContext, Size, Target,
+    UpdateCtx, Widget, WidgetId, WidgetPod,
+};
+use lapce_data::{
+    command::{LapceUICommand, LAPCE_UI_COMMAND},
+    config::LapceTheme,
+    list::ListData,
 };
-use lapce_data::{config::LapceTheme, list::ListData};
 
 use crate::scroll::{LapceIdentityWrapper, LapceScroll};
 
@@ -186,14 +190,33 @@ fn event(
         _env: &Env,
     ) {
         match event {
-            Event::MouseMove(_) => {
+            Event::MouseMove(mouse_event) => {
                 ctx.set_cursor(&druid::Cursor::Pointer);
+                let line =
+                    (mouse_event.pos.y / data.line_height() as f64).floor() as usize;
+                if data.preview_on_hover
+                    && line < data.items.len()
+                    && line != data.selected_index
+                {
+                    data.selected_index = line;
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ListItemHovered,
+                        Target::Widget(data.parent),
+                    ));
+                }
                 ctx.set_handled();
             }
             Event::MouseDown(mouse_event) => {
                 let line =
                     (mouse_event.pos.y / data.line_height() as f64).floor() as usize;
                 self.mouse_down = line;
+                if mouse_event.button == MouseButton::Right
+                    && line < data.items.len()
+                {
+                    data.selected_index = line;
+                    data.context_menu(ctx, ctx.to_window(mouse_event.pos));
+                }
                 ctx.set_handled();
             }
             Event::MouseUp(mouse_event) => {
@@ -201,7 +224,9 @@ fn event(
                 // this calculation; which makes it harder to change later
                 let line =
                     (mouse_event.pos.y / data.line_height() as f64).floor() as usize;
-                if line == self.mouse_down {
+                if line == self.mouse_down
+                    && mouse_event.button != MouseButton::Right
+                {
                     data.selected_index = line;
                     data.select(ctx);
                 }
@@ -11,16 +11,19 @@
     WidgetExt, WidgetId, WidgetPod,
 };
 use druid::{FontWeight, Modifiers};
-use lapce_data::command::LAPCE_COMMAND;
+use lapce_data::command::{
+    CommandKind, LapceCommand, LapceWorkbenchCommand, LAPCE_COMMAND,
+};
 use lapce_data::data::LapceWorkspaceType;
 use lapce_data::list::ListData;
+use lapce_data::menu::{MenuItem, MenuKind};
 use lapce_data::palette::{PaletteItem, PaletteItemContent, PaletteListData};
 use lapce_data::{
     command::{LapceUICommand, LAPCE_UI_COMMAND},
     config::LapceTheme,
     data::LapceTabData,
     keypress::KeyPressFocus,
-    palette::{PaletteStatus, PaletteType, PaletteViewData},
+    palette::{PaletteStatus, PaletteType, PaletteViewData, ThemeFileAction},
 };
 use lsp_types::SymbolKind;
 
@@ -164,6 +167,38 @@ fn event(
                     LapceUICommand::ListItemSelected => {
                         data.palette_view_data().select(ctx);
                     }
+                    LapceUICommand::ListItemHovered => {
+                        data.palette.preview(ctx);
+                    }
+                    LapceUICommand::ListItemContextMenu(point) => {
+                        if let Some(PaletteItemContent::Command(command)) = data
+                            .palette
+                            .list_data
+                            .current_selected_item()
+                            .map(|item| &item.content)
+                        {
+                            let item = MenuItem {
+                                desc: Some("Edit Keybinding".to_string()),
+                                command: LapceCommand {
+                                    kind: CommandKind::Workbench(
+                                        LapceWorkbenchCommand::EditKeybinding,
+                                    ),
+                                    data: Some(serde_json::json!(command
+                                        .kind
+                                        .str())),
+                                },
+                                enabled: true,
+                            };
+                            ctx.submit_command(Command::new(
+                                LAPCE_UI_COMMAND,
+                                LapceUICommand::ShowMenu(
+                                    *point,
+                                    Arc::new(vec![MenuKind::Item(item)]),
+                                ),
+                                Target::Widget(data.id),
+                            ));
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -511,11 +546,42 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &PaletteViewData, _env: &Env) {
         let text = data.palette.input.clone();
         let cursor = data.palette.cursor;
 
-        let text_layout = if text.is_empty()
-            && data.palette.palette_type == PaletteType::SshHost
-        {
+        let placeholder = match &data.palette.palette_type {
+            _ if !text.is_empty() => None,
+            PaletteType::SshHost => {
+                Some("Enter your SSH details, like user@host".to_string())
+            }
+            PaletteType::ThemeName(ThemeFileAction::Rename) => Some(format!(
+                "Enter the new name of \"{}\"",
+                data.config.lapce.color_theme
+            )),
+            PaletteType::ThemeName(ThemeFileAction::Duplicate) => Some(format!(
+                "Enter the name of the copy of \"{}\"",
+                data.config.lapce.color_theme
+            )),
+            PaletteType::ThemeGallery => Some("Choose a theme".to_string()),
+            PaletteType::SettingsBackup => {
+                Some("Choose the settings backup to restore".to_string())
+            }
+            PaletteType::ChangedDefaults => {
+                Some("Choose the new default to adopt".to_string())
+            }
+            PaletteType::ToggleSetting => {
+                Some("Choose the setting to toggle".to_string())
+            }
+            PaletteType::ThemeColors => {
+                Some("Search the base, syntax and UI colors".to_string())
+            }
+            PaletteType::ThemeColorReplacement => Some(
+                "Enter the color to replace and its replacement, like #E06C75 \
+                 #FF0000"
+                    .to_string(),
+            ),
+            _ => None,
+        };
+        let text_layout = if let Some(placeholder) = placeholder {
             ctx.text()
-                .new_text_layout("Enter your SSH details, like user@host")
+                .new_text_layout(placeholder)
                 .font(
                     data.config.ui.font_family(),
                     data.config.ui.font_size() as f64,
@@ -738,14 +804,62 @@ fn paint(
                     .unwrap_or_else(|| "".to_string());
                 PaletteItemPaintInfo::new_text(text, self.indices.to_vec())
             }
-            PaletteItemContent::Theme(theme) => PaletteItemPaintInfo::new_text(
-                theme.to_string(),
-                self.indices.to_vec(),
-            ),
+            PaletteItemContent::Theme(theme) => {
+                let mut info = PaletteItemPaintInfo::new_text(
+                    theme.to_string(),
+                    self.indices.to_vec(),
+                );
+                if data.data.theme_gallery.as_ref() == Some(theme) {
+                    info.hint = "Active".to_string();
+                }
+                info
+            }
             PaletteItemContent::Language(name) => PaletteItemPaintInfo::new_text(
                 name.to_string(),
                 self.indices.to_vec(),
             ),
+            PaletteItemContent::SettingsBackup(_, time) => {
+                PaletteItemPaintInfo::new_text(time.clone(), self.indices.to_vec())
+            }
+            PaletteItemContent::ToggleSetting(id, value) => {
+                let mut info = PaletteItemPaintInfo::new_text(
+                    id.clone(),
+                    self.indices.to_vec(),
+                );
+                info.hint = if *value { "On" } else { "Off" }.to_string();
+                info
+            }
+            PaletteItemContent::ThemeColor { kind, key, value } => {
+                let mut info = PaletteItemPaintInfo::new_text(
+                    key.clone(),
+                    self.indices.to_vec(),
+                );
+                let kind = match kind.as_str() {
+                    "theme.base" => "Base",
+                    "theme.syntax" => "Syntax",
+                    _ => "UI",
+                };
+                info.hint = format!("{kind}: {value}");
+                info
+            }
+            PaletteItemContent::ChangedDefault(change, value) => {
+                let old = change.old.as_ref().map(|old| old.to_string());
+                let mut info = PaletteItemPaintInfo::new_text(
+                    format!(
+                        "{}.{}: {} → {}",
+                        change.table,
+                        change.key,
+                        old.unwrap_or_default(),
+                        change.new
+                    ),
+                    self.indices.to_vec(),
+                );
+                info.hint = match value {
+                    Some(value) => format!("Set to {value}"),
+                    None => "In use".to_string(),
+                };
+                info
+            }
             PaletteItemContent::TerminalLine(_line, content) => {
                 PaletteItemPaintInfo::new_text(
                     content.clone(),
@@ -762,6 +876,51 @@ fn paint(
 
         let line_height = data.line_height() as f64;
 
+        if let (PaletteItemContent::Theme(theme), Some(active)) =
+            (&self.content, data.data.theme_gallery.as_ref())
+        {
+            let y = line_height * line as f64;
+            if theme == active {
+                ctx.fill(
+                    Rect::new(0.0, y, 3.0, y + line_height),
+                    data.config.get_color_unchecked(LapceTheme::EDITOR_FOCUS),
+                );
+            }
+
+            let size = 12.0;
+            let width = ctx.size().width;
+            let swatches = data.config.theme_swatches(theme);
+            for (i, color) in swatches.iter().enumerate() {
+                let x = width - 10.0 - (size + 4.0) * (swatches.len() - i) as f64;
+                let rect = Size::new(size, size)
+                    .to_rect()
+                    .with_origin(Point::new(x, y + (line_height - size) / 2.0));
+                ctx.fill(rect, color);
+                ctx.stroke(
+                    rect,
+                    data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                    1.0,
+                );
+            }
+        }
+
+        if let PaletteItemContent::ThemeColor { kind, key, .. } = &self.content {
+            if let Some(color) = data.config.theme_color(kind, key) {
+                let y = line_height * line as f64;
+                let size = 12.0;
+                let rect = Size::new(size, size).to_rect().with_origin(Point::new(
+                    ctx.size().width - 10.0 - size,
+                    y + (line_height - size) / 2.0,
+                ));
+                ctx.fill(rect, color);
+                ctx.stroke(
+                    rect,
+                    data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                    1.0,
+                );
+            }
+        }
+
         if let Some(svg) = svg.as_ref() {
             let width = 14.0;
             let height = 14.0;
@@ -1,32 +1,53 @@
-use std::{collections::HashMap, fmt::Display, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use chrono::TimeZone;
 use druid::{
-    kurbo::{BezPath, Line},
+    kurbo::{BezPath, Circle, Line},
     piet::{
         PietText, PietTextLayout, Text, TextAttribute, TextLayout, TextLayoutBuilder,
     },
-    BoxConstraints, Command, Env, Event, EventCtx, ExtEventSink, FontWeight,
-    LayoutCtx, LifeCycle, LifeCycleCtx, Modifiers, MouseEvent, PaintCtx, Point,
-    Rect, RenderContext, Size, Target, TimerToken, UpdateCtx, Widget, WidgetExt,
-    WidgetId, WidgetPod,
+    BoxConstraints, Color, Command, Env, Event, EventCtx, ExtEventSink, FontFamily,
+    FontWeight, KbKey, KeyEvent, LayoutCtx, LifeCycle, LifeCycleCtx, Modifiers,
+    MouseEvent, PaintCtx, Point, Rect, RenderContext, Size, Target, TimerToken,
+    UpdateCtx, Widget, WidgetExt, WidgetId, WidgetPod,
 };
 use inflector::Inflector;
 use itertools::Itertools;
 use lapce_core::{
-    command::{EditCommand, MoveCommand},
+    command::{EditCommand, FocusCommand, MoveCommand},
+    cursor::CursorMode,
     mode::Mode,
+    selection::Selection,
 };
 use lapce_data::{
     command::{
-        CommandExecuted, CommandKind, LapceUICommand, LAPCE_COMMAND,
-        LAPCE_UI_COMMAND,
+        CommandExecuted, CommandKind, LapceCommand, LapceUICommand,
+        LapceWorkbenchCommand, LAPCE_COMMAND, LAPCE_UI_COMMAND,
+    },
+    config::{
+        eval_color, expand_env_vars, Config, EditorConfig, LapceConfig, LapceTheme,
+        TerminalConfig, UIConfig,
     },
-    config::{EditorConfig, LapceConfig, LapceTheme, TerminalConfig, UIConfig},
     data::{FocusArea, LapceEditorData, LapceTabData},
-    document::{BufferContent, Document},
-    keypress::KeyPressFocus,
-    settings::{LapceSettingsFocusData, SettingsValueKind},
+    document::{BufferContent, Document, LocalBufferKind},
+    editor::EditorLocation,
+    keypress::{KeyPress, KeyPressData, KeyPressFocus},
+    menu::{MenuItem, MenuKind},
+    settings::{
+        adjust_color, color_components, complete_setting_id, deprecation_note,
+        numeric_input_status, parse_color_components, parse_settings_value,
+        reset_tooltip_text, rounded_settings_value, settings_change_age,
+        settings_filter_matches, settings_key_line, settings_map_value,
+        settings_value_text, text_size_readout, unmet_settings_dependency,
+        LapceSettingsFocusData, SettingsPanelPreferences, SettingsValueKind,
+    },
 };
+use once_cell::sync::Lazy;
 use serde::Serialize;
 use xi_rope::Rope;
 
@@ -35,6 +56,7 @@
     keymap::LapceKeymap,
     scroll::{LapcePadding, LapceScroll},
     split::LapceSplit,
+    svg::get_svg,
 };
 
 enum LapceSettingsKind {
@@ -42,6 +64,22 @@ enum LapceSettingsKind {
     UI,
     Editor,
     Terminal,
+    /// The most recently changed settings of all the tables
+    Recent,
+}
+
+impl LapceSettingsKind {
+    /// The settings file table this section edits, the recently changed
+    /// section spans all tables and isn't tied to one
+    fn table(&self) -> &'static str {
+        match self {
+            LapceSettingsKind::Core => "lapce",
+            LapceSettingsKind::UI => "ui",
+            LapceSettingsKind::Editor => "editor",
+            LapceSettingsKind::Terminal => "terminal",
+            LapceSettingsKind::Recent => "recent",
+        }
+    }
 }
 
 pub struct LapceSettingsPanel {
@@ -51,32 +89,128 @@ pub struct LapceSettingsPanel {
     content_rect: Rect,
     switcher_rect: Rect,
     switcher_line_height: f64,
+    /// The gap between the switcher and the content, dragging it resizes them
+    divider_rect: Rect,
+    sort_order_rect: Rect,
+    raw_keys_rect: Rect,
+    advanced_rect: Rect,
+    deprecated_rect: Rect,
+    group_rect: Rect,
+    effective_config_rect: Rect,
+    /// The pane showing the effective config at the end of the content, with
+    /// the button hiding it
+    config_pane_rect: Rect,
+    config_pane_close_rect: Rect,
+    config_pane: WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    /// The banner telling a setting couldn't be stored, with its close button
+    write_error_banner: Option<(Rect, PietTextLayout)>,
+    write_error_close_rect: Rect,
+    /// The banner telling the open files don't follow a changed setting yet,
+    /// with the link reloading them and its close button
+    reload_banner: Option<(Rect, PietTextLayout, Rect, PietTextLayout)>,
+    reload_close_rect: Rect,
+    /// The button next to the search input listing the recent searches, and
+    /// the listed searches while they're open
+    history_rect: Rect,
+    history_open: bool,
+    history_rows: Vec<(Rect, String)>,
+    view_defaults_rect: Rect,
+    /// The button collapsing the switcher to icons, or expanding it again
+    collapse_rect: Rect,
+    /// The switcher row under the mouse in the collapsed switcher, its section
+    /// is named in a tooltip
+    hover_section: Option<usize>,
+    section_ids: Vec<(&'static str, WidgetId)>,
+    recent_id: WidgetId,
+    /// The table of the section whose search is in the search input
+    filter_section: Option<&'static str>,
+    /// The section part of the breadcrumb, clicking it goes back to the top
+    breadcrumb_section_rect: Rect,
+    /// The search input above the settings sections
+    input: WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>,
     children: Vec<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
 }
 
+/// The titles of the panel sections, in the order of the switcher
+const SETTINGS_SECTIONS: [&str; 7] = [
+    "Core Settings",
+    "UI Settings",
+    "Editor Settings",
+    "Terminal Settings",
+    "Theme Settings",
+    "Keybindings",
+    "Recently Changed",
+];
+
+/// The names of the panel sections in the `settings-sections` UI setting
+const SETTINGS_SECTION_KEYS: [&str; 7] = [
+    "core",
+    "ui",
+    "editor",
+    "terminal",
+    "theme",
+    "keybindings",
+    "recent",
+];
+
+/// The icons of the panel sections in the collapsed switcher
+const SETTINGS_SECTION_ICONS: [&str; 7] = [
+    "settings.svg",
+    "layout-sidebar-left.svg",
+    "symbol-file.svg",
+    "terminal.svg",
+    "symbol-color.svg",
+    "symbol-key.svg",
+    "symbol-event.svg",
+];
+
 impl LapceSettingsPanel {
+    const BREADCRUMB_HEIGHT: f64 = 30.0;
+    const COLLAPSED_SWITCHER_WIDTH: f64 = 50.0;
+    const CONFIG_PANE_WIDTH: f64 = 350.0;
+    /// The sections before the theme section each list a table of the
+    /// settings file
+    const THEME_SECTION: usize = 4;
+    const KEYBINDINGS_SECTION: usize = 5;
+    const RECENT_SECTION: usize = 6;
+
     pub fn new(
         data: &LapceTabData,
         widget_id: WidgetId,
         editor_tab_id: WidgetId,
         keymap_input_view_id: WidgetId,
+        settings_input_view_id: WidgetId,
     ) -> Self {
-        let children = vec![
-            WidgetPod::new(
-                LapceSettings::new_split(LapceSettingsKind::Core, data).boxed(),
-            ),
-            WidgetPod::new(
-                LapceSettings::new_split(LapceSettingsKind::UI, data).boxed(),
-            ),
-            WidgetPod::new(
-                LapceSettings::new_split(LapceSettingsKind::Editor, data).boxed(),
-            ),
-            WidgetPod::new(
-                LapceSettings::new_split(LapceSettingsKind::Terminal, data).boxed(),
-            ),
-            WidgetPod::new(ThemeSettings::new_boxed().boxed()),
-            WidgetPod::new(LapceKeymap::new_split(keymap_input_view_id).boxed()),
+        let kinds = [
+            LapceSettingsKind::Core,
+            LapceSettingsKind::UI,
+            LapceSettingsKind::Editor,
+            LapceSettingsKind::Terminal,
         ];
+        let section_ids: Vec<(&'static str, WidgetId)> = kinds
+            .iter()
+            .map(|kind| (kind.table(), WidgetId::next()))
+            .collect();
+        let mut children = Vec::new();
+        for (kind, (_, id)) in kinds.into_iter().zip(section_ids.iter()) {
+            children.push(WidgetPod::new(
+                LapceSettings::new_split(kind, *id, data).boxed(),
+            ));
+        }
+        children.push(WidgetPod::new(ThemeSettings::new_boxed().boxed()));
+        children.push(WidgetPod::new(
+            LapceKeymap::new_split(keymap_input_view_id).boxed(),
+        ));
+        let recent_id = WidgetId::next();
+        children.push(WidgetPod::new(
+            LapceSettings::new_split(LapceSettingsKind::Recent, recent_id, data)
+                .boxed(),
+        ));
+        let input =
+            LapceEditorView::new(settings_input_view_id, WidgetId::next(), None)
+                .hide_header()
+                .hide_gutter()
+                .padding((15.0, 15.0, 0.0, 15.0));
         Self {
             widget_id,
             editor_tab_id,
@@ -84,992 +218,6267 @@ pub fn new(
             content_rect: Rect::ZERO,
             switcher_rect: Rect::ZERO,
             switcher_line_height: 40.0,
+            divider_rect: Rect::ZERO,
+            sort_order_rect: Rect::ZERO,
+            raw_keys_rect: Rect::ZERO,
+            advanced_rect: Rect::ZERO,
+            deprecated_rect: Rect::ZERO,
+            group_rect: Rect::ZERO,
+            effective_config_rect: Rect::ZERO,
+            config_pane_rect: Rect::ZERO,
+            config_pane_close_rect: Rect::ZERO,
+            config_pane: WidgetPod::new(
+                LapceScroll::new(EffectiveConfigView::new().boxed()).boxed(),
+            ),
+            write_error_banner: None,
+            write_error_close_rect: Rect::ZERO,
+            reload_banner: None,
+            reload_close_rect: Rect::ZERO,
+            history_rect: Rect::ZERO,
+            history_open: false,
+            history_rows: Vec::new(),
+            view_defaults_rect: Rect::ZERO,
+            collapse_rect: Rect::ZERO,
+            hover_section: None,
+            section_ids,
+            recent_id,
+            filter_section: None,
+            breadcrumb_section_rect: Rect::ZERO,
+            input: WidgetPod::new(input.boxed()),
             children,
         }
     }
 
-    fn mouse_down(
-        &mut self,
-        ctx: &mut EventCtx,
-        mouse_event: &MouseEvent,
-        data: &mut LapceTabData,
-    ) {
-        if self.switcher_rect.contains(mouse_event.pos) {
-            let index = ((mouse_event.pos.y - self.switcher_rect.y0)
-                / self.switcher_line_height)
-                .floor() as usize;
-            if index < self.children.len() {
-                self.active = index;
-                ctx.request_layout();
+    /// The indices of the sections listed in the switcher, a row of the
+    /// switcher shows the section at the same position
+    fn visible_sections(data: &LapceTabData) -> Vec<usize> {
+        (0..SETTINGS_SECTIONS.len())
+            .filter(|i| {
+                data.config
+                    .ui
+                    .shows_settings_section(SETTINGS_SECTION_KEYS[*i])
+            })
+            .collect()
+    }
+
+    /// The switcher row at the position `y`
+    fn switcher_row(&self, y: f64) -> usize {
+        ((y - self.switcher_rect.y0) / self.switcher_line_height).floor() as usize
+    }
+
+    /// Whether the active section is a list of settings which can be searched
+    fn shows_input(&self) -> bool {
+        self.active < Self::THEME_SECTION || self.active == Self::RECENT_SECTION
+    }
+
+    /// The table and widget id of the active section, if it's a list of
+    /// settings
+    fn active_section(&self) -> Option<(&'static str, WidgetId)> {
+        match self.active {
+            active if active < Self::THEME_SECTION => Some(self.section_ids[active]),
+            Self::RECENT_SECTION => {
+                Some((LapceSettingsKind::Recent.table(), self.recent_id))
             }
-            ctx.set_handled();
-            self.request_focus(ctx, data);
+            _ => None,
         }
     }
 
-    fn request_focus(&self, ctx: &mut EventCtx, data: &mut LapceTabData) {
-        let editor_tab = data
+    /// Keep the search of the section left for when it's back, and give the
+    /// input the search of the active section
+    fn switch_section_filter(&mut self, data: &mut LapceTabData) {
+        let table = match self.active_section() {
+            Some((table, _)) => table,
+            None => return,
+        };
+        let previous = match self.filter_section.replace(table) {
+            Some(previous) if previous != table => previous,
+            // The first section shown keeps what's in the input
+            _ => return,
+        };
+        let settings = Arc::make_mut(&mut data.settings);
+        let pattern = settings.section_filters.remove(table).unwrap_or_default();
+        let left = std::mem::replace(&mut settings.filter_pattern, pattern.clone());
+        if !left.is_empty() {
+            settings.section_filters.insert(previous.to_string(), left);
+        }
+        // The search input reports its new text as the filter
+        if let Some(doc) = data
             .main_split
-            .editor_tabs
-            .get_mut(&self.editor_tab_id)
-            .unwrap();
-        let editor_tab = Arc::make_mut(editor_tab);
-        if let Some(index) = editor_tab
-            .children
-            .iter()
-            .position(|child| child.widget_id() == self.widget_id)
+            .local_docs
+            .get_mut(&LocalBufferKind::Settings)
         {
-            editor_tab.active = index;
+            Arc::make_mut(doc).reload(Rope::from(pattern.as_str()), true);
         }
-
-        data.main_split.active_tab = Arc::new(Some(self.editor_tab_id));
-        data.focus = Arc::new(self.widget_id);
-        data.focus_area = FocusArea::Editor;
-        ctx.request_focus();
     }
-}
 
-impl Widget<LapceTabData> for LapceSettingsPanel {
-    fn id(&self) -> Option<WidgetId> {
-        Some(self.widget_id)
+    /// The setting of the active section the breadcrumb leads to
+    fn current_setting<'a>(
+        &self,
+        data: &'a LapceTabData,
+    ) -> Option<&'a (String, String, String)> {
+        let (table, _) = self.active_section()?;
+        data.settings
+            .current_setting
+            .as_ref()
+            .filter(|(section, _, _)| section == table)
     }
 
-    fn event(
+    fn mouse_down(
         &mut self,
         ctx: &mut EventCtx,
-        event: &Event,
+        mouse_event: &MouseEvent,
         data: &mut LapceTabData,
-        env: &Env,
     ) {
-        match event {
-            Event::KeyDown(key_event) => {
-                if ctx.is_focused() {
-                    let mut keypress = data.keypress.clone();
-                    let mut focus = LapceSettingsFocusData {
-                        widget_id: self.widget_id,
-                        editor_tab_id: self.editor_tab_id,
-                        main_split: data.main_split.clone(),
-                        config: data.config.clone(),
-                    };
-                    let mut_keypress = Arc::make_mut(&mut keypress);
-                    let performed_action =
-                        mut_keypress.key_down(ctx, key_event, &mut focus, env);
-                    data.keypress = keypress;
-                    data.main_split = focus.main_split;
-                    if performed_action {
-                        ctx.set_handled();
-                    }
-                }
-            }
-            Event::MouseDown(mouse_event) => {
-                self.mouse_down(ctx, mouse_event, data);
-            }
-            Event::Command(cmd) if cmd.is(LAPCE_COMMAND) => {
-                let cmd = cmd.get_unchecked(LAPCE_COMMAND);
-                let mut focus = LapceSettingsFocusData {
-                    widget_id: self.widget_id,
-                    editor_tab_id: self.editor_tab_id,
-                    main_split: data.main_split.clone(),
-                    config: data.config.clone(),
-                };
-                if focus.run_command(ctx, cmd, None, Modifiers::empty(), env)
-                    == CommandExecuted::Yes
-                {
-                    ctx.set_handled();
-                }
-                data.main_split = focus.main_split;
+        if self.divider_rect.contains(mouse_event.pos) {
+            ctx.set_active(true);
+            ctx.set_handled();
+            return;
+        }
+
+        if self.history_open {
+            self.history_open = false;
+            ctx.request_layout();
+            if let Some((_, pattern)) = self
+                .history_rows
+                .iter()
+                .find(|(rect, _)| rect.contains(mouse_event.pos))
+            {
+                ctx.submit_command(Command::new(
+                    LAPCE_COMMAND,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::RunSettingsSearch,
+                        ),
+                        data: Some(serde_json::json!(pattern)),
+                    },
+                    Target::Widget(data.id),
+                ));
+                ctx.set_handled();
+                return;
             }
-            Event::Command(cmd) if cmd.is(LAPCE_UI_COMMAND) => {
-                let command = cmd.get_unchecked(LAPCE_UI_COMMAND);
-                match command {
-                    LapceUICommand::Focus => {
-                        ctx.set_handled();
-                        self.request_focus(ctx, data);
-                    }
-                    LapceUICommand::ShowSettings => {
-                        ctx.request_focus();
-                        self.active = 0;
-                    }
-                    LapceUICommand::ShowKeybindings => {
-                        ctx.request_focus();
-                        self.active = 5;
-                    }
-                    LapceUICommand::Hide => {
-                        if let Some(active) = *data.main_split.active {
-                            ctx.submit_command(Command::new(
-                                LAPCE_UI_COMMAND,
-                                LapceUICommand::Focus,
-                                Target::Widget(active),
-                            ));
-                        }
-                    }
-                    _ => (),
-                }
+            if self.history_rect.contains(mouse_event.pos) {
+                ctx.set_handled();
+                return;
             }
-            _ => {}
+        } else if self.history_rect.contains(mouse_event.pos) {
+            self.history_open = true;
+            ctx.request_layout();
+            ctx.set_handled();
+            return;
         }
 
-        if ctx.is_handled() {
+        if self.write_error_close_rect.contains(mouse_event.pos) {
+            let settings = Arc::make_mut(&mut data.settings);
+            settings.write_error = None;
+            settings.mirror_error = None;
+            ctx.set_handled();
             return;
         }
 
-        if event.should_propagate_to_hidden() {
-            for child in self.children.iter_mut() {
-                child.event(ctx, event, data, env);
+        if self.reload_close_rect.contains(mouse_event.pos) {
+            Arc::make_mut(&mut data.settings).reload_pending = None;
+            ctx.set_handled();
+            return;
+        }
+        if let Some((_, _, link_rect, _)) = self.reload_banner.as_ref() {
+            if link_rect.contains(mouse_event.pos) {
+                ctx.submit_command(Command::new(
+                    LAPCE_COMMAND,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::ReloadOpenFiles,
+                        ),
+                        data: None,
+                    },
+                    Target::Widget(data.id),
+                ));
+                ctx.set_handled();
+                return;
             }
-        } else {
-            self.children[self.active].event(ctx, event, data, env);
         }
-    }
 
-    fn lifecycle(
-        &mut self,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
-        data: &LapceTabData,
-        env: &Env,
-    ) {
-        for child in self.children.iter_mut() {
-            child.lifecycle(ctx, event, data, env);
+        if self.sort_order_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsAlphabeticalOrder,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
         }
-    }
 
-    fn update(
-        &mut self,
-        ctx: &mut UpdateCtx,
-        _old_data: &LapceTabData,
-        data: &LapceTabData,
-        env: &Env,
-    ) {
-        for child in self.children.iter_mut() {
-            child.update(ctx, data, env);
+        if self.collapse_rect.contains(mouse_event.pos) {
+            self.hover_section = None;
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsSwitcherCollapsed,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
         }
-    }
 
-    fn layout(
-        &mut self,
-        ctx: &mut LayoutCtx,
-        bc: &BoxConstraints,
-        data: &LapceTabData,
-        env: &Env,
-    ) -> Size {
-        let self_size = bc.max();
-        let origin = Point::ZERO;
-        self.content_rect = self_size.to_rect().with_origin(origin).round();
+        if self.raw_keys_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsRawKeys,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-        self.switcher_rect = Size::new(150.0, self_size.height)
-            .to_rect()
-            .with_origin(Point::ZERO)
-            .round();
+        if self.group_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsGroupByChanged,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-        let content_size = Size::new(
-            self_size.width - self.switcher_rect.width() - 20.0,
-            self_size.height,
-        );
-        let content_origin = Point::new(self.switcher_rect.width() + 20.0, 0.0);
-        let content_bc = BoxConstraints::tight(content_size);
-        let child = &mut self.children[self.active];
-        child.layout(ctx, &content_bc, data, env);
-        child.set_origin(ctx, data, env, content_origin);
+        if self.effective_config_rect.contains(mouse_event.pos)
+            || self.config_pane_close_rect.contains(mouse_event.pos)
+        {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsEffectiveConfig,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-        self_size
-    }
+        if self.deprecated_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsDeprecated,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
-        ctx.fill(
-            self.content_rect,
-            data.config
-                .get_color_unchecked(LapceTheme::EDITOR_BACKGROUND),
-        );
+        if self.advanced_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::ToggleSettingsAdvanced,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-        ctx.fill(
-            Size::new(self.switcher_rect.width(), self.switcher_line_height)
-                .to_rect()
-                .with_origin(
-                    self.switcher_rect.origin()
-                        + (0.0, self.active as f64 * self.switcher_line_height),
-                ),
-            data.config
-                .get_color_unchecked(LapceTheme::EDITOR_CURRENT_LINE),
-        );
+        if self.breadcrumb_section_rect.contains(mouse_event.pos)
+            && mouse_event.button.is_right()
+        {
+            self.show_files_menu(ctx, data, mouse_event.pos);
+            ctx.set_handled();
+            return;
+        }
 
-        ctx.with_save(|ctx| {
-            ctx.clip(self.switcher_rect);
-            const SETTINGS_SECTIONS: [&str; 6] = [
-                "Core Settings",
-                "UI Settings",
-                "Editor Settings",
-                "Terminal Settings",
-                "Theme Settings",
-                "Keybindings",
-            ];
-
-            for (i, text) in SETTINGS_SECTIONS.into_iter().enumerate() {
-                let text_layout = ctx
-                    .text()
-                    .new_text_layout(text)
-                    .font(
-                        data.config.ui.font_family(),
-                        (data.config.ui.font_size() + 1) as f64,
-                    )
-                    .text_color(
-                        data.config
-                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                            .clone(),
-                    )
-                    .build()
-                    .unwrap();
-                ctx.draw_text(
-                    &text_layout,
-                    self.switcher_rect.origin()
-                        + (
-                            20.0,
-                            i as f64 * self.switcher_line_height
-                                + text_layout.y_offset(self.switcher_line_height),
-                        ),
-                );
+        if self.breadcrumb_section_rect.contains(mouse_event.pos) {
+            if let Some((_, id)) = self.active_section() {
+                if self.current_setting(data).is_some() {
+                    Arc::make_mut(&mut data.settings).current_setting = None;
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ScrollTo((0.0, 0.0)),
+                        Target::Widget(id),
+                    ));
+                }
             }
-        });
+            ctx.set_handled();
+            return;
+        }
 
-        self.children[self.active].paint(ctx, data, env);
+        if self.view_defaults_rect.contains(mouse_event.pos) {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::OpenDefaultSettings,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+            ctx.set_handled();
+            return;
+        }
 
-        ctx.stroke(
-            Line::new(
-                Point::new(self.switcher_rect.x1 + 0.5, self.switcher_rect.y0),
-                Point::new(self.switcher_rect.x1 + 0.5, self.switcher_rect.y1),
-            ),
-            data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
-            1.0,
-        );
+        if self.switcher_rect.contains(mouse_event.pos) {
+            let row = self.switcher_row(mouse_event.pos.y);
+            if let Some(index) = Self::visible_sections(data).get(row) {
+                self.active = *index;
+                ctx.request_layout();
+            }
+            ctx.set_handled();
+            self.request_focus(ctx, data);
+        }
     }
-}
 
-struct LapceSettings {
-    widget_id: WidgetId,
-    kind: LapceSettingsKind,
-    children: Vec<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
-}
+    /// The menu of the breadcrumb section, which reveals the files the
+    /// settings are stored in
+    fn show_files_menu(&self, ctx: &mut EventCtx, data: &LapceTabData, pos: Point) {
+        let item = |desc: &str, command, enabled| {
+            MenuKind::Item(MenuItem {
+                desc: Some(desc.to_string()),
+                command: LapceCommand {
+                    kind: CommandKind::Workbench(command),
+                    data: None,
+                },
+                enabled,
+            })
+        };
+        let mut items = vec![
+            item(
+                "Reveal Settings File",
+                LapceWorkbenchCommand::RevealSettingsFileInFileExplorer,
+                true,
+            ),
+            item(
+                "Reveal Workspace Settings File",
+                LapceWorkbenchCommand::RevealWorkspaceSettingsFileInFileExplorer,
+                data.config.workspace_settings().is_some(),
+            ),
+            item(
+                "Reveal Theme File",
+                LapceWorkbenchCommand::RevealThemeFileInFileExplorer,
+                true,
+            ),
+        ];
+        // Recently changed settings aren't a section of the settings file
+        if let Some((table, _)) = self
+            .active_section()
+            .filter(|_| self.active < Self::THEME_SECTION)
+        {
+            items.push(MenuKind::Separator);
+            for (desc, changed_only) in [
+                ("Copy Section as JSON", false),
+                ("Copy Changed Settings as JSON", true),
+            ] {
+                items.push(MenuKind::Item(MenuItem {
+                    desc: Some(desc.to_string()),
+                    command: LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::CopySettingsSectionAsJson,
+                        ),
+                        data: Some(serde_json::json!((table, changed_only))),
+                    },
+                    enabled: true,
+                }));
+            }
+        }
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowMenu(ctx.to_window(pos), Arc::new(items)),
+            Target::Widget(data.id),
+        ));
+    }
 
-impl LapceSettings {
-    pub fn new_split(kind: LapceSettingsKind, data: &LapceTabData) -> LapceSplit {
-        let settings = LapceScroll::new(
-            Self {
-                widget_id: WidgetId::next(),
-                kind,
-                children: Vec::new(),
-            }
-            .boxed(),
+    /// Paint the path to the current setting as "Section › Setting",
+    /// the section is a link back up while a setting is current
+    fn paint_breadcrumb(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let current = self.current_setting(data);
+        let font_family = data.config.ui.font_family();
+        let font_size = data.config.ui.font_size() as f64;
+        let rect = self.breadcrumb_section_rect;
+        let section_text = ctx
+            .text()
+            .new_text_layout(SETTINGS_SECTIONS[self.active])
+            .font(font_family.clone(), font_size)
+            .text_color(
+                data.config
+                    .get_color_unchecked(if current.is_some() {
+                        LapceTheme::EDITOR_LINK
+                    } else {
+                        LapceTheme::EDITOR_FOREGROUND
+                    })
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        ctx.draw_text(
+            &section_text,
+            Point::new(rect.x0, rect.y0 + section_text.y_offset(rect.height())),
         );
 
-        let _input = LapceEditorView::new(
-            data.settings.settings_view_id,
-            WidgetId::next(),
-            None,
-        )
-        .hide_header()
-        .hide_gutter()
-        .padding((15.0, 15.0, 0.0, 15.0));
-
-        let split = LapceSplit::new(data.settings.settings_split_id)
-            .horizontal()
-            //.with_child(input.boxed(), None, 55.0)
-            .with_flex_child(settings.boxed(), None, 1.0, false);
+        let mut breadcrumb_x1 = rect.x1;
+        if let Some((_, table, key)) = current {
+            let name = setting_display_name(table, key, &data.settings.preferences);
+            let setting_text = ctx
+                .text()
+                .new_text_layout(format!("\u{203a}  {name}"))
+                .font(font_family, font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &setting_text,
+                Point::new(
+                    rect.x1 + 8.0,
+                    rect.y0 + setting_text.y_offset(rect.height()),
+                ),
+            );
+            breadcrumb_x1 += 8.0 + setting_text.size().width;
+        }
 
-        split
+        if data.settings.reloading.is_none() {
+            self.paint_shortcut_hints(ctx, data, breadcrumb_x1);
+        }
     }
 
-    fn update_children(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
-        fn into_settings_map(
-            data: &impl Serialize,
-        ) -> HashMap<String, serde_json::Value> {
-            serde_json::to_value(data)
-                .and_then(serde_json::from_value)
-                .unwrap()
+    /// The keys bound to opening the settings and the key bindings, at the
+    /// right of the breadcrumb so they can be learned. They're left out when
+    /// they don't fit after the breadcrumb.
+    fn paint_shortcut_hints(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        breadcrumb_x1: f64,
+    ) {
+        let hint = |command: LapceWorkbenchCommand| {
+            let kind = CommandKind::Workbench(command);
+            let keys = data
+                .keypress
+                .command_keymaps
+                .get(kind.str())
+                .and_then(|keymaps| keymaps.first())
+                .map(|keymap| keymap.key.iter().map(|k| k.to_string()).join(" "))
+                .unwrap_or_else(|| "unbound".to_string());
+            format!("{}: {keys}", kind.desc().unwrap_or_else(|| kind.str()))
+        };
+        let text_layout = ctx
+            .text()
+            .new_text_layout(format!(
+                "{}    {}",
+                hint(LapceWorkbenchCommand::OpenSettings),
+                hint(LapceWorkbenchCommand::OpenKeyboardShortcuts)
+            ))
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_x = self.breadcrumb_end() - 15.0 - text_layout.size().width;
+        if text_x < breadcrumb_x1 + 20.0 {
+            return;
         }
+        ctx.draw_text(
+            &text_layout,
+            Point::new(text_x, text_layout.y_offset(Self::BREADCRUMB_HEIGHT)),
+        );
+    }
 
-        self.children.clear();
+    /// Where the breadcrumb row ends, before the effective config if it's
+    /// shown
+    fn breadcrumb_end(&self) -> f64 {
+        if self.config_pane_rect.width() > 0.0 {
+            self.config_pane_rect.x0
+        } else {
+            self.content_rect.x1
+        }
+    }
 
-        let (kind, fields, descs, mut settings) = match self.kind {
-            LapceSettingsKind::Core => (
-                "lapce",
-                &LapceConfig::FIELDS[..],
-                &LapceConfig::DESCS[..],
-                into_settings_map(&data.config.lapce),
-            ),
-            LapceSettingsKind::UI => (
-                "ui",
-                &UIConfig::FIELDS[..],
-                &UIConfig::DESCS[..],
-                into_settings_map(&data.config.ui),
-            ),
-            LapceSettingsKind::Editor => (
-                "editor",
-                &EditorConfig::FIELDS[..],
-                &EditorConfig::DESCS[..],
-                into_settings_map(&data.config.editor),
-            ),
-            LapceSettingsKind::Terminal => (
-                "terminal",
-                &TerminalConfig::FIELDS[..],
-                &TerminalConfig::DESCS[..],
-                into_settings_map(&data.config.terminal),
-            ),
+    /// A spinner with what's being done at the right of the breadcrumb, while
+    /// a bulk change takes longer than a moment
+    fn paint_reloading(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let (label, started) = match (
+            data.settings.reloading_label(),
+            data.settings.reloading.as_ref(),
+        ) {
+            (Some(label), Some((_, started))) => (label, started),
+            _ => return,
         };
+        let text_layout = ctx
+            .text()
+            .new_text_layout(format!("{label}\u{2026}"))
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_x = self.breadcrumb_end() - 15.0 - text_layout.size().width;
+        ctx.draw_text(
+            &text_layout,
+            Point::new(text_x, text_layout.y_offset(Self::BREADCRUMB_HEIGHT)),
+        );
 
-        for (field, desc) in fields.iter().zip(descs.iter()) {
-            // TODO(dbuga): we should generate kebab-case field names
-            let field = field.replace('_', "-");
-            let value = settings.remove(&field).unwrap();
-            self.children.push(WidgetPod::new(
-                LapcePadding::new(
-                    (10.0, 10.0),
-                    LapceSettingsItem::new(
-                        data,
-                        kind.to_string(),
-                        field,
-                        desc.to_string(),
-                        value,
-                        ctx.get_external_handle(),
-                    ),
-                )
-                .boxed(),
-            ))
+        // Eight dots around a circle, the brightest one going round
+        let center = Point::new(text_x - 14.0, Self::BREADCRUMB_HEIGHT / 2.0);
+        let step = (started.elapsed().as_millis() / 100 % 8) as usize;
+        let color = data
+            .config
+            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND);
+        for i in 0..8 {
+            let angle = i as f64 * std::f64::consts::PI / 4.0;
+            let alpha = 1.0 - ((step + 8 - i) % 8) as f64 / 8.0;
+            ctx.fill(
+                Circle::new(center + (6.0 * angle.cos(), 6.0 * angle.sin()), 1.5),
+                &color.clone().with_alpha(alpha),
+            );
         }
     }
-}
 
-impl Widget<LapceTabData> for LapceSettings {
-    fn id(&self) -> Option<WidgetId> {
-        Some(self.widget_id)
+    /// The recent searches below the search input, picking one searches it
+    fn paint_search_history(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        if self.history_rect != Rect::ZERO {
+            if let Some(svg) = get_svg("chevron-down.svg") {
+                ctx.draw_svg(
+                    &svg,
+                    self.history_rect,
+                    Some(data.config.get_color_unchecked(LapceTheme::EDITOR_DIM)),
+                );
+            }
+        }
+        let rect = match (self.history_rows.first(), self.history_rows.last()) {
+            (Some((first, _)), Some((last, _))) => first.union(*last),
+            _ => return,
+        };
+        let shadow_width = data.config.ui.drop_shadow_width() as f64;
+        if shadow_width > 0.0 {
+            ctx.blurred_rect(
+                rect,
+                shadow_width,
+                data.config
+                    .get_color_unchecked(LapceTheme::LAPCE_DROPDOWN_SHADOW),
+            );
+        } else {
+            ctx.stroke(
+                rect.inflate(0.5, 0.5),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+        }
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::PANEL_BACKGROUND),
+        );
+        for (row, pattern) in self.history_rows.iter() {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(pattern.to_string())
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .max_width(row.width() - 20.0)
+                .build()
+                .unwrap();
+            ctx.with_save(|ctx| {
+                ctx.clip(*row);
+                ctx.draw_text(
+                    &text_layout,
+                    Point::new(
+                        row.x0 + 10.0,
+                        row.y0 + text_layout.y_offset(row.height()),
+                    ),
+                );
+            });
+        }
     }
 
-    fn event(
-        &mut self,
-        ctx: &mut EventCtx,
-        event: &Event,
-        data: &mut LapceTabData,
-        env: &Env,
-    ) {
-        for child in self.children.iter_mut() {
-            child.event(ctx, event, data, env);
+    fn paint_write_error(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let (rect, text_layout) = match self.write_error_banner.as_ref() {
+            Some(banner) => banner,
+            None => return,
+        };
+        let error_color = data.config.get_color_unchecked(LapceTheme::LAPCE_ERROR);
+        ctx.fill(*rect, &error_color.clone().with_alpha(0.1));
+        ctx.stroke(rect.inflate(-0.5, -0.5), error_color, 1.0);
+        ctx.draw_text(text_layout, rect.origin() + (10.0, 8.0));
+        if let Some(svg) = get_svg("close.svg") {
+            ctx.draw_svg(
+                &svg,
+                self.write_error_close_rect,
+                Some(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
+                ),
+            );
         }
-        if self.children.is_empty() {
-            self.update_children(ctx, data);
-            ctx.children_changed();
+    }
+
+    fn paint_reload_banner(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let (rect, text_layout, link_rect, link) = match self.reload_banner.as_ref()
+        {
+            Some(banner) => banner,
+            None => return,
+        };
+        let warn_color = data.config.get_color_unchecked(LapceTheme::LAPCE_WARN);
+        ctx.fill(*rect, &warn_color.clone().with_alpha(0.1));
+        ctx.stroke(rect.inflate(-0.5, -0.5), warn_color, 1.0);
+        ctx.draw_text(text_layout, rect.origin() + (10.0, 8.0));
+        ctx.draw_text(link, link_rect.origin());
+        if let Some(svg) = get_svg("close.svg") {
+            ctx.draw_svg(
+                &svg,
+                self.reload_close_rect,
+                Some(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
+                ),
+            );
         }
     }
 
-    fn lifecycle(
+    /// The command of the panel the key press is bound to, cycling the
+    /// sections or toggling the descriptions, while focus is in the panel
+    fn panel_key_command(
+        &self,
+        data: &LapceTabData,
+        key_event: &KeyEvent,
+    ) -> Option<FocusCommand> {
+        let focus = LapceSettingsFocusData {
+            widget_id: self.widget_id,
+            editor_tab_id: self.editor_tab_id,
+            main_split: data.main_split.clone(),
+            config: data.config.clone(),
+        };
+        let keypress = KeyPress {
+            key: key_event.key.clone(),
+            mods: KeyPressData::get_key_modifiers(key_event),
+        };
+        data.keypress
+            .matching_keymaps(&[keypress.clone()], &focus)
+            .iter()
+            .rev()
+            .filter(|keymap| keymap.key == [keypress.to_lowercase()])
+            .find_map(|keymap| {
+                match &data.keypress.commands.get(&keymap.command)?.kind {
+                    CommandKind::Focus(
+                        command @ (FocusCommand::NextSettingsSection
+                        | FocusCommand::PreviousSettingsSection
+                        | FocusCommand::ToggleSettingsDescriptions),
+                    ) => Some(command.clone()),
+                    _ => None,
+                }
+            })
+    }
+
+    /// Run a command the panel handles itself rather than its focus
+    fn run_panel_command(
         &mut self,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
+        ctx: &mut EventCtx,
         data: &LapceTabData,
-        env: &Env,
+        command: &FocusCommand,
     ) {
-        for child in self.children.iter_mut() {
-            child.lifecycle(ctx, event, data, env);
+        match command {
+            FocusCommand::ToggleSettingsDescriptions => {
+                if let Some((table, _)) = self.active_section() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::ToggleSectionDescriptions,
+                            ),
+                            data: Some(serde_json::json!(table)),
+                        },
+                        Target::Widget(data.id),
+                    ));
+                }
+            }
+            _ => {
+                self.cycle_section(
+                    ctx,
+                    data,
+                    *command == FocusCommand::NextSettingsSection,
+                );
+            }
         }
     }
 
-    fn update(
+    /// Completes the search to the longest common prefix of the setting
+    /// identifiers it starts, leaving it as it is when there's none
+    fn complete_search(data: &mut LapceTabData) {
+        let doc = match data
+            .main_split
+            .local_docs
+            .get_mut(&LocalBufferKind::Settings)
+        {
+            Some(doc) => doc,
+            None => return,
+        };
+        let pattern = doc.buffer().to_string();
+        let completion =
+            match complete_setting_id(pattern.trim(), &Config::setting_ids()) {
+                Some(completion) => completion,
+                None => return,
+            };
+        // The search input reports its new text as the filter
+        Arc::make_mut(doc).reload(Rope::from(completion.as_str()), true);
+        if let Some(editor) = data
+            .main_split
+            .editors
+            .get_mut(&data.settings.settings_view_id)
+        {
+            Arc::make_mut(editor).cursor.mode =
+                CursorMode::Insert(Selection::caret(completion.len()));
+        }
+    }
+
+    /// Moves to the next or the previous section of the switcher, wrapping
+    /// around at the ends
+    fn cycle_section(
         &mut self,
-        ctx: &mut UpdateCtx,
-        _old_data: &LapceTabData,
+        ctx: &mut EventCtx,
         data: &LapceTabData,
-        env: &Env,
+        forward: bool,
     ) {
-        for child in self.children.iter_mut() {
-            child.update(ctx, data, env);
+        let visible = Self::visible_sections(data);
+        if visible.is_empty() {
+            return;
+        }
+        let len = visible.len();
+        let index = match visible.iter().position(|i| *i == self.active) {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None => 0,
+        };
+        if visible[index] != self.active {
+            self.active = visible[index];
+            // An input of the section left behind gives its focus back
+            ctx.request_focus();
+            ctx.request_layout();
         }
     }
 
-    fn layout(
-        &mut self,
-        ctx: &mut LayoutCtx,
-        bc: &BoxConstraints,
-        data: &LapceTabData,
-        env: &Env,
-    ) -> Size {
-        if self.children.is_empty() {
+    fn request_focus(&self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        let editor_tab = data
+            .main_split
+            .editor_tabs
+            .get_mut(&self.editor_tab_id)
+            .unwrap();
+        let editor_tab = Arc::make_mut(editor_tab);
+        if let Some(index) = editor_tab
+            .children
+            .iter()
+            .position(|child| child.widget_id() == self.widget_id)
+        {
+            editor_tab.active = index;
+        }
+
+        data.main_split.active_tab = Arc::new(Some(self.editor_tab_id));
+        data.focus = Arc::new(self.widget_id);
+        data.focus_area = FocusArea::Editor;
+        ctx.request_focus();
+    }
+}
+
+impl Widget<LapceTabData> for LapceSettingsPanel {
+    fn id(&self) -> Option<WidgetId> {
+        Some(self.widget_id)
+    }
+
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
+        env: &Env,
+    ) {
+        match event {
+            Event::KeyDown(key_event) => {
+                if ctx.is_focused() {
+                    let mut keypress = data.keypress.clone();
+                    let mut focus = LapceSettingsFocusData {
+                        widget_id: self.widget_id,
+                        editor_tab_id: self.editor_tab_id,
+                        main_split: data.main_split.clone(),
+                        config: data.config.clone(),
+                    };
+                    let mut_keypress = Arc::make_mut(&mut keypress);
+                    let performed_action =
+                        mut_keypress.key_down(ctx, key_event, &mut focus, env);
+                    data.keypress = keypress;
+                    data.main_split = focus.main_split;
+                    if performed_action {
+                        ctx.set_handled();
+                    }
+                } else if ctx.has_focus() {
+                    // A focused input keeps its keys, apart from the ones
+                    // of the panel commands
+                    if let Some(command) = self.panel_key_command(data, key_event) {
+                        self.run_panel_command(ctx, data, &command);
+                        ctx.set_handled();
+                    } else if key_event.key == KbKey::Tab
+                        && key_event.mods.is_empty()
+                        && *data.focus == data.settings.settings_view_id
+                    {
+                        // Tab completes a setting identifier in the search
+                        Self::complete_search(data);
+                        ctx.set_handled();
+                    }
+                }
+            }
+            Event::MouseDown(mouse_event) => {
+                self.mouse_down(ctx, mouse_event, data);
+            }
+            Event::MouseMove(mouse_event) => {
+                if ctx.is_active() {
+                    let width = (mouse_event.pos.x - self.switcher_rect.x0)
+                        .round()
+                        .max(0.0) as u32;
+                    let width = width.clamp(
+                        SettingsPanelPreferences::MIN_SWITCHER_WIDTH,
+                        SettingsPanelPreferences::MAX_SWITCHER_WIDTH,
+                    );
+                    if width != data.settings.preferences.switcher_width {
+                        Arc::make_mut(&mut data.settings)
+                            .preferences
+                            .switcher_width = width;
+                        ctx.request_layout();
+                    }
+                    ctx.set_handled();
+                }
+                if ctx.is_active() || self.divider_rect.contains(mouse_event.pos) {
+                    ctx.set_cursor(&druid::Cursor::ResizeLeftRight);
+                } else {
+                    ctx.clear_cursor();
+                }
+                let hover_section = if data.settings.preferences.switcher_collapsed
+                    && self.switcher_rect.contains(mouse_event.pos)
+                    && !self.collapse_rect.contains(mouse_event.pos)
+                {
+                    let row = self.switcher_row(mouse_event.pos.y);
+                    (row < Self::visible_sections(data).len()).then_some(row)
+                } else {
+                    None
+                };
+                if hover_section != self.hover_section {
+                    self.hover_section = hover_section;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                let _ = data
+                    .db
+                    .save_settings_preferences(&data.settings.preferences);
+                ctx.set_handled();
+            }
+            // Turns the spinner, the settings build on animation frames too
+            Event::AnimFrame(_) if data.settings.reloading.is_some() => {
+                ctx.request_paint();
+                if data.settings.is_reloading() {
+                    ctx.request_anim_frame();
+                }
+            }
+            Event::Command(cmd) if cmd.is(LAPCE_COMMAND) => {
+                let cmd = cmd.get_unchecked(LAPCE_COMMAND);
+                if let CommandKind::Focus(
+                    command @ (FocusCommand::NextSettingsSection
+                    | FocusCommand::PreviousSettingsSection
+                    | FocusCommand::ToggleSettingsDescriptions),
+                ) = &cmd.kind
+                {
+                    self.run_panel_command(ctx, data, command);
+                    ctx.set_handled();
+                } else {
+                    let mut focus = LapceSettingsFocusData {
+                        widget_id: self.widget_id,
+                        editor_tab_id: self.editor_tab_id,
+                        main_split: data.main_split.clone(),
+                        config: data.config.clone(),
+                    };
+                    if focus.run_command(ctx, cmd, None, Modifiers::empty(), env)
+                        == CommandExecuted::Yes
+                    {
+                        ctx.set_handled();
+                    }
+                    data.main_split = focus.main_split;
+                }
+            }
+            Event::Command(cmd) if cmd.is(LAPCE_UI_COMMAND) => {
+                let command = cmd.get_unchecked(LAPCE_UI_COMMAND);
+                match command {
+                    LapceUICommand::Focus => {
+                        ctx.set_handled();
+                        self.request_focus(ctx, data);
+                    }
+                    LapceUICommand::ShowSettings => {
+                        ctx.request_focus();
+                        self.active = 0;
+                    }
+                    LapceUICommand::ShowKeybindings => {
+                        ctx.request_focus();
+                        self.active = Self::KEYBINDINGS_SECTION;
+                    }
+                    LapceUICommand::ResetSettingsPanelLayout => {
+                        self.active = 0;
+                        self.history_open = false;
+                        for (_, id) in self.section_ids.iter() {
+                            ctx.submit_command(Command::new(
+                                LAPCE_UI_COMMAND,
+                                LapceUICommand::ScrollTo((0.0, 0.0)),
+                                Target::Widget(*id),
+                            ));
+                        }
+                        ctx.request_layout();
+                    }
+                    LapceUICommand::StartSettingsSession => {
+                        Arc::make_mut(&mut data.settings).session_changes.clear();
+                    }
+                    LapceUICommand::ShowSettingsField(table, key) => {
+                        ctx.request_focus();
+                        if let Some(index) =
+                            self.section_ids.iter().position(|(t, _)| t == table)
+                        {
+                            self.active = index;
+                            ctx.submit_command(Command::new(
+                                LAPCE_UI_COMMAND,
+                                LapceUICommand::ShowSettingsField(
+                                    table.clone(),
+                                    key.clone(),
+                                ),
+                                Target::Widget(self.section_ids[index].1),
+                            ));
+                        } else if table.starts_with("theme") {
+                            self.active = Self::THEME_SECTION;
+                        }
+                        ctx.request_layout();
+                    }
+                    LapceUICommand::Hide => {
+                        if let Some(active) = *data.main_split.active {
+                            ctx.submit_command(Command::new(
+                                LAPCE_UI_COMMAND,
+                                LapceUICommand::Focus,
+                                Target::Widget(active),
+                            ));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            _ => {}
+        }
+        self.switch_section_filter(data);
+
+        if ctx.is_handled() {
+            return;
+        }
+
+        if event.should_propagate_to_hidden() {
+            self.input.event(ctx, event, data, env);
+            for child in self.children.iter_mut() {
+                child.event(ctx, event, data, env);
+            }
+            self.config_pane.event(ctx, event, data, env);
+        } else {
+            if self.shows_input() {
+                self.input.event(ctx, event, data, env);
+            }
+            self.children[self.active].event(ctx, event, data, env);
+            if data.settings.preferences.show_effective_config {
+                self.config_pane.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.hover_section.take().is_some() {
+                ctx.request_paint();
+            }
+        }
+        if let LifeCycle::WidgetAdded = event {
             ctx.submit_command(Command::new(
                 LAPCE_UI_COMMAND,
-                LapceUICommand::InitChildren,
+                LapceUICommand::StartSettingsSession,
                 Target::Widget(self.widget_id),
             ));
         }
+        self.input.lifecycle(ctx, event, data, env);
+        for child in self.children.iter_mut() {
+            child.lifecycle(ctx, event, data, env);
+        }
+        self.config_pane.lifecycle(ctx, event, data, env);
+    }
 
-        let mut y = 0.0;
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        self.input.update(ctx, data, env);
+        // A search is kept in the history once the input loses the focus
+        let input_id = data.settings.settings_view_id;
+        if *old_data.focus == input_id && *data.focus != input_id {
+            ctx.submit_command(Command::new(
+                LAPCE_COMMAND,
+                LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::RecordSettingsSearch,
+                    ),
+                    data: None,
+                },
+                Target::Widget(data.id),
+            ));
+        }
+        let visible = Self::visible_sections(data);
+        if visible != Self::visible_sections(old_data) {
+            // A section opened by a command stays until the list changes
+            if !visible.contains(&self.active) {
+                if let Some(index) = visible.first() {
+                    self.active = *index;
+                }
+            }
+            ctx.request_layout();
+        }
+        if data.settings.reloading.is_some()
+            && data.settings.reloading != old_data.settings.reloading
+        {
+            ctx.request_anim_frame();
+        } else if data.settings.reloading.is_none()
+            && old_data.settings.reloading.is_some()
+        {
+            ctx.request_paint();
+        }
+        if data.settings.preferences.switcher_width
+            != old_data.settings.preferences.switcher_width
+            || data.settings.preferences.switcher_collapsed
+                != old_data.settings.preferences.switcher_collapsed
+            || data.settings.write_error != old_data.settings.write_error
+            || data.settings.mirror_error != old_data.settings.mirror_error
+            || data.settings.reload_pending != old_data.settings.reload_pending
+            || data.settings.search_history.is_empty()
+                != old_data.settings.search_history.is_empty()
+            || data.settings.preferences.show_effective_config
+                != old_data.settings.preferences.show_effective_config
+        {
+            ctx.request_layout();
+        }
         for child in self.children.iter_mut() {
-            let size = child.layout(ctx, bc, data, env);
-            child.set_origin(ctx, data, env, Point::new(0.0, y));
-            y += size.height;
+            child.update(ctx, data, env);
+        }
+        self.config_pane.update(ctx, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        env: &Env,
+    ) -> Size {
+        let self_size = bc.max();
+        let origin = Point::ZERO;
+        self.content_rect = self_size.to_rect().with_origin(origin).round();
+
+        let collapsed = data.settings.preferences.switcher_collapsed;
+        let switcher_width = if collapsed {
+            Self::COLLAPSED_SWITCHER_WIDTH
+        } else {
+            data.settings.preferences.switcher_width as f64
+        };
+        self.switcher_rect = Size::new(switcher_width, self_size.height)
+            .to_rect()
+            .with_origin(Point::ZERO)
+            .round();
+        let bottom_row =
+            Size::new(self.switcher_rect.width(), self.switcher_line_height)
+                .to_rect()
+                .with_origin(Point::new(
+                    self.switcher_rect.x0,
+                    self.switcher_rect.y1 - self.switcher_line_height,
+                ));
+        if collapsed {
+            // Only the button expanding the switcher has room next to the icons
+            self.divider_rect = Rect::ZERO;
+            self.view_defaults_rect = Rect::ZERO;
+            self.sort_order_rect = Rect::ZERO;
+            self.raw_keys_rect = Rect::ZERO;
+            self.advanced_rect = Rect::ZERO;
+            self.deprecated_rect = Rect::ZERO;
+            self.group_rect = Rect::ZERO;
+            self.effective_config_rect = Rect::ZERO;
+            self.collapse_rect = bottom_row;
+        } else {
+            self.divider_rect = Rect::new(
+                self.switcher_rect.x1,
+                0.0,
+                self.switcher_rect.x1 + 20.0,
+                self_size.height,
+            );
+            self.view_defaults_rect = bottom_row;
+            self.sort_order_rect = self.view_defaults_rect.with_origin(
+                self.view_defaults_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.raw_keys_rect = self.sort_order_rect.with_origin(
+                self.sort_order_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.advanced_rect = self.raw_keys_rect.with_origin(
+                self.raw_keys_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.deprecated_rect = self.advanced_rect.with_origin(
+                self.advanced_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.group_rect = self.deprecated_rect.with_origin(
+                self.deprecated_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.effective_config_rect = self.group_rect.with_origin(
+                self.group_rect.origin() - (0.0, self.switcher_line_height),
+            );
+            self.collapse_rect = self.effective_config_rect.with_origin(
+                self.effective_config_rect.origin()
+                    - (0.0, self.switcher_line_height),
+            );
+        }
+
+        let content_width = self_size.width - self.switcher_rect.width() - 20.0;
+        // The effective config takes the end of the content
+        let pane_width = if data.settings.preferences.show_effective_config {
+            (content_width / 2.0).min(Self::CONFIG_PANE_WIDTH).round()
+        } else {
+            0.0
+        };
+        let content_width = content_width - pane_width;
+        self.config_pane_rect = Rect::ZERO;
+        self.config_pane_close_rect = Rect::ZERO;
+        if pane_width > 0.0 {
+            self.config_pane_rect = Rect::new(
+                self_size.width - pane_width,
+                0.0,
+                self_size.width,
+                self_size.height,
+            );
+            self.config_pane_close_rect =
+                Size::new(14.0, 14.0).to_rect().with_origin(Point::new(
+                    self.config_pane_rect.x1 - 24.0,
+                    (Self::BREADCRUMB_HEIGHT - 14.0) / 2.0,
+                ));
+            let pane_bc = BoxConstraints::tight(Size::new(
+                pane_width,
+                self_size.height - Self::BREADCRUMB_HEIGHT,
+            ));
+            self.config_pane.layout(ctx, &pane_bc, data, env);
+            self.config_pane.set_origin(
+                ctx,
+                data,
+                env,
+                Point::new(self.config_pane_rect.x0, Self::BREADCRUMB_HEIGHT),
+            );
         }
+        let mut content_origin = Point::new(self.switcher_rect.width() + 20.0, 0.0);
+
+        let section_width = ctx
+            .text()
+            .new_text_layout(SETTINGS_SECTIONS[self.active])
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .build()
+            .unwrap()
+            .size()
+            .width;
+        self.breadcrumb_section_rect = Rect::new(
+            content_origin.x + 15.0,
+            content_origin.y + 5.0,
+            content_origin.x + 15.0 + section_width,
+            content_origin.y + Self::BREADCRUMB_HEIGHT - 5.0,
+        );
+        content_origin.y += Self::BREADCRUMB_HEIGHT;
+
+        self.write_error_banner = None;
+        self.write_error_close_rect = Rect::ZERO;
+        let banner = match (
+            data.settings.write_error.as_ref(),
+            data.settings.mirror_error.as_ref(),
+        ) {
+            (Some((table, key, reason)), _) => Some(format!(
+                "{table}.{key} couldn't be saved and is back to its stored \
+                 value. {reason}"
+            )),
+            (None, Some(reason)) => Some(format!(
+                "The settings were saved but couldn't be copied to the settings \
+                 mirror. {reason}"
+            )),
+            (None, None) => None,
+        };
+        if let Some(banner) = banner {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(banner)
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .max_width(content_width - 70.0)
+                .build()
+                .unwrap();
+            let rect = Rect::new(
+                content_origin.x + 15.0,
+                content_origin.y,
+                content_origin.x + content_width - 15.0,
+                content_origin.y + text_layout.size().height + 16.0,
+            );
+            self.write_error_close_rect = Size::new(14.0, 14.0)
+                .to_rect()
+                .with_origin(Point::new(rect.x1 - 24.0, rect.y0 + 8.0));
+            content_origin.y += rect.height() + 10.0;
+            self.write_error_banner = Some((rect, text_layout));
+        }
+
+        self.reload_banner = None;
+        self.reload_close_rect = Rect::ZERO;
+        if let Some(setting) = data.settings.reload_pending.as_ref() {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let text_layout = ctx
+                .text()
+                .new_text_layout(format!(
+                    "The open files follow the change of {setting} once they're \
+                     reloaded."
+                ))
+                .font(font_family.clone(), font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .max_width(content_width - 70.0)
+                .build()
+                .unwrap();
+            let link = ctx
+                .text()
+                .new_text_layout("Reload Open Files")
+                .font(font_family, font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let text_size = text_layout.size();
+            let rect = Rect::new(
+                content_origin.x + 15.0,
+                content_origin.y,
+                content_origin.x + content_width - 15.0,
+                content_origin.y + text_size.height + link.size().height + 21.0,
+            );
+            let link_rect = link
+                .size()
+                .to_rect()
+                .with_origin(rect.origin() + (10.0, 8.0 + text_size.height + 5.0));
+            self.reload_close_rect = Size::new(14.0, 14.0)
+                .to_rect()
+                .with_origin(Point::new(rect.x1 - 24.0, rect.y0 + 8.0));
+            content_origin.y += rect.height() + 10.0;
+            self.reload_banner = Some((rect, text_layout, link_rect, link));
+        }
+
+        self.history_rect = Rect::ZERO;
+        self.history_rows.clear();
+        if self.shows_input() {
+            // The button listing the recent searches takes the input's end
+            let history_width = if data.settings.search_history.is_empty() {
+                0.0
+            } else {
+                30.0
+            };
+            let input_bc = BoxConstraints::tight(Size::new(
+                content_width - history_width,
+                55.0,
+            ));
+            let input_size = self.input.layout(ctx, &input_bc, data, env);
+            self.input.set_origin(ctx, data, env, content_origin);
+            if history_width > 0.0 {
+                self.history_rect =
+                    Size::new(16.0, 16.0).to_rect().with_origin(Point::new(
+                        content_origin.x + content_width - history_width,
+                        content_origin.y + (input_size.height - 16.0) / 2.0,
+                    ));
+                if self.history_open {
+                    let row_height = 25.0;
+                    let x1 = content_origin.x + content_width - 10.0;
+                    let x0 = (x1 - 250.0).max(content_origin.x + 15.0);
+                    let mut y = content_origin.y + input_size.height;
+                    for pattern in data.settings.search_history.iter() {
+                        self.history_rows.push((
+                            Rect::new(x0, y, x1, y + row_height),
+                            pattern.clone(),
+                        ));
+                        y += row_height;
+                    }
+                }
+            }
+            content_origin.y += input_size.height;
+        }
+        let content_size =
+            Size::new(content_width, self_size.height - content_origin.y);
+        let content_bc = BoxConstraints::tight(content_size);
+        let child = &mut self.children[self.active];
+        child.layout(ctx, &content_bc, data, env);
+        child.set_origin(ctx, data, env, content_origin);
+
+        self_size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
+        ctx.fill(
+            self.content_rect,
+            data.config.get_color_or(
+                LapceTheme::SETTINGS_BACKGROUND,
+                LapceTheme::EDITOR_BACKGROUND,
+            ),
+        );
+        ctx.fill(
+            self.switcher_rect,
+            data.config.get_color_or(
+                LapceTheme::SETTINGS_SWITCHER_BACKGROUND,
+                LapceTheme::EDITOR_BACKGROUND,
+            ),
+        );
+
+        let visible = Self::visible_sections(data);
+        if let Some(row) = visible.iter().position(|i| *i == self.active) {
+            ctx.fill(
+                Size::new(self.switcher_rect.width(), self.switcher_line_height)
+                    .to_rect()
+                    .with_origin(
+                        self.switcher_rect.origin()
+                            + (0.0, row as f64 * self.switcher_line_height),
+                    ),
+                data.config.get_color_or(
+                    LapceTheme::SETTINGS_SWITCHER_CURRENT,
+                    LapceTheme::EDITOR_CURRENT_LINE,
+                ),
+            );
+        }
+
+        if data.settings.preferences.switcher_collapsed {
+            self.paint_collapsed_switcher(ctx, data, &visible);
+        } else {
+            self.paint_switcher(ctx, data, &visible);
+        }
+
+        self.paint_breadcrumb(ctx, data);
+        self.paint_reloading(ctx, data);
+        self.paint_write_error(ctx, data);
+        self.paint_reload_banner(ctx, data);
+        if self.shows_input() {
+            self.input.paint(ctx, data, env);
+        }
+        self.children[self.active].paint(ctx, data, env);
+        self.paint_config_pane(ctx, data, env);
+        self.paint_search_history(ctx, data);
+
+        ctx.stroke(
+            Line::new(
+                Point::new(self.switcher_rect.x1 + 0.5, self.switcher_rect.y0),
+                Point::new(self.switcher_rect.x1 + 0.5, self.switcher_rect.y1),
+            ),
+            data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+            1.0,
+        );
+
+        if let Some(row) = self.hover_section {
+            if let Some(index) = visible.get(row) {
+                self.paint_section_tooltip(ctx, data, row, *index);
+            }
+        }
+    }
+}
+
+impl LapceSettingsPanel {
+    fn paint_switcher(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        visible: &[usize],
+    ) {
+        ctx.with_save(|ctx| {
+            ctx.clip(self.switcher_rect);
+            for (row, index) in visible.iter().enumerate() {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(SETTINGS_SECTIONS[*index])
+                    .font(
+                        data.config.ui.font_family(),
+                        (data.config.ui.font_size() + 1) as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                ctx.draw_text(
+                    &text_layout,
+                    self.switcher_rect.origin()
+                        + (
+                            20.0,
+                            row as f64 * self.switcher_line_height
+                                + text_layout.y_offset(self.switcher_line_height),
+                        ),
+                );
+            }
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(if data.settings.preferences.sort_alphabetically {
+                    "Sort: A-Z"
+                } else {
+                    "Sort: Default"
+                })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.sort_order_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(if data.settings.preferences.show_raw_keys {
+                    "Names: Keys"
+                } else {
+                    "Names: Titles"
+                })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.raw_keys_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(if data.settings.preferences.show_advanced {
+                    "Advanced: Shown"
+                } else {
+                    "Advanced: Hidden"
+                })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.advanced_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(if data.settings.preferences.show_deprecated {
+                    "Deprecated: Shown"
+                } else {
+                    "Deprecated: Hidden"
+                })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.deprecated_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(if data.settings.preferences.group_by_changed {
+                    "Group: Changed"
+                } else {
+                    "Group: None"
+                })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.group_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout(
+                    if data.settings.preferences.show_effective_config {
+                        "Effective Config: Shown"
+                    } else {
+                        "Effective Config: Hidden"
+                    },
+                )
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.effective_config_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout("View Defaults")
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.view_defaults_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+
+            let text_layout = ctx
+                .text()
+                .new_text_layout("\u{2039} Icons Only")
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                self.collapse_rect.origin()
+                    + (20.0, text_layout.y_offset(self.switcher_line_height)),
+            );
+        });
+    }
+
+    /// Paint the pane of the effective config with its title and the button
+    /// hiding it, if it's shown
+    fn paint_config_pane(
+        &mut self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        if self.config_pane_rect.width() <= 0.0 {
+            return;
+        }
+        ctx.fill(
+            self.config_pane_rect,
+            data.config.get_color_or(
+                LapceTheme::SETTINGS_SWITCHER_BACKGROUND,
+                LapceTheme::EDITOR_BACKGROUND,
+            ),
+        );
+        ctx.stroke(
+            Line::new(
+                Point::new(self.config_pane_rect.x0 + 0.5, self.config_pane_rect.y0),
+                Point::new(self.config_pane_rect.x0 + 0.5, self.config_pane_rect.y1),
+            ),
+            data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+            1.0,
+        );
+
+        let text_layout = ctx
+            .text()
+            .new_text_layout("Effective Config")
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        ctx.draw_text(
+            &text_layout,
+            Point::new(
+                self.config_pane_rect.x0 + 10.0,
+                text_layout.y_offset(Self::BREADCRUMB_HEIGHT),
+            ),
+        );
+        if let Some(svg) = get_svg("close.svg") {
+            ctx.draw_svg(
+                &svg,
+                self.config_pane_close_rect,
+                Some(data.config.get_color_unchecked(LapceTheme::EDITOR_DIM)),
+            );
+        }
+
+        self.config_pane.paint(ctx, data, env);
+    }
+
+    fn paint_collapsed_switcher(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        visible: &[usize],
+    ) {
+        let icon_size = 16.0;
+        let icon_rect = |row: Rect| {
+            Size::new(icon_size, icon_size)
+                .to_rect()
+                .with_origin(row.center() - (icon_size / 2.0, icon_size / 2.0))
+        };
+        let color = data
+            .config
+            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND);
+        ctx.with_save(|ctx| {
+            ctx.clip(self.switcher_rect);
+            for (row, index) in visible.iter().enumerate() {
+                let row_rect =
+                    Size::new(self.switcher_rect.width(), self.switcher_line_height)
+                        .to_rect()
+                        .with_origin(
+                            self.switcher_rect.origin()
+                                + (0.0, row as f64 * self.switcher_line_height),
+                        );
+                if let Some(svg) = get_svg(SETTINGS_SECTION_ICONS[*index]) {
+                    ctx.draw_svg(&svg, icon_rect(row_rect), Some(color));
+                }
+            }
+            if let Some(svg) = get_svg("chevron-right.svg") {
+                ctx.draw_svg(
+                    &svg,
+                    icon_rect(self.collapse_rect),
+                    Some(data.config.get_color_unchecked(LapceTheme::EDITOR_DIM)),
+                );
+            }
+        });
+    }
+
+    /// Name the section of the switcher row under the mouse next to the
+    /// collapsed switcher
+    fn paint_section_tooltip(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        row: usize,
+        index: usize,
+    ) {
+        let text_layout = ctx
+            .text()
+            .new_text_layout(SETTINGS_SECTIONS[index])
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_size = text_layout.size();
+        let rect = Size::new(text_size.width + 16.0, text_size.height + 10.0)
+            .to_rect()
+            .with_origin(Point::new(
+                self.switcher_rect.x1 + 4.0,
+                self.switcher_rect.y0
+                    + row as f64 * self.switcher_line_height
+                    + (self.switcher_line_height - text_size.height - 10.0) / 2.0,
+            ));
+        let shadow_width = data.config.ui.drop_shadow_width() as f64;
+        if shadow_width > 0.0 {
+            ctx.blurred_rect(
+                rect,
+                shadow_width,
+                data.config
+                    .get_color_unchecked(LapceTheme::LAPCE_DROPDOWN_SHADOW),
+            );
+        } else {
+            ctx.stroke(
+                rect.inflate(0.5, 0.5),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+        }
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::PANEL_BACKGROUND),
+        );
+        ctx.draw_text(&text_layout, rect.origin() + (8.0, 5.0));
+    }
+}
+
+struct LapceSettings {
+    widget_id: WidgetId,
+    scroll_id: WidgetId,
+    kind: LapceSettingsKind,
+    keys: Vec<String>,
+    /// The settings table of each item, which differ in the recent changes
+    tables: Vec<String>,
+    reveal_field: Option<String>,
+    /// The preview of the editor font settings, above the editor settings
+    preview: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    children: Vec<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    /// The (table, field, description, value) of the items not built yet,
+    /// which are built a few at a time on the next frames
+    pending: VecDeque<(String, String, String, serde_json::Value)>,
+    /// The headers of the changed and default settings while they're
+    /// grouped, by the index of the first item under them
+    group_headers: Vec<(usize, String)>,
+    group_header_rects: Vec<(Rect, String)>,
+}
+
+impl LapceSettings {
+    /// The number of items built at once, so that opening a large section
+    /// doesn't block the UI
+    const ITEMS_PER_FRAME: usize = 15;
+    /// The narrowest a column of items gets, a wide section lays its items
+    /// out in as many columns of at least this width as fit
+    const MIN_COLUMN_WIDTH: f64 = 480.0;
+    const MAX_COLUMNS: usize = 3;
+
+    pub fn new_split(
+        kind: LapceSettingsKind,
+        widget_id: WidgetId,
+        data: &LapceTabData,
+    ) -> LapceSplit {
+        let scroll_id = WidgetId::next();
+        let table = kind.table().to_string();
+        let preview = match kind {
+            LapceSettingsKind::Editor => Some(WidgetPod::new(
+                LapcePadding::new((10.0, 10.0), EditorFontPreview::new()).boxed(),
+            )),
+            _ => None,
+        };
+        let settings = LapceSettingsScroll::new(
+            scroll_id,
+            Some(widget_id),
+            table,
+            LapceScroll::new(
+                Self {
+                    widget_id,
+                    scroll_id,
+                    kind,
+                    keys: Vec::new(),
+                    tables: Vec::new(),
+                    reveal_field: None,
+                    preview,
+                    children: Vec::new(),
+                    pending: VecDeque::new(),
+                    group_headers: Vec::new(),
+                    group_header_rects: Vec::new(),
+                }
+                .boxed(),
+            ),
+        );
+
+        let split = LapceSplit::new(data.settings.settings_split_id)
+            .horizontal()
+            .with_flex_child(settings.boxed(), None, 1.0, false);
+
+        split
+    }
+
+    fn update_children(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        self.clear_children();
+
+        let items: Vec<(String, String, String, serde_json::Value)> = match self.kind
+        {
+            LapceSettingsKind::Recent => data
+                .settings
+                .recent_changes
+                .iter()
+                .filter_map(|change| {
+                    table_items(&change.kind, &data.config)
+                        .into_iter()
+                        .find(|(field, _, _)| field == &change.name)
+                        .map(|(field, desc, value)| {
+                            (change.kind.clone(), field, desc, value)
+                        })
+                })
+                .collect(),
+            _ => {
+                let table = self.kind.table();
+                let mut items: Vec<_> = table_items(table, &data.config)
+                    .into_iter()
+                    .map(|(field, desc, value)| {
+                        (table.to_string(), field, desc, value)
+                    })
+                    .collect();
+                if data.settings.preferences.sort_alphabetically {
+                    items.sort_by(|a, b| a.1.cmp(&b.1));
+                }
+                items
+            }
+        };
+
+        let pattern = &data.settings.filter_pattern;
+        // The recently changed section lists changes already
+        let group = data.settings.preferences.group_by_changed
+            && !matches!(self.kind, LapceSettingsKind::Recent);
+        let mut default_items = Vec::new();
+        for (table, field, desc, value) in items {
+            if !data.settings.preferences.lists_setting(&table, &field) {
+                continue;
+            }
+            if !pattern.trim().is_empty()
+                && settings_filter_matches(
+                    &setting_display_name(
+                        &table,
+                        &field,
+                        &data.settings.preferences,
+                    ),
+                    pattern,
+                )
+                .is_empty()
+                && settings_filter_matches(&format!("{table}.{field}"), pattern)
+                    .is_empty()
+                && settings_filter_matches(&desc, pattern).is_empty()
+            {
+                continue;
+            }
+            // The search filters within the groups
+            // A setting without a default is at it while it's unset
+            if group
+                && Config::default_setting_table(&table)
+                    .and_then(|defaults| defaults.get(&field))
+                    .unwrap_or(&serde_json::Value::Null)
+                    == &value
+            {
+                default_items.push((table, field, desc, value));
+                continue;
+            }
+            self.pending.push_back((table, field, desc, value));
+        }
+        if group {
+            let changed = self.pending.len();
+            if changed > 0 {
+                self.group_headers.push((0, format!("Changed ({changed})")));
+            }
+            if !default_items.is_empty() {
+                self.group_headers
+                    .push((changed, format!("Default ({})", default_items.len())));
+            }
+            self.pending.extend(default_items);
+        }
+        self.build_pending(ctx, data);
+    }
+
+    /// Build the next few pending items, asking for another frame to build
+    /// the rest
+    fn build_pending(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        let expanded = data
+            .settings
+            .preferences
+            .descriptions_expanded(self.kind.table());
+        for _ in 0..Self::ITEMS_PER_FRAME {
+            let (table, field, desc, value) = match self.pending.pop_front() {
+                Some(item) => item,
+                None => break,
+            };
+            self.keys.push(field.clone());
+            self.tables.push(table.clone());
+            self.children.push(WidgetPod::new(
+                LapcePadding::new(
+                    (10.0, 10.0),
+                    LapceSettingsItem::new(
+                        data,
+                        table,
+                        field,
+                        desc,
+                        value,
+                        expanded,
+                        ctx.get_external_handle(),
+                    ),
+                )
+                .boxed(),
+            ))
+        }
+        if !self.pending.is_empty() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn clear_children(&mut self) {
+        self.children.clear();
+        self.keys.clear();
+        self.tables.clear();
+        self.pending.clear();
+        self.group_headers.clear();
+        self.group_header_rects.clear();
+    }
+
+    /// Make the item at the index the current setting of the breadcrumb
+    fn set_current(&self, data: &mut LapceTabData, index: Option<usize>) {
+        let current = index.map(|index| {
+            (
+                self.kind.table().to_string(),
+                self.tables[index].clone(),
+                self.keys[index].clone(),
+            )
+        });
+        if data.settings.current_setting != current {
+            Arc::make_mut(&mut data.settings).current_setting = current;
+        }
+    }
+}
+
+/// The name a setting is shown with, either the title of the field or the
+/// dotted key it has in the settings file
+fn setting_display_name(
+    table: &str,
+    field: &str,
+    preferences: &SettingsPanelPreferences,
+) -> String {
+    if preferences.show_raw_keys {
+        format!("{table}.{field}")
+    } else {
+        field.to_title_case()
+    }
+}
+
+/// The settings of a settings file table as (field, description, value),
+/// in the order they are declared.
+fn table_items(
+    table: &str,
+    config: &Config,
+) -> Vec<(String, String, serde_json::Value)> {
+    fn into_settings_map(
+        data: &impl Serialize,
+    ) -> HashMap<String, serde_json::Value> {
+        serde_json::to_value(data)
+            .and_then(serde_json::from_value)
+            .unwrap()
+    }
+
+    let (fields, descs, mut settings) = match table {
+        "lapce" => (
+            &LapceConfig::FIELDS[..],
+            &LapceConfig::DESCS[..],
+            into_settings_map(&config.lapce),
+        ),
+        "ui" => (
+            &UIConfig::FIELDS[..],
+            &UIConfig::DESCS[..],
+            into_settings_map(&config.ui),
+        ),
+        "editor" => (
+            &EditorConfig::FIELDS[..],
+            &EditorConfig::DESCS[..],
+            into_settings_map(&config.editor),
+        ),
+        "terminal" => (
+            &TerminalConfig::FIELDS[..],
+            &TerminalConfig::DESCS[..],
+            into_settings_map(&config.terminal),
+        ),
+        _ => return Vec::new(),
+    };
+
+    fields
+        .iter()
+        .zip(descs.iter())
+        .filter_map(|(field, desc)| {
+            // TODO(dbuga): we should generate kebab-case field names
+            let field = field.replace('_', "-");
+            let value = settings.remove(&field)?;
+            Some((field, desc.to_string(), value))
+        })
+        .collect()
+}
+
+impl Widget<LapceTabData> for LapceSettings {
+    fn id(&self) -> Option<WidgetId> {
+        Some(self.widget_id)
+    }
+
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd)
+                if cmd.is(LAPCE_UI_COMMAND)
+                    && cmd.target() == Target::Widget(self.widget_id) =>
+            {
+                match cmd.get_unchecked(LAPCE_UI_COMMAND) {
+                    LapceUICommand::ShowSettingsField(table, key) => {
+                        self.reveal_field = Some(key.clone());
+                        Arc::make_mut(&mut data.settings).current_setting = Some((
+                            self.kind.table().to_string(),
+                            table.clone(),
+                            key.clone(),
+                        ));
+                        ctx.request_layout();
+                        ctx.set_handled();
+                    }
+                    LapceUICommand::ScrollTo(offset) => {
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::ScrollTo(*offset),
+                            Target::Widget(self.scroll_id),
+                        ));
+                        ctx.set_handled();
+                    }
+                    LapceUICommand::SettingsScrolled(y) => {
+                        let index = if *y > 0.0 {
+                            self.children
+                                .iter()
+                                .position(|child| child.layout_rect().y1 > *y)
+                        } else {
+                            None
+                        };
+                        self.set_current(data, index);
+                        // Scrolling towards the end needs the rest of the
+                        // items sooner
+                        if !self.pending.is_empty() {
+                            self.build_pending(ctx, data);
+                            ctx.children_changed();
+                        }
+                        ctx.set_handled();
+                    }
+                    _ => {}
+                }
+            }
+            Event::AnimFrame(_) if !self.pending.is_empty() => {
+                self.build_pending(ctx, data);
+                ctx.children_changed();
+            }
+            Event::MouseDown(mouse_event) => {
+                let index = self
+                    .children
+                    .iter()
+                    .position(|child| child.layout_rect().contains(mouse_event.pos));
+                if index.is_some() {
+                    self.set_current(data, index);
+                }
+            }
+            _ => {}
+        }
+        if let Some(preview) = self.preview.as_mut() {
+            preview.event(ctx, event, data, env);
+        }
+        for child in self.children.iter_mut() {
+            child.event(ctx, event, data, env);
+        }
+        if self.children.is_empty() {
+            self.update_children(ctx, data);
+            ctx.children_changed();
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        if let Some(preview) = self.preview.as_mut() {
+            preview.lifecycle(ctx, event, data, env);
+        }
+        for child in self.children.iter_mut() {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        if let Some(preview) = self.preview.as_mut() {
+            preview.update(ctx, data, env);
+        }
+        let recent_changed = matches!(self.kind, LapceSettingsKind::Recent)
+            && !data
+                .settings
+                .recent_changes
+                .iter()
+                .map(|c| (&c.kind, &c.name))
+                .eq(old_data
+                    .settings
+                    .recent_changes
+                    .iter()
+                    .map(|c| (&c.kind, &c.name)));
+        if recent_changed
+            || data.settings.preferences.sort_alphabetically
+                != old_data.settings.preferences.sort_alphabetically
+            || data.settings.preferences.show_advanced
+                != old_data.settings.preferences.show_advanced
+            || data.settings.preferences.show_deprecated
+                != old_data.settings.preferences.show_deprecated
+            || data.settings.preferences.group_by_changed
+                != old_data.settings.preferences.group_by_changed
+            || data.settings.filter_pattern != old_data.settings.filter_pattern
+            || data
+                .settings
+                .preferences
+                .descriptions_expanded(self.kind.table())
+                != old_data
+                    .settings
+                    .preferences
+                    .descriptions_expanded(self.kind.table())
+        {
+            // The items get rebuilt in the new order on the next event
+            self.clear_children();
+            ctx.children_changed();
+            return;
+        }
+        for child in self.children.iter_mut() {
+            child.update(ctx, data, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        env: &Env,
+    ) -> Size {
+        if self.children.is_empty() {
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::InitChildren,
+                Target::Widget(self.widget_id),
+            ));
+        }
+
+        let mut y = 0.0;
+        if let Some(preview) = self.preview.as_mut() {
+            let size = preview.layout(ctx, &bc.loosen(), data, env);
+            preview.set_origin(ctx, data, env, Point::ZERO);
+            y += size.height;
+        }
+        // The items fill the columns row by row, so they're still read in
+        // order and the first item below an offset is the one shown there
+        let columns = ((bc.max().width / Self::MIN_COLUMN_WIDTH).floor() as usize)
+            .clamp(1, Self::MAX_COLUMNS);
+        let column_width = bc.max().width / columns as f64;
+        let child_bc = BoxConstraints::new(
+            Size::new(column_width, bc.min().height),
+            Size::new(column_width, bc.max().height),
+        );
+        let mut column = 0;
+        let mut row_height: f64 = 0.0;
+        self.group_header_rects.clear();
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Some((_, header)) =
+                self.group_headers.iter().find(|(start, _)| *start == i)
+            {
+                // A group starts on a row of its own
+                if column > 0 {
+                    y += row_height;
+                    column = 0;
+                    row_height = 0.0;
+                }
+                let rect =
+                    Rect::new(10.0, y + 10.0, bc.max().width - 10.0, y + 40.0);
+                self.group_header_rects.push((rect, header.clone()));
+                y = rect.y1;
+            }
+            let size = child.layout(ctx, &child_bc, data, env);
+            child.set_origin(
+                ctx,
+                data,
+                env,
+                Point::new(column as f64 * column_width, y),
+            );
+            row_height = row_height.max(size.height);
+            column += 1;
+            if column == columns {
+                y += row_height;
+                column = 0;
+                row_height = 0.0;
+            }
+        }
+        y += row_height;
+
+        if !self.children.is_empty() {
+            if let Some(key) = self.reveal_field.take() {
+                if let Some(index) = self.keys.iter().position(|k| k == &key) {
+                    let y = self.children[index].layout_rect().y0;
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::ScrollTo((0.0, y)),
+                        Target::Widget(self.scroll_id),
+                    ));
+                } else if self.pending.iter().any(|(_, field, _, _)| field == &key) {
+                    // Revealed once the item is built
+                    self.reveal_field = Some(key);
+                }
+            }
+        }
+
+        Size::new(bc.max().width, bc.max().height.max(y))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
+        if let Some(preview) = self.preview.as_mut() {
+            preview.paint(ctx, data, env);
+        }
+        for child in self.children.iter_mut() {
+            child.paint(ctx, data, env);
+        }
+
+        for (rect, header) in self.group_header_rects.iter() {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(header.clone())
+                .font(
+                    data.config.ui.font_family(),
+                    (data.config.ui.font_size() + 1) as f64,
+                )
+                .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &text_layout,
+                Point::new(rect.x0, rect.y0 + text_layout.y_offset(rect.height())),
+            );
+            ctx.stroke(
+                Line::new(
+                    Point::new(rect.x0, rect.y1 - 0.5),
+                    Point::new(rect.x1, rect.y1 - 0.5),
+                ),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+        }
+
+        let top = ctx.region().bounding_box().y0;
+        if top > 0.0 {
+            if let Some(index) = self
+                .children
+                .iter()
+                .position(|child| child.layout_rect().y1 > top)
+            {
+                let name = setting_display_name(
+                    &self.tables[index],
+                    &self.keys[index],
+                    &data.settings.preferences,
+                );
+                paint_scroll_indicator(ctx, data, &name);
+            }
+        }
+    }
+}
+
+/// Paint a floating label at the top right of the visible region, naming
+/// the topmost visible setting as the section is scrolled.
+fn paint_scroll_indicator(ctx: &mut PaintCtx, data: &LapceTabData, name: &str) {
+    let region = ctx.region().bounding_box();
+    let text_layout = ctx
+        .text()
+        .new_text_layout(name.to_string())
+        .font(
+            data.config.ui.font_family(),
+            data.config.ui.font_size() as f64,
+        )
+        .text_color(
+            data.config
+                .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                .clone(),
+        )
+        .build()
+        .unwrap();
+    let text_size = text_layout.size();
+    let rect = Size::new(text_size.width + 20.0, text_size.height + 10.0)
+        .to_rect()
+        .with_origin(Point::new(
+            region.x1 - text_size.width - 40.0,
+            region.y0 + 10.0,
+        ));
+    ctx.fill(
+        rect,
+        data.config
+            .get_color_unchecked(LapceTheme::PANEL_BACKGROUND),
+    );
+    ctx.stroke(
+        rect.inflate(-0.5, -0.5),
+        data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+        1.0,
+    );
+    ctx.draw_text(&text_layout, rect.origin() + (10.0, 5.0));
+}
+
+/// Scroll container of a settings section which keeps its offset in
+/// `LapceSettingsPanelData`, so it's restored when the section is shown again.
+struct LapceSettingsScroll {
+    widget_id: WidgetId,
+    /// The settings section being scrolled, which is told the new offsets
+    content_id: Option<WidgetId>,
+    table: String,
+    scroll: LapceScroll<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    restored: bool,
+}
+
+impl LapceSettingsScroll {
+    fn new(
+        widget_id: WidgetId,
+        content_id: Option<WidgetId>,
+        table: String,
+        scroll: LapceScroll<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    ) -> Self {
+        Self {
+            widget_id,
+            content_id,
+            table,
+            scroll,
+            restored: false,
+        }
+    }
+}
+
+impl Widget<LapceTabData> for LapceSettingsScroll {
+    fn id(&self) -> Option<WidgetId> {
+        Some(self.widget_id)
+    }
+
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
+        env: &Env,
+    ) {
+        let offset = self.scroll.offset();
+        match event {
+            Event::Command(cmd)
+                if cmd.is(LAPCE_UI_COMMAND)
+                    && cmd.target() == Target::Widget(self.widget_id) =>
+            {
+                match cmd.get_unchecked(LAPCE_UI_COMMAND) {
+                    LapceUICommand::ScrollTo((x, y)) => {
+                        self.restored = true;
+                        self.scroll.scroll_to(Point::new(*x, *y));
+                        ctx.request_paint();
+                        ctx.set_handled();
+                    }
+                    LapceUICommand::EnsureRectVisible(rect) => {
+                        self.restored = true;
+                        self.scroll.scroll_to_visible(*rect, env);
+                        ctx.request_paint();
+                        ctx.set_handled();
+                    }
+                    _ => {}
+                }
+            }
+            _ => self.scroll.event(ctx, event, data, env),
+        }
+        // Whatever moved the offset, the wheel, the scroll bar or the keys,
+        // it's where the section is shown again from
+        if self.scroll.offset() != offset {
+            self.restored = true;
+        }
+
+        if self.restored {
+            let offset = self.scroll.offset();
+            if data.settings.scroll_offsets.get(&self.table) != Some(&offset) {
+                Arc::make_mut(&mut data.settings)
+                    .scroll_offsets
+                    .insert(self.table.clone(), offset);
+                if let Some(content_id) = self.content_id {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::SettingsScrolled(offset.y),
+                        Target::Widget(content_id),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        self.scroll.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        self.scroll.update(ctx, old_data, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        env: &Env,
+    ) -> Size {
+        let size = self.scroll.layout(ctx, bc, data, env);
+        if !self.restored {
+            // The section items are created lazily, so keep trying until
+            // the content is tall enough to scroll to the stored offset.
+            match data.settings.scroll_offsets.get(&self.table) {
+                Some(offset) => {
+                    self.scroll.scroll_to(offset.to_point());
+                    if self.scroll.offset() == *offset {
+                        self.restored = true;
+                    }
+                }
+                None => self.restored = true,
+            }
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
+        self.scroll.paint(ctx, data, env);
+    }
+}
+
+/// Sample code drawn with the editor font settings, including the values that
+/// are still being edited in the settings inputs, so they can be judged before
+/// they're applied. A caret blinks in it at the blink interval, shaped like
+/// the caret of the mode the editors start in.
+struct EditorFontPreview {
+    /// The editor config with the values being edited, recomputed when the
+    /// config or the settings inputs change
+    editor: EditorConfig,
+    /// The font family, size and line height the text layouts were built with
+    font: Option<(String, usize, usize)>,
+    text_layouts: Vec<PietTextLayout>,
+    /// The blink interval the caret timer was requested with
+    blink_interval: u64,
+    caret_timer: TimerToken,
+    caret_visible: bool,
+}
+
+impl EditorFontPreview {
+    const SAMPLE: &'static str = "fn main() {
+    let words = vec![\"lapce\", \"editor\"];
+    // -> :: == === !== >= <= && || |> <| /* */ ..= www
+    for (i, word) in words.iter().enumerate() {
+        println!(\"{i}: {word} 0O 1lI {} [] () => != <=\");
+    }
+}";
+
+    /// The line and column of the sample the caret is on
+    const CARET_POSITION: (usize, usize) = (0, 3);
+
+    fn new() -> Self {
+        Self {
+            editor: EditorConfig::default(),
+            font: None,
+            text_layouts: Vec::new(),
+            blink_interval: 0,
+            caret_timer: TimerToken::INVALID,
+            caret_visible: true,
+        }
+    }
+
+    /// The editor config with the font settings taken from the settings
+    /// inputs where they hold a valid value
+    fn live_config(data: &LapceTabData) -> EditorConfig {
+        let mut editor = match serde_json::to_value(&data.config.editor) {
+            Ok(editor) => editor,
+            Err(_) => return data.config.editor.clone(),
+        };
+        for key in ["font-family", "font-size", "line-height", "blink-interval"] {
+            let value = data
+                .main_split
+                .value_docs
+                .get(&format!("editor.{key}"))
+                .and_then(|doc| match doc.content() {
+                    BufferContent::SettingsValue(_, kind, parent, _) => {
+                        parse_settings_value(kind, parent, &doc.buffer().to_string())
+                            .ok()
+                    }
+                    _ => None,
+                });
+            if let Some(value) = value {
+                editor[key] = value;
+            }
+        }
+        serde_json::from_value(editor).unwrap_or_else(|_| data.config.editor.clone())
+    }
+
+    fn live_font(&self) -> (String, usize, usize) {
+        (
+            self.editor.font_family.clone(),
+            self.editor.font_size,
+            self.editor.line_height(),
+        )
+    }
+}
+
+impl Widget<LapceTabData> for EditorFontPreview {
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        _data: &mut LapceTabData,
+        _env: &Env,
+    ) {
+        if let Event::Timer(token) = event {
+            if *token == self.caret_timer && self.blink_interval > 0 {
+                self.caret_visible = !self.caret_visible;
+                self.caret_timer = ctx
+                    .request_timer(Duration::from_millis(self.blink_interval), None);
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &LapceTabData,
+        _env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.editor = Self::live_config(data);
+            self.blink_interval = self.editor.blink_interval;
+            if self.blink_interval > 0 {
+                self.caret_timer = ctx
+                    .request_timer(Duration::from_millis(self.blink_interval), None);
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        _env: &Env,
+    ) {
+        if data.config.id != old_data.config.id
+            || !data
+                .main_split
+                .value_docs
+                .same(&old_data.main_split.value_docs)
+        {
+            self.editor = Self::live_config(data);
+        }
+        if data.config.id != old_data.config.id {
+            self.font = None;
+            ctx.request_layout();
+        } else if self.font.as_ref() != Some(&self.live_font()) {
+            ctx.request_layout();
+        }
+
+        // The caret blinks at an interval as soon as it's typed, shown first
+        let blink_interval = self.editor.blink_interval;
+        if blink_interval != self.blink_interval {
+            self.blink_interval = blink_interval;
+            self.caret_visible = true;
+            self.caret_timer = if blink_interval > 0 {
+                ctx.request_timer(Duration::from_millis(blink_interval), None)
+            } else {
+                TimerToken::INVALID
+            };
+            ctx.request_paint();
+        }
+        if data.config.lapce.modal != old_data.config.lapce.modal {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        _env: &Env,
+    ) -> Size {
+        let font = self.live_font();
+        if self.font.as_ref() != Some(&font) {
+            let (family, size, _) = &font;
+            let family = if family.is_empty() {
+                druid::FontFamily::SYSTEM_UI
+            } else {
+                druid::FontFamily::new_unchecked(family.as_str())
+            };
+            self.text_layouts = Self::SAMPLE
+                .lines()
+                .map(|line| {
+                    ctx.text()
+                        .new_text_layout(line.to_string())
+                        .font(family.clone(), *size as f64)
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap()
+                })
+                .collect();
+            self.font = Some(font);
+        }
+
+        let line_height = self.font.as_ref().map(|(_, _, h)| *h).unwrap_or(0);
+        Size::new(
+            bc.max().width,
+            (self.text_layouts.len() * line_height) as f64 + 20.0,
+        )
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, _env: &Env) {
+        let rect = ctx.size().to_rect();
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::EDITOR_BACKGROUND),
+        );
+        ctx.stroke(
+            rect.inflate(-0.5, -0.5),
+            data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+            1.0,
+        );
+
+        let line_height = self.font.as_ref().map(|(_, _, h)| *h).unwrap_or(0) as f64;
+        let (caret_line, caret_col) = Self::CARET_POSITION;
+        let caret = self
+            .text_layouts
+            .get(caret_line)
+            .filter(|_| self.caret_visible)
+            .map(|text_layout| {
+                let x0 = text_layout.hit_test_text_position(caret_col).point.x;
+                let x1 = text_layout.hit_test_text_position(caret_col + 1).point.x;
+                let y = 10.0 + caret_line as f64 * line_height;
+                (Point::new(10.0 + x0, y), x1 - x0)
+            });
+        ctx.with_save(|ctx| {
+            ctx.clip(rect);
+            // Under the text, like the block caret of the editor
+            if let Some((origin, char_width)) = caret {
+                let caret_color =
+                    data.config.get_color_unchecked(LapceTheme::EDITOR_CARET);
+                if data.config.lapce.modal {
+                    ctx.fill(
+                        Size::new(char_width, line_height)
+                            .to_rect()
+                            .with_origin(origin),
+                        caret_color,
+                    );
+                } else {
+                    ctx.stroke(
+                        Line::new(origin, origin + (0.0, line_height)),
+                        caret_color,
+                        2.0,
+                    );
+                }
+            }
+            for (i, text_layout) in self.text_layouts.iter().enumerate() {
+                ctx.draw_text(
+                    text_layout,
+                    Point::new(
+                        10.0,
+                        10.0 + i as f64 * line_height
+                            + text_layout.y_offset(line_height),
+                    ),
+                );
+            }
+        });
+    }
+}
+
+/// The families of the fonts installed on the system, where the text backend
+/// looks fonts up
+static INSTALLED_FONT_FAMILIES: Lazy<Vec<String>> = Lazy::new(|| {
+    font_kit::source::SystemSource::new()
+        .all_families()
+        .map(|families| families.into_iter().sorted().dedup().collect())
+        .unwrap_or_default()
+});
+
+/// The installed font families matching what's typed in a font family input,
+/// all of them if it doesn't narrow them down
+fn font_family_options(input: &str) -> Vec<&'static str> {
+    let input = input.trim().to_lowercase();
+    let families = || INSTALLED_FONT_FAMILIES.iter().map(|s| s.as_str());
+    if input.is_empty() || families().any(|family| family.to_lowercase() == input) {
+        return families().collect();
+    }
+    let matched: Vec<&str> = families()
+        .filter(|family| family.to_lowercase().contains(&input))
+        .collect();
+    if matched.is_empty() {
+        families().collect()
+    } else {
+        matched
+    }
+}
+
+struct LapceSettingsItemKeypress {
+    input: String,
+    cursor: usize,
+}
+
+struct LapceSettingsItem {
+    kind: String,
+    name: String,
+    desc: String,
+    value: serde_json::Value,
+    padding: f64,
+    checkbox_width: f64,
+    input_max_width: f64,
+    width: f64,
+    cursor: usize,
+    input: String,
+    last_idle_timer: TimerToken,
+
+    name_text: Option<PietTextLayout>,
+    name_rect: Rect,
+    /// Whether the mouse is over the name, which tells when the setting was
+    /// last changed in a tooltip
+    name_hovered: bool,
+    desc_text: Option<PietTextLayout>,
+    /// Whether a long description is shown in full
+    expanded: bool,
+    more_text: Option<PietTextLayout>,
+    more_rect: Rect,
+    value_text: Option<Option<PietTextLayout>>,
+    error_text: Option<(Point, PietTextLayout)>,
+    /// The link that rounds a fractional input of an integer setting, with
+    /// the whole number it rounds to
+    round_link: Option<(Rect, PietTextLayout, i64)>,
+    /// Whether the text of a focused numeric input is a number the setting
+    /// takes, shown as it's typed
+    status_text: Option<(Point, PietTextLayout)>,
+    /// What a setting with environment variables expands to, below the
+    /// template that's typed
+    expanded_text: Option<(Point, PietTextLayout)>,
+    /// The size of a long string as it's typed, below its input
+    size_text: Option<(Point, PietTextLayout)>,
+    /// The warning that the workspace overrides the setting, and the link to
+    /// edit the workspace value
+    workspace_override: Option<(Point, PietTextLayout, Rect, PietTextLayout)>,
+    /// The hint that the setting differs from the setting it usually follows,
+    /// and the link to follow it again
+    divergence_note: Option<(Point, PietTextLayout, Rect, PietTextLayout)>,
+    /// The hint that the setting differs from the reference settings file,
+    /// the link aligning it to the reference and the reference value
+    reference_note: Option<(
+        Point,
+        PietTextLayout,
+        Rect,
+        PietTextLayout,
+        serde_json::Value,
+    )>,
+    /// The unit the value is measured in, it isn't part of the stored value
+    unit: Option<&'static str>,
+    /// An example of the string the setting expects, shown muted below the
+    /// description
+    example: Option<&'static str>,
+    example_text: Option<PietTextLayout>,
+    unit_text: Option<(Point, PietTextLayout)>,
+    /// Whether the setting doesn't apply because a setting it depends on is off
+    disabled: bool,
+    dependency_note: Option<(Point, PietTextLayout)>,
+    /// Whether the workspace policy enforces the setting, it can't be edited
+    locked: bool,
+    lock_note: Option<(Rect, Point, PietTextLayout)>,
+    /// The key of the setting replacing a deprecated setting, "" if nothing
+    /// does, and the warning pointing to it
+    deprecated: Option<&'static str>,
+    deprecation_note: Option<(Rect, Point, PietTextLayout)>,
+    /// Whether the value is a boolean which can be unset, it cycles through
+    /// unset, on and off
+    nullable: bool,
+    /// Whether the value is a string too long to be edited inline
+    large_value: bool,
+    /// The start of a large value, and the link to edit it in the settings file
+    large_value_text: Option<(Point, PietTextLayout, Rect, PietTextLayout)>,
+    /// Whether the installed fonts are listed below a font family input
+    fonts_open: bool,
+    fonts_link: Option<(Rect, PietTextLayout)>,
+    /// The listed fonts, each drawn in its own font, and the input text they
+    /// were filtered with
+    font_options: Vec<(Rect, String, PietTextLayout)>,
+    fonts_filter: String,
+    fonts_more_text: Option<(Point, PietTextLayout)>,
+    /// Whether the available themes are listed below the color theme input
+    themes_open: bool,
+    themes_link: Option<(Rect, PietTextLayout)>,
+    theme_options: Vec<(Rect, String, PietTextLayout)>,
+    /// The index of the listed theme being previewed, with the theme to go
+    /// back to if the list is closed without picking one
+    theme_preview: Option<(usize, String)>,
+    /// The link that shows the value as JSON in an input of its own
+    json_link: Option<(Rect, PietTextLayout)>,
+    /// The link that copies the value to the linked setting, with the
+    /// (table, key) of that setting
+    apply_link: Option<(Rect, PietTextLayout, &'static str, &'static str)>,
+    /// The default value of the setting, and the tooltip of the reset link
+    /// telling it
+    default: Option<(serde_json::Value, String)>,
+    /// The link resetting the setting to its default
+    reset_link: Option<(Rect, PietTextLayout)>,
+    reset_hovered: bool,
+    json_input: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    json_error_text: Option<(Point, PietTextLayout)>,
+    /// Whether the value is a map of strings, edited as a table of entries
+    map: bool,
+    /// The id of each entry, naming its docs, with its key and value inputs
+    /// and the rect of its remove link
+    map_rows: Vec<MapRow>,
+    /// The (key, value) of the entries as they were last stored
+    map_entries: Vec<(String, String)>,
+    map_next_id: usize,
+    map_add_link: Option<(Rect, PietTextLayout)>,
+    input_widget: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+}
+
+/// An entry of a map setting in the settings panel
+struct MapRow {
+    id: usize,
+    key_input: WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    value_input: WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    remove_rect: Rect,
+}
+
+impl LapceSettingsItem {
+    /// The amount of time to wait for the next key press before storing settings.
+    const SAVE_DELAY: Duration = Duration::from_millis(500);
+    /// The number of description lines shown until the description is expanded
+    const COLLAPSED_DESC_LINES: usize = 2;
+    /// String values longer than this, in bytes, aren't laid out or edited in
+    /// the settings, as that would block the UI
+    const LARGE_VALUE_LEN: usize = 10_000;
+    /// String inputs at least this long, in characters, tell the size of
+    /// their text below them
+    const SIZE_READOUT_LEN: usize = 80;
+    /// The number of characters of a large value that are shown
+    const LARGE_VALUE_PREVIEW_LEN: usize = 80;
+    /// The settings that name a font family, and can be picked from the
+    /// installed fonts
+    const FONT_FAMILY_SETTINGS: [(&'static str, &'static str); 2] =
+        [("ui", "font-family"), ("editor", "font-family")];
+    /// The settings whose environment variables are expanded when they're
+    /// read
+    const ENV_EXPANDED_SETTINGS: [(&'static str, &'static str); 3] = [
+        ("terminal", "shell"),
+        ("lapce", "settings-mirror"),
+        ("lapce", "reference-settings"),
+    ];
+    /// The number of installed fonts listed at once
+    const FONT_OPTIONS_LIMIT: usize = 30;
+    /// The narrowest width text is wrapped to in a narrow panel
+    const MIN_WRAP_WIDTH: f64 = 60.0;
+
+    pub fn new(
+        data: &mut LapceTabData,
+        kind: String,
+        key: String,
+        desc: String,
+        value: serde_json::Value,
+        expanded: bool,
+        event_sink: ExtEventSink,
+    ) -> Self {
+        let large_value = matches!(
+            &value,
+            serde_json::Value::String(s) if s.len() > Self::LARGE_VALUE_LEN
+        );
+        let input = match &value {
+            serde_json::Value::String(_) if large_value => None,
+            serde_json::Value::Number(_) | serde_json::Value::String(_) => Some((
+                settings_value_text(&SettingsValueKind::of(&value), &value),
+                SettingsValueKind::of(&value),
+            )),
+            serde_json::Value::Array(_)
+            | serde_json::Value::Object(_)
+            | serde_json::Value::Bool(_)
+            | serde_json::Value::Null => None,
+        };
+        let map = matches!(
+            &value,
+            serde_json::Value::Object(map) if map.values().all(|v| v.is_string())
+        );
+        let map_sink = event_sink.clone();
+        let input_widget = input.map(|(input, value_kind)| {
+            let content = BufferContent::SettingsValue(
+                format!("{kind}.{key}"),
+                value_kind,
+                kind.clone(),
+                key.clone(),
+            );
+            Self::new_input(data, content, &input, event_sink)
+        });
+        let unit = Config::setting_unit(&kind, &key);
+        let default = Config::default_setting_value(&kind, &key).map(|default| {
+            let tooltip = reset_tooltip_text(&default, unit);
+            (default, tooltip)
+        });
+        let example = Config::setting_example(&kind, &key);
+        let deprecated = Config::deprecated_setting(&kind, &key);
+        let nullable = Config::nullable_bool_setting(&kind, &key)
+            && (value.is_boolean() || value.is_null());
+        let mut item = Self {
+            kind,
+            name: key,
+            desc,
+            value,
+            padding: 10.0,
+            width: 0.0,
+            checkbox_width: 20.0,
+            input_max_width: 500.0,
+            cursor: 0,
+            input: "".to_string(),
+            last_idle_timer: TimerToken::INVALID,
+
+            name_text: None,
+            name_rect: Rect::ZERO,
+            name_hovered: false,
+            desc_text: None,
+            expanded,
+            more_text: None,
+            more_rect: Rect::ZERO,
+            value_text: None,
+            error_text: None,
+            round_link: None,
+            status_text: None,
+            expanded_text: None,
+            size_text: None,
+            workspace_override: None,
+            divergence_note: None,
+            reference_note: None,
+            unit,
+            example,
+            example_text: None,
+            unit_text: None,
+            disabled: false,
+            dependency_note: None,
+            locked: false,
+            deprecated,
+            deprecation_note: None,
+            lock_note: None,
+            nullable,
+            large_value,
+            large_value_text: None,
+            fonts_open: false,
+            fonts_link: None,
+            font_options: Vec::new(),
+            fonts_filter: String::new(),
+            fonts_more_text: None,
+            themes_open: false,
+            themes_link: None,
+            theme_options: Vec::new(),
+            theme_preview: None,
+            json_link: None,
+            apply_link: None,
+            default,
+            reset_link: None,
+            reset_hovered: false,
+            json_input: None,
+            json_error_text: None,
+            map,
+            map_rows: Vec::new(),
+            map_entries: Vec::new(),
+            map_next_id: 0,
+            map_add_link: None,
+            input_widget,
+        };
+        if let serde_json::Value::Object(entries) = &item.value {
+            if item.map {
+                let entries: Vec<(String, String)> = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        (key.to_string(), value.as_str().unwrap_or("").to_string())
+                    })
+                    .collect();
+                for (key, value) in entries.iter() {
+                    item.add_map_row(data, key, value, map_sink.clone());
+                }
+                item.map_entries = entries;
+            }
+        }
+        item
+    }
+
+    /// The name of the doc of the key or the value of a map entry
+    fn map_doc_name(&self, id: usize, part: &str) -> String {
+        format!("{}.{}.map.{id}.{part}", self.kind, self.name)
+    }
+
+    fn add_map_row(
+        &mut self,
+        data: &mut LapceTabData,
+        key: &str,
+        value: &str,
+        event_sink: ExtEventSink,
+    ) {
+        let id = self.map_next_id;
+        self.map_next_id += 1;
+        let [key_input, value_input] =
+            [("key", key), ("value", value)].map(|(part, text)| {
+                let content = BufferContent::SettingsValue(
+                    self.map_doc_name(id, part),
+                    SettingsValueKind::MapEntry,
+                    self.kind.clone(),
+                    self.name.clone(),
+                );
+                Self::new_input(data, content, text, event_sink.clone())
+            });
+        self.map_rows.push(MapRow {
+            id,
+            key_input,
+            value_input,
+            remove_rect: Rect::ZERO,
+        });
+    }
+
+    fn remove_map_row(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        index: usize,
+    ) {
+        let row = self.map_rows.remove(index);
+        for part in ["key", "value"] {
+            data.main_split
+                .value_docs
+                .remove(&self.map_doc_name(row.id, part));
+        }
+        ctx.children_changed();
+        self.store_map(ctx, data);
+    }
+
+    /// The (key, value) of the map entries, as they're typed
+    fn map_texts(&self, data: &LapceTabData) -> Vec<(String, String)> {
+        let text = |id: usize, part: &str| {
+            data.main_split
+                .value_docs
+                .get(&self.map_doc_name(id, part))
+                .map(|doc| doc.buffer().to_string())
+                .unwrap_or_default()
+        };
+        self.map_rows
+            .iter()
+            .map(|row| (text(row.id, "key"), text(row.id, "value")))
+            .collect()
+    }
+
+    /// Store the pending change after `SAVE_DELAY`, unless it waits for the
+    /// setting to lose focus
+    fn schedule_save(&mut self, ctx: &mut EventCtx, data: &LapceTabData) {
+        if !data.config.ui.save_settings_on_focus_loss() {
+            self.last_idle_timer = ctx.request_timer(Self::SAVE_DELAY, None);
+        }
+    }
+
+    /// Store the map the entries make after `SAVE_DELAY`, or show why they
+    /// can't be stored
+    fn store_map(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        let entries = self.map_texts(data);
+        let name = self.value_doc_name();
+        let settings = Arc::make_mut(&mut data.settings);
+        match settings_map_value(&entries) {
+            Ok(value) => {
+                settings.value_errors.remove(&name);
+                settings
+                    .pending_changes
+                    .insert(name, (self.kind.clone(), self.name.clone(), value));
+                self.schedule_save(ctx, data);
+            }
+            Err(err) => {
+                settings.pending_changes.remove(&name);
+                settings.value_errors.insert(name, err);
+            }
+        }
+        self.map_entries = entries;
+        ctx.request_layout();
+    }
+
+    /// The input editing a settings value doc with the content
+    fn new_input(
+        data: &mut LapceTabData,
+        content: BufferContent,
+        text: &str,
+        event_sink: ExtEventSink,
+    ) -> WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>> {
+        let name = match &content {
+            BufferContent::SettingsValue(name, ..) => name.clone(),
+            _ => unreachable!(),
+        };
+        let mut doc =
+            Document::new(content.clone(), data.id, event_sink, data.proxy.clone());
+        doc.reload(Rope::from(text), true);
+        data.main_split.value_docs.insert(name, Arc::new(doc));
+        let editor = LapceEditorData::new(None, None, None, content, &data.config);
+        let view_id = editor.view_id;
+        let input = LapceEditorView::new(editor.view_id, editor.editor_id, None)
+            .hide_header()
+            .hide_gutter()
+            .padding((5.0, 0.0, 50.0, 0.0));
+        data.main_split.editors.insert(view_id, Arc::new(editor));
+        WidgetPod::new(input.boxed())
+    }
+
+    fn value_doc_name(&self) -> String {
+        format!("{}.{}", self.kind, self.name)
+    }
+
+    /// The name of the doc the value is edited as JSON in
+    fn json_doc_name(&self) -> String {
+        format!("{}.{}.json", self.kind, self.name)
+    }
+
+    /// Show or hide the JSON input, it starts with the stored value each
+    /// time it's shown
+    fn toggle_json(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        let name = self.json_doc_name();
+        if self.json_input.take().is_some() {
+            data.main_split.value_docs.remove(&name);
+            if data.settings.value_errors.contains_key(&name) {
+                Arc::make_mut(&mut data.settings).value_errors.remove(&name);
+            }
+        } else {
+            let value = data
+                .config
+                .setting_value(&self.kind, &self.name)
+                .unwrap_or_else(|| self.value.clone());
+            let content = BufferContent::SettingsValue(
+                name,
+                SettingsValueKind::Json,
+                self.kind.clone(),
+                self.name.clone(),
+            );
+            self.json_input = Some(Self::new_input(
+                data,
+                content,
+                &settings_value_text(&SettingsValueKind::Json, &value),
+                ctx.get_external_handle(),
+            ));
+        }
+        ctx.children_changed();
+        ctx.request_layout();
+    }
+
+    /// The text in the input of the setting, as it's being edited
+    fn value_doc_text(&self, data: &LapceTabData) -> String {
+        data.main_split
+            .value_docs
+            .get(&self.value_doc_name())
+            .map(|doc| doc.buffer().to_string())
+            .unwrap_or_default()
+    }
+
+    fn expands_env_vars(&self) -> bool {
+        Self::ENV_EXPANDED_SETTINGS
+            .iter()
+            .any(|(kind, name)| kind == &self.kind && name == &self.name)
+    }
+
+    fn lists_fonts(&self) -> bool {
+        Self::FONT_FAMILY_SETTINGS
+            .iter()
+            .any(|(kind, name)| kind == &self.kind && name == &self.name)
+    }
+
+    fn lists_themes(&self) -> bool {
+        self.kind == "lapce" && self.name == "color-theme"
+    }
+
+    /// The names of the available themes, in the order they're listed
+    fn theme_names(data: &LapceTabData) -> Vec<String> {
+        data.config
+            .available_themes
+            .values()
+            .map(|(name, _)| name.clone())
+            .sorted()
+            .collect()
+    }
+
+    /// The listed theme that's highlighted, the previewed one or else the
+    /// applied one
+    fn highlighted_theme(&self, data: &LapceTabData) -> Option<usize> {
+        match self.theme_preview.as_ref() {
+            Some((index, _)) => Some(*index),
+            None => self.theme_options.iter().position(|(_, name, _)| {
+                name.eq_ignore_ascii_case(&data.config.lapce.color_theme)
+            }),
+        }
+    }
+
+    /// Apply the listed theme at `index` for now, remembering the theme from
+    /// before the first preview
+    fn preview_theme(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        index: usize,
+    ) {
+        let theme = match self.theme_options.get(index) {
+            Some((_, theme, _)) => theme.clone(),
+            None => return,
+        };
+        let before = match self.theme_preview.take() {
+            Some((_, before)) => before,
+            None => data.config.lapce.color_theme.clone(),
+        };
+        self.theme_preview = Some((index, before));
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::SetTheme(theme, true),
+            Target::Auto,
+        ));
+        ctx.request_paint();
+    }
+
+    /// Close the theme list, going back to the theme from before the
+    /// previews
+    fn close_themes(&mut self, ctx: &mut EventCtx) {
+        self.themes_open = false;
+        if let Some((_, before)) = self.theme_preview.take() {
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::SetTheme(before, true),
+                Target::Auto,
+            ));
+        }
+        ctx.request_layout();
+    }
+
+    /// Put the picked theme in the input, it's stored after `SAVE_DELAY`
+    /// and stays applied meanwhile
+    fn pick_theme(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        index: usize,
+    ) {
+        let theme = match self.theme_options.get(index) {
+            Some((_, theme, _)) => theme.clone(),
+            None => return,
+        };
+        if self.theme_preview.as_ref().map(|(i, _)| *i) != Some(index) {
+            self.preview_theme(ctx, data, index);
+        }
+        self.theme_preview = None;
+        self.themes_open = false;
+        self.replace_input(ctx, data, &theme, serde_json::json!(theme));
+    }
+
+    /// The theme list works like a dropdown from the keyboard while the
+    /// input has the focus: Down opens it, the arrows preview the themes,
+    /// Enter picks one and Escape goes back to the theme from before
+    fn theme_key_down(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        key_event: &KeyEvent,
+    ) -> bool {
+        if !self.themes_open {
+            if key_event.key == KbKey::ArrowDown {
+                self.themes_open = true;
+                ctx.request_layout();
+                return true;
+            }
+            return false;
+        }
+        let len = self.theme_options.len();
+        let highlighted = self.highlighted_theme(data);
+        match &key_event.key {
+            KbKey::ArrowDown | KbKey::ArrowUp if len > 0 => {
+                let index = match (highlighted, key_event.key == KbKey::ArrowDown) {
+                    (Some(i), true) => (i + 1) % len,
+                    (Some(i), false) => (i + len - 1) % len,
+                    (None, true) => 0,
+                    (None, false) => len - 1,
+                };
+                self.preview_theme(ctx, data, index);
+            }
+            KbKey::Enter => match highlighted {
+                Some(index) => self.pick_theme(ctx, data, index),
+                None => self.close_themes(ctx),
+            },
+            KbKey::Escape => self.close_themes(ctx),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Put the picked font family in the input, it's stored after
+    /// `SAVE_DELAY` like a toggle
+    fn pick_font(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        family: String,
+    ) {
+        self.replace_input(ctx, data, &family, serde_json::json!(family));
+        self.fonts_open = false;
+    }
+
+    /// Replace the text of the input with `text`, storing `value` after
+    /// `SAVE_DELAY`
+    fn replace_input(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        text: &str,
+        value: serde_json::Value,
+    ) {
+        let name = self.value_doc_name();
+        if let Some(doc) = data.main_split.value_docs.get_mut(&name) {
+            Arc::make_mut(doc).reload(Rope::from(text), true);
+        }
+        let settings = Arc::make_mut(&mut data.settings);
+        settings.value_errors.remove(&name);
+        settings
+            .pending_changes
+            .insert(name, (self.kind.clone(), self.name.clone(), value));
+        self.schedule_save(ctx, data);
+        ctx.request_layout();
+    }
+
+    /// The whole number the input rounds to, if it's a fraction typed into
+    /// an integer setting
+    fn rounded_input(&self, data: &LapceTabData) -> Option<i64> {
+        let doc = data.main_split.value_docs.get(&self.value_doc_name())?;
+        match doc.content() {
+            BufferContent::SettingsValue(_, kind, _, _) => {
+                rounded_settings_value(kind, &doc.buffer().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// The color of the name and description, dimmed when the setting
+    /// doesn't apply
+    fn text_color<'a>(&self, data: &'a LapceTabData) -> &'a Color {
+        data.config
+            .get_color_unchecked(if self.disabled || self.locked {
+                LapceTheme::EDITOR_DIM
+            } else {
+                LapceTheme::EDITOR_FOREGROUND
+            })
+    }
+
+    pub fn name(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> &PietTextLayout {
+        if self.name_text.is_none() {
+            let name = setting_display_name(
+                &self.kind,
+                &self.name,
+                &data.settings.preferences,
+            );
+            let matches =
+                settings_filter_matches(&name, &data.settings.filter_pattern);
+            let mut text_layout = text
+                .new_text_layout(name)
+                .font(
+                    data.config.ui.font_family(),
+                    (data.config.ui.font_size() + 1) as f64,
+                )
+                .text_color(self.text_color(data).clone())
+                .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
+                // Leaving room for the type badge
+                .max_width(self.wrap_width(80.0))
+                .set_line_height(1.5);
+            for range in matches {
+                text_layout = text_layout.range_attribute(
+                    range,
+                    TextAttribute::TextColor(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_FOCUS)
+                            .clone(),
+                    ),
+                );
+            }
+            let text_layout = text_layout.build().unwrap();
+            self.name_text = Some(text_layout);
+        }
+
+        self.name_text.as_ref().unwrap()
+    }
+
+    pub fn desc(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> &PietTextLayout {
+        if self.desc_text.is_none() {
+            let max_width = self.desc_wrap_width();
+            let matches =
+                settings_filter_matches(&self.desc, &data.settings.filter_pattern);
+            let mut text_layout = text
+                .new_text_layout(self.desc.clone())
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(self.text_color(data).clone())
+                .max_width(max_width)
+                .set_line_height(1.5);
+            for range in matches {
+                text_layout = text_layout
+                    .range_attribute(
+                        range.clone(),
+                        TextAttribute::TextColor(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_FOCUS)
+                                .clone(),
+                        ),
+                    )
+                    .range_attribute(range, TextAttribute::Weight(FontWeight::BOLD));
+            }
+            let text_layout = text_layout.build().unwrap();
+            self.desc_text = Some(text_layout);
+        }
+
+        self.desc_text.as_ref().unwrap()
+    }
+
+    /// The width left for text after `reserved`, never so narrow that the
+    /// text stops wrapping, as a layout without a positive max width isn't
+    /// wrapped at all
+    fn wrap_width(&self, reserved: f64) -> f64 {
+        (self.width - reserved).max(Self::MIN_WRAP_WIDTH)
+    }
+
+    /// The width the description wraps to, next to the checkbox of a boolean
+    fn desc_wrap_width(&self) -> f64 {
+        if self.is_checkbox() {
+            self.wrap_width(self.checkbox_width + 30.0)
+        } else {
+            self.wrap_width(30.0)
+        }
+    }
+
+    /// The height of the visible part of the description, and whether the
+    /// description is too long to be shown in full when collapsed
+    fn visible_desc_height(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> (f64, bool) {
+        let expanded = self.expanded;
+        let desc = self.desc(text, data);
+        if desc.line_count() <= Self::COLLAPSED_DESC_LINES {
+            return (desc.size().height, false);
+        }
+        let height = if expanded {
+            desc.size().height
+        } else {
+            desc.line_metric(Self::COLLAPSED_DESC_LINES - 1)
+                .map(|line| line.y_offset + line.height)
+                .unwrap_or_else(|| desc.size().height)
+        };
+        (height, true)
+    }
+
+    /// The "more" or "less" link that toggles a long description
+    fn more(&mut self, text: &mut PietText, data: &LapceTabData) -> &PietTextLayout {
+        if self.more_text.is_none() {
+            let text_layout = text
+                .new_text_layout(if self.expanded { "less" } else { "more" })
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            self.more_text = Some(text_layout);
+        }
+
+        self.more_text.as_ref().unwrap()
+    }
+
+    /// The "e.g. …" example below the description, if the setting has one
+    fn example(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> Option<&PietTextLayout> {
+        let example = self.example?;
+        if self.example_text.is_none() {
+            let text_layout = text
+                .new_text_layout(format!("e.g. {example}"))
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .max_width(self.desc_wrap_width())
+                .build()
+                .unwrap();
+            self.example_text = Some(text_layout);
+        }
+
+        self.example_text.as_ref()
+    }
+
+    /// The height of the description and its "more" link, without the
+    /// example below them
+    fn desc_text_height(&mut self, text: &mut PietText, data: &LapceTabData) -> f64 {
+        let (height, truncated) = self.visible_desc_height(text, data);
+        if truncated {
+            height + self.more(text, data).size().height
+        } else {
+            height
+        }
+    }
+
+    /// The height of the description including its "more" link and example
+    fn desc_block_height(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> f64 {
+        let height = self.desc_text_height(text, data);
+        match self.example(text, data) {
+            Some(example) => height + example.size().height,
+            None => height,
+        }
+    }
+
+    pub fn value(
+        &mut self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> Option<&PietTextLayout> {
+        if self.value_text.is_none() {
+            let value = match &self.value {
+                serde_json::Value::String(_) if self.large_value => None,
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                serde_json::Value::String(s) => Some(s.to_string()),
+                serde_json::Value::Array(_)
+                | serde_json::Value::Object(_)
+                | serde_json::Value::Bool(_)
+                | serde_json::Value::Null => None,
+            };
+            let text_layout = value.map(|value| {
+                self.input = value.to_string();
+                text.new_text_layout(value)
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap()
+            });
+            self.value_text = Some(text_layout);
+        }
+
+        self.value_text.as_ref().unwrap().as_ref()
+    }
+
+    /// Open the workspace settings file at the line that sets this setting
+    fn edit_workspace_value(&self, ctx: &mut EventCtx, data: &LapceTabData) {
+        if let Some(path) = data.config.workspace_settings() {
+            self.open_settings_file(ctx, data, path.to_path_buf());
+        }
+    }
+
+    /// Open a settings file at the line that sets this setting, or at the top
+    /// if the file doesn't set it
+    fn open_settings_file(
+        &self,
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        path: std::path::PathBuf,
+    ) {
+        let line = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| settings_key_line(&text, &self.kind, &self.name));
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::JumpToLineLocation(
+                None,
+                EditorLocation {
+                    path,
+                    position: line.map(|line| lapce_data::editor::Line(line + 1)),
+                    scroll_offset: None,
+                    history: None,
+                },
+            ),
+            Target::Widget(data.id),
+        ));
+    }
+
+    /// Whether the setting is edited with a checkbox
+    fn is_checkbox(&self) -> bool {
+        self.value.is_boolean() || self.nullable
+    }
+
+    /// Flip a boolean setting, a nullable one goes from unset to on to off.
+    /// It's stored after `SAVE_DELAY` unless the pending settings are flushed
+    /// before that
+    fn toggle(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        if self.locked {
+            return;
+        }
+        self.value = match &self.value {
+            serde_json::Value::Bool(false) if self.nullable => {
+                serde_json::Value::Null
+            }
+            serde_json::Value::Bool(checked) => serde_json::json!(!checked),
+            serde_json::Value::Null if self.nullable => serde_json::json!(true),
+            _ => return,
+        };
+        Arc::make_mut(&mut data.settings).pending_changes.insert(
+            self.value_doc_name(),
+            (self.kind.clone(), self.name.clone(), self.value.clone()),
+        );
+        self.schedule_save(ctx, data);
+        ctx.request_paint();
+    }
+
+    /// The value of the setting, a toggle that isn't stored yet counts as the
+    /// current value
+    fn current_value(&self, data: &LapceTabData) -> Option<serde_json::Value> {
+        match data.settings.pending_changes.get(&self.value_doc_name()) {
+            Some((_, _, value)) => Some(value.clone()),
+            None => data.config.setting_value(&self.kind, &self.name),
+        }
+    }
+
+    /// The menu resetting the setting and copying its identifier or value,
+    /// the reset is disabled while it's at its default
+    fn show_context_menu(
+        &self,
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        pos: Point,
+    ) {
+        let current = self.current_value(data);
+        let resettable = !self.locked
+            && self
+                .default
+                .as_ref()
+                .map(|(default, _)| current.as_ref() != Some(default))
+                .unwrap_or(false);
+        let value = match current {
+            Some(serde_json::Value::String(s)) => s,
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        let item = |desc: &str, command, data, enabled| {
+            MenuKind::Item(MenuItem {
+                desc: Some(desc.to_string()),
+                command: LapceCommand {
+                    kind: CommandKind::Workbench(command),
+                    data: Some(data),
+                },
+                enabled,
+            })
+        };
+        let items = vec![
+            item(
+                "Reset to Default",
+                LapceWorkbenchCommand::ResetSetting,
+                serde_json::json!((self.kind, self.name)),
+                resettable,
+            ),
+            MenuKind::Separator,
+            item(
+                "Copy Identifier",
+                LapceWorkbenchCommand::CopySettingText,
+                serde_json::json!(format!("{}.{}", self.kind, self.name)),
+                true,
+            ),
+            item(
+                "Copy Value",
+                LapceWorkbenchCommand::CopySettingText,
+                serde_json::json!(value),
+                true,
+            ),
+        ];
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowMenu(ctx.to_window(pos), Arc::new(items)),
+            Target::Widget(data.id),
+        ));
+    }
+
+    /// Reset the setting to its default value, unless it already has it
+    fn reset(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        if self.locked {
+            return;
+        }
+        let default = match self.default.as_ref() {
+            Some((default, _)) => default.clone(),
+            None => return,
+        };
+        let name = self.value_doc_name();
+        if self.current_value(data).as_ref() == Some(&default) {
+            return;
+        }
+
+        if let Some(doc) = data.main_split.value_docs.get_mut(&name) {
+            let content = match &default {
+                serde_json::Value::String(s) => s.to_string(),
+                value => value.to_string(),
+            };
+            Arc::make_mut(doc).reload(Rope::from(content), true);
+        }
+        let settings = Arc::make_mut(&mut data.settings);
+        settings.pending_changes.remove(&name);
+        settings.value_errors.remove(&name);
+        if self.is_checkbox() {
+            self.value = default.clone();
+        }
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::UpdateSettingsFile(
+                self.kind.clone(),
+                self.name.clone(),
+                default,
+            ),
+            Target::Widget(data.id),
+        ));
+        ctx.request_layout();
+    }
+
+    /// The short name of the type of the value, shown next to the setting name
+    fn type_name(&self) -> &'static str {
+        match &self.value {
+            serde_json::Value::Bool(_) => "bool",
+            serde_json::Value::Null if self.nullable => "bool",
+            serde_json::Value::Null => "none",
+            serde_json::Value::Array(_) => "array",
+            serde_json::Value::Object(_) if self.map => "map",
+            serde_json::Value::Object(_) => "table",
+            value => match SettingsValueKind::of(value) {
+                SettingsValueKind::Integer => "int",
+                SettingsValueKind::Float => "float",
+                _ => "string",
+            },
+        }
+    }
+
+    /// Paint the type of the value in a small badge, vertically centered on
+    /// a line of `line_height` at `origin`
+    fn paint_type_badge(
+        &self,
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        origin: Point,
+        line_height: f64,
+    ) {
+        let text_layout = ctx
+            .text()
+            .new_text_layout(self.type_name())
+            .font(
+                data.config.ui.font_family(),
+                (data.config.ui.font_size() as f64 - 2.0).max(6.0),
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_size = text_layout.size();
+        let rect = Size::new(text_size.width + 8.0, text_size.height + 2.0)
+            .to_rect()
+            .with_origin(Point::new(
+                origin.x,
+                origin.y + ((line_height - text_size.height - 2.0) / 2.0).round(),
+            ));
+        ctx.stroke(
+            rect.inflate(-0.5, -0.5).to_rounded_rect(3.0),
+            data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+            1.0,
+        );
+        ctx.draw_text(&text_layout, rect.origin() + (4.0, 1.0));
+    }
+
+    /// Tell when the setting was last changed in a tooltip below its name
+    fn paint_change_tooltip(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let text = match data
+            .settings
+            .change_times
+            .get(&format!("{}.{}", self.kind, self.name))
+        {
+            Some(time) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let date = chrono::Local
+                    .timestamp_opt(*time as i64, 0)
+                    .single()
+                    .map(|date| format!(" ({})", date.format("%Y-%m-%d %H:%M")))
+                    .unwrap_or_default();
+                format!("Last changed {}{date}", settings_change_age(*time, now))
+            }
+            None => "Not changed in Lapce".to_string(),
+        };
+        Self::paint_tooltip(
+            ctx,
+            data,
+            &text,
+            Point::new(self.name_rect.x0, self.name_rect.y1 + 4.0),
+        );
+    }
+
+    /// Paint `text` in a tooltip box at `origin`
+    fn paint_tooltip(
+        ctx: &mut PaintCtx,
+        data: &LapceTabData,
+        text: &str,
+        origin: Point,
+    ) {
+        let text_layout = ctx
+            .text()
+            .new_text_layout(text.to_string())
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_size = text_layout.size();
+        let rect = Size::new(text_size.width + 16.0, text_size.height + 10.0)
+            .to_rect()
+            .with_origin(origin);
+        let shadow_width = data.config.ui.drop_shadow_width() as f64;
+        if shadow_width > 0.0 {
+            ctx.blurred_rect(
+                rect,
+                shadow_width,
+                data.config
+                    .get_color_unchecked(LapceTheme::LAPCE_DROPDOWN_SHADOW),
+            );
+        } else {
+            ctx.stroke(
+                rect.inflate(0.5, 0.5),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+        }
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::PANEL_BACKGROUND),
+        );
+        ctx.draw_text(&text_layout, rect.origin() + (8.0, 5.0));
+    }
+
+    fn clear_text_layout_cache(&mut self) {
+        self.name_text = None;
+        self.desc_text = None;
+        self.more_text = None;
+        self.example_text = None;
+        self.value_text = None;
+    }
+}
+
+impl KeyPressFocus for LapceSettingsItemKeypress {
+    fn get_mode(&self) -> Mode {
+        Mode::Insert
+    }
+
+    fn check_condition(&self, _condition: &str) -> bool {
+        false
+    }
+
+    fn receive_char(&mut self, _ctx: &mut EventCtx, c: &str) {
+        self.input.insert_str(self.cursor, c);
+        self.cursor += c.len();
+    }
+
+    fn run_command(
+        &mut self,
+        _ctx: &mut EventCtx,
+        command: &lapce_data::command::LapceCommand,
+        _count: Option<usize>,
+        _mods: Modifiers,
+        _env: &Env,
+    ) -> CommandExecuted {
+        match &command.kind {
+            CommandKind::Move(cmd) => match cmd {
+                MoveCommand::Right => {
+                    self.cursor += 1;
+                    if self.cursor > self.input.len() {
+                        self.cursor = self.input.len();
+                    }
+                }
+                MoveCommand::Left => {
+                    if self.cursor == 0 {
+                        return CommandExecuted::Yes;
+                    }
+                    self.cursor -= 1;
+                }
+                _ => return CommandExecuted::No,
+            },
+            CommandKind::Edit(EditCommand::DeleteForward) => {
+                if self.cursor == 0 {
+                    return CommandExecuted::Yes;
+                }
+                self.input.remove(self.cursor - 1);
+                self.cursor -= 1;
+            }
+            _ => return CommandExecuted::No,
+        }
+        CommandExecuted::Yes
+    }
+}
+
+impl Widget<LapceTabData> for LapceSettingsItem {
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LapceTabData,
+        env: &Env,
+    ) {
+        // The theme list keeps the keys it uses from the input
+        if let Event::KeyDown(key_event) = event {
+            if self.lists_themes()
+                && !self.locked
+                && ctx.has_focus()
+                && self.theme_key_down(ctx, data, key_event)
+            {
+                ctx.set_handled();
+                return;
+            }
+        }
+        // The whole item has the context menu, its input too
+        if let Event::MouseDown(mouse_event) = event {
+            if mouse_event.button.is_right() {
+                self.show_context_menu(ctx, data, mouse_event.pos);
+                ctx.set_handled();
+                return;
+            }
+        }
+        for input in self.input_widget.iter_mut().chain(self.json_input.as_mut()) {
+            match event {
+                Event::Wheel(_) => {}
+                // Edits of a locked setting are ignored
+                Event::MouseDown(_)
+                | Event::MouseUp(_)
+                | Event::KeyDown(_)
+                | Event::KeyUp(_)
+                    if self.locked => {}
+                _ => {
+                    input.event(ctx, event, data, env);
+                }
+            }
+        }
+        if !self.locked {
+            for row in self.map_rows.iter_mut() {
+                if !matches!(event, Event::Wheel(_)) {
+                    row.key_input.event(ctx, event, data, env);
+                    row.value_input.event(ctx, event, data, env);
+                }
+            }
+            if !self.map_rows.is_empty() && self.map_texts(data) != self.map_entries
+            {
+                self.store_map(ctx, data);
+            }
+        }
+        match event {
+            Event::MouseDown(mouse_event) => {
+                if let Some(index) = self
+                    .map_rows
+                    .iter()
+                    .position(|row| row.remove_rect.contains(mouse_event.pos))
+                {
+                    self.remove_map_row(ctx, data, index);
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some((link_rect, _)) = self.map_add_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        self.add_map_row(data, "", "", ctx.get_external_handle());
+                        ctx.children_changed();
+                        ctx.request_layout();
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _)) = self.json_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        self.toggle_json(ctx, data);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _)) = self.reset_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        self.reset(ctx, data);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _, table, key)) = self.apply_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        if let Some(value) =
+                            data.config.setting_value(&self.kind, &self.name)
+                        {
+                            ctx.submit_command(Command::new(
+                                LAPCE_UI_COMMAND,
+                                LapceUICommand::UpdateSettingsFile(
+                                    table.to_string(),
+                                    key.to_string(),
+                                    value,
+                                ),
+                                Target::Widget(data.id),
+                            ));
+                        }
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((_, _, link_rect, _)) = self.workspace_override.as_ref()
+                {
+                    if link_rect.contains(mouse_event.pos) {
+                        self.edit_workspace_value(ctx, data);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((_, _, link_rect, _)) = self.divergence_note.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        // Back at its default it follows the other setting
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::ResetSettingsFile(
+                                self.kind.clone(),
+                                self.name.clone(),
+                            ),
+                            Target::Widget(data.id),
+                        ));
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((_, _, link_rect, _, value)) =
+                    self.reference_note.as_ref()
+                {
+                    if link_rect.contains(mouse_event.pos) {
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::UpdateSettingsFile(
+                                self.kind.clone(),
+                                self.name.clone(),
+                                value.clone(),
+                            ),
+                            Target::Widget(data.id),
+                        ));
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((_, _, link_rect, _)) = self.large_value_text.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        if let Some(path) = Config::settings_file() {
+                            self.open_settings_file(ctx, data, path);
+                        }
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _, n)) = self.round_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        let n = *n;
+                        self.replace_input(
+                            ctx,
+                            data,
+                            &n.to_string(),
+                            serde_json::json!(n),
+                        );
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _)) = self.fonts_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        self.fonts_open = !self.fonts_open;
+                        ctx.request_layout();
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some((link_rect, _)) = self.themes_link.as_ref() {
+                    if link_rect.contains(mouse_event.pos) {
+                        if self.themes_open {
+                            self.close_themes(ctx);
+                        } else {
+                            self.themes_open = true;
+                            ctx.request_layout();
+                        }
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+                if let Some(index) = self
+                    .theme_options
+                    .iter()
+                    .position(|(rect, _, _)| rect.contains(mouse_event.pos))
+                {
+                    self.pick_theme(ctx, data, index);
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some((_, family, _)) = self
+                    .font_options
+                    .iter()
+                    .find(|(rect, _, _)| rect.contains(mouse_event.pos))
+                {
+                    let family = family.clone();
+                    self.pick_font(ctx, data, family);
+                    ctx.set_handled();
+                    return;
+                }
+                if self.more_rect.contains(mouse_event.pos) {
+                    self.expanded = !self.expanded;
+                    self.more_text = None;
+                    ctx.request_layout();
+                    ctx.set_handled();
+                    return;
+                }
+                // ctx.request_focus();
+                let input = self.input.clone();
+                if let Some(_text) = self.value(ctx.text(), data) {
+                    let text = ctx
+                        .text()
+                        .new_text_layout(input)
+                        .font(
+                            data.config.ui.font_family(),
+                            data.config.ui.font_size() as f64,
+                        )
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap();
+                    let mut height = self.name(ctx.text(), data).size().height;
+                    height += self.desc_block_height(ctx.text(), data);
+                    height += self.padding * 2.0 + self.padding;
+
+                    let rect = Size::new(
+                        ctx.size().width.min(self.input_max_width),
+                        text.size().height,
+                    )
+                    .to_rect()
+                    .with_origin(Point::new(0.0, height))
+                    .inflate(0.0, 8.0);
+                    if rect.contains(mouse_event.pos) {
+                        let pos = mouse_event.pos - (8.0, 0.0);
+                        let hit = text.hit_test_point(pos);
+                        self.cursor = hit.idx;
+                    }
+                } else if self.is_checkbox() {
+                    ctx.request_focus();
+                    let rect = Size::new(self.checkbox_width, self.checkbox_width)
+                        .to_rect()
+                        .with_origin(Point::new(
+                            0.0,
+                            self.name(ctx.text(), data).size().height
+                                + self.padding * 2.0
+                                + 4.0,
+                        ));
+                    if rect.contains(mouse_event.pos) {
+                        self.toggle(ctx, data);
+                    }
+                }
+            }
+            Event::KeyDown(key_event) if ctx.is_focused() => {
+                match &key_event.key {
+                    KbKey::Enter => {
+                        self.toggle(ctx, data);
+                    }
+                    KbKey::Character(c) if c == " " => {
+                        self.toggle(ctx, data);
+                    }
+                    KbKey::Tab if key_event.mods.shift() => ctx.focus_prev(),
+                    KbKey::Tab => ctx.focus_next(),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            Event::MouseMove(mouse_event) => {
+                // Hovering a listed theme previews it
+                if let Some(index) = self
+                    .theme_options
+                    .iter()
+                    .position(|(rect, _, _)| rect.contains(mouse_event.pos))
+                {
+                    if self.highlighted_theme(data) != Some(index) {
+                        self.preview_theme(ctx, data, index);
+                    }
+                }
+                let name_hovered = self.name_rect.contains(mouse_event.pos);
+                if name_hovered != self.name_hovered {
+                    self.name_hovered = name_hovered;
+                    ctx.request_paint();
+                }
+                let reset_hovered = self
+                    .reset_link
+                    .as_ref()
+                    .map(|(rect, _)| rect.contains(mouse_event.pos))
+                    .unwrap_or(false);
+                if reset_hovered != self.reset_hovered {
+                    self.reset_hovered = reset_hovered;
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if ctx.has_focus()
+                    && matches!(
+                        cmd.get(LAPCE_UI_COMMAND),
+                        Some(LapceUICommand::ResetFocusedSetting)
+                    ) =>
+            {
+                self.reset(ctx, data);
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if matches!(
+                    cmd.get(LAPCE_UI_COMMAND),
+                    Some(LapceUICommand::ResetSetting(table, key))
+                        if *table == self.kind && *key == self.name
+                ) =>
+            {
+                self.reset(ctx, data);
+                ctx.set_handled();
+            }
+            Event::Timer(token)
+                if *token == self.last_idle_timer
+                    && data
+                        .settings
+                        .pending_changes
+                        .contains_key(&self.value_doc_name()) =>
+            {
+                self.last_idle_timer = TimerToken::INVALID;
+                if let Some((parent, key, value)) = Arc::make_mut(&mut data.settings)
+                    .pending_changes
+                    .remove(&self.value_doc_name())
+                {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::UpdateSettingsFile(parent, key, value),
+                        Target::Widget(data.id),
+                    ));
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::FocusChanged(false)
+                if data.config.ui.save_settings_on_focus_loss() =>
+            {
+                ctx.submit_command(Command::new(
+                    LAPCE_COMMAND,
+                    LapceCommand {
+                        kind: CommandKind::Workbench(
+                            LapceWorkbenchCommand::FlushSettings,
+                        ),
+                        data: None,
+                    },
+                    Target::Auto,
+                ));
+                ctx.request_paint();
+            }
+            LifeCycle::HotChanged(hot) => {
+                if !hot {
+                    self.name_hovered = false;
+                    self.reset_hovered = false;
+                }
+                ctx.request_paint();
+            }
+            LifeCycle::FocusChanged(_) => {
+                ctx.request_paint();
+            }
+            LifeCycle::BuildFocusChain if self.is_checkbox() => {
+                ctx.register_for_focus();
+            }
+            _ => {}
+        }
+        for input in self.input_widget.iter_mut().chain(self.json_input.as_mut()) {
+            input.lifecycle(ctx, event, data, env);
+        }
+        for row in self.map_rows.iter_mut() {
+            row.key_input.lifecycle(ctx, event, data, env);
+            row.value_input.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        env: &Env,
+    ) {
+        if data.config.id != old_data.config.id
+            || data.settings.filter_pattern != old_data.settings.filter_pattern
+        {
+            self.clear_text_layout_cache();
+        }
+        // The flush stored the pending change already
+        if data.settings.flush_id != old_data.settings.flush_id {
+            self.last_idle_timer = TimerToken::INVALID;
+        }
+        if data.settings.preferences.show_raw_keys
+            != old_data.settings.preferences.show_raw_keys
+        {
+            self.clear_text_layout_cache();
+            ctx.request_layout();
+        }
+        // The setting a setting follows may have changed, and the listed
+        // themes are drawn in the colors of the theme applied
+        if data.config.id != old_data.config.id
+            && (Config::shared_setting(&self.kind, &self.name).is_some()
+                || self.themes_open)
+        {
+            ctx.request_layout();
+        }
+        for name in [self.value_doc_name(), self.json_doc_name()] {
+            if data.settings.value_errors.get(&name)
+                != old_data.settings.value_errors.get(&name)
+            {
+                ctx.request_layout();
+            }
+        }
+        // A toggle that couldn't be stored flips back
+        if data.settings.write_error != old_data.settings.write_error
+            && matches!(
+                data.settings.write_error.as_ref(),
+                Some((table, key, _)) if *table == self.kind && *key == self.name
+            )
+        {
+            if let Some(value) = data.config.setting_value(&self.kind, &self.name) {
+                self.value = value;
+            }
+            ctx.request_layout();
+        }
+        // The status under a numeric input follows the text while it's focused
+        if self.value.is_number() {
+            let name = self.value_doc_name();
+            if *data.focus != *old_data.focus
+                || data.main_split.value_docs.get(&name).map(Arc::as_ptr)
+                    != old_data.main_split.value_docs.get(&name).map(Arc::as_ptr)
+            {
+                ctx.request_layout();
+            }
+        }
+        // The listed fonts narrow down as a family is typed
+        if self.fonts_open && self.value_doc_text(data) != self.fonts_filter {
+            ctx.request_layout();
+        }
+        if unmet_settings_dependency(&data.config, &self.kind, &self.name).is_some()
+            != self.disabled
+            || data.config.locked_by_policy(&self.kind, &self.name) != self.locked
+        {
+            ctx.request_layout();
+        }
+        for input in self.input_widget.iter_mut().chain(self.json_input.as_mut()) {
+            input.update(ctx, data, env);
+        }
+        for row in self.map_rows.iter_mut() {
+            row.key_input.update(ctx, data, env);
+            row.value_input.update(ctx, data, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        env: &Env,
+    ) -> Size {
+        let width = bc.max().width;
+        if width != self.width {
+            self.width = width;
+            self.clear_text_layout_cache();
+        }
+        let dependency =
+            unmet_settings_dependency(&data.config, &self.kind, &self.name);
+        if dependency.is_some() != self.disabled {
+            self.disabled = dependency.is_some();
+            self.clear_text_layout_cache();
+        }
+        let locked = data.config.locked_by_policy(&self.kind, &self.name);
+        if locked != self.locked {
+            self.locked = locked;
+            self.clear_text_layout_cache();
+        }
+        let text = ctx.text();
+        let name = self.name(text, data).size();
+        let desc_height = self.desc_block_height(text, data);
+        let (visible_desc_height, truncated) = self.visible_desc_height(text, data);
+        self.more_rect = if truncated {
+            let x = if self.is_checkbox() {
+                self.checkbox_width
+            } else {
+                0.0
+            };
+            self.more(text, data)
+                .size()
+                .to_rect()
+                .with_origin(Point::new(
+                    x,
+                    name.height + self.padding * 2.0 + visible_desc_height,
+                ))
+        } else {
+            Rect::ZERO
+        };
+        let mut height = name.height + desc_height + (self.padding * 3.0);
+        height = height.round();
+
+        self.unit_text = None;
+        if let Some(input) = self.input_widget.as_mut() {
+            let input_size = input.layout(ctx, bc, data, env);
+            input.set_origin(ctx, data, env, Point::new(0.0, height));
+
+            // The unit goes in the right padding of the input
+            self.unit_text = self.unit.map(|unit| {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(unit)
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let origin = Point::new(
+                    input_size.width - 45.0,
+                    height + (input_size.height - text_layout.size().height) / 2.0,
+                );
+                (origin, text_layout)
+            });
+        }
+
+        let text = ctx.text();
+        let value = self
+            .value(text, data)
+            .map(|v| v.size().height)
+            .unwrap_or(0.0);
+        if value > 0.0 {
+            height += value + self.padding * 2.0;
+        }
+
+        self.fonts_link = None;
+        self.fonts_more_text = None;
+        self.font_options.clear();
+        if self.lists_fonts() && !self.locked {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let link = ctx
+                .text()
+                .new_text_layout(if self.fonts_open {
+                    "Hide Installed Fonts"
+                } else {
+                    "Show Installed Fonts"
+                })
+                .font(font_family.clone(), font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let link_rect =
+                link.size().to_rect().with_origin(Point::new(0.0, height));
+            height += link_rect.height() + self.padding;
+            self.fonts_link = Some((link_rect, link));
+
+            if self.fonts_open {
+                self.fonts_filter = self.value_doc_text(data);
+                let options = font_family_options(&self.fonts_filter);
+                let width = self.width.min(self.input_max_width);
+                for family in options.iter().take(Self::FONT_OPTIONS_LIMIT) {
+                    let text_layout = ctx
+                        .text()
+                        .new_text_layout(family.to_string())
+                        .font(FontFamily::new_unchecked(*family), font_size + 1.0)
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap();
+                    let rect = Rect::new(
+                        0.0,
+                        height,
+                        width,
+                        height + text_layout.size().height + 8.0,
+                    );
+                    height += rect.height();
+                    self.font_options
+                        .push((rect, family.to_string(), text_layout));
+                }
+                height += self.padding;
+
+                let hidden = options.len().saturating_sub(Self::FONT_OPTIONS_LIMIT);
+                if hidden > 0 {
+                    let text_layout = ctx
+                        .text()
+                        .new_text_layout(format!(
+                            "{hidden} more, type in the input to narrow them down"
+                        ))
+                        .font(font_family, font_size)
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap();
+                    let origin = Point::new(0.0, height);
+                    height += text_layout.size().height + self.padding;
+                    self.fonts_more_text = Some((origin, text_layout));
+                }
+            }
+        }
+
+        self.themes_link = None;
+        self.theme_options.clear();
+        if self.lists_themes() && !self.locked {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let link = ctx
+                .text()
+                .new_text_layout(if self.themes_open {
+                    "Hide Themes"
+                } else {
+                    "Show Themes"
+                })
+                .font(font_family.clone(), font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let link_rect =
+                link.size().to_rect().with_origin(Point::new(0.0, height));
+            height += link_rect.height() + self.padding;
+            self.themes_link = Some((link_rect, link));
+
+            if self.themes_open {
+                let width = self.width.min(self.input_max_width);
+                for theme in Self::theme_names(data) {
+                    let text_layout = ctx
+                        .text()
+                        .new_text_layout(theme.clone())
+                        .font(font_family.clone(), font_size + 1.0)
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap();
+                    let rect = Rect::new(
+                        0.0,
+                        height,
+                        width,
+                        height + text_layout.size().height + 8.0,
+                    );
+                    height += rect.height();
+                    self.theme_options.push((rect, theme, text_layout));
+                }
+                height += self.padding;
+            }
+        }
+
+        self.map_add_link = None;
+        if self.map {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let line_height = ctx
+                .text()
+                .new_text_layout("{}")
+                .font(font_family.clone(), font_size)
+                .build()
+                .unwrap()
+                .size()
+                .height;
+            let remove_size = ctx
+                .text()
+                .new_text_layout("Remove")
+                .font(font_family.clone(), font_size)
+                .build()
+                .unwrap()
+                .size();
+            let input_width = ((self.width.min(self.input_max_width)
+                - remove_size.width
+                - self.padding * 2.0)
+                / 2.0)
+                .max(0.0);
+            let input_bc = BoxConstraints::tight(Size::new(
+                input_width,
+                line_height + self.padding * 2.0,
+            ));
+            for row in self.map_rows.iter_mut() {
+                let size = row.key_input.layout(ctx, &input_bc, data, env);
+                row.key_input
+                    .set_origin(ctx, data, env, Point::new(0.0, height));
+                row.value_input.layout(ctx, &input_bc, data, env);
+                row.value_input.set_origin(
+                    ctx,
+                    data,
+                    env,
+                    Point::new(input_width + self.padding, height),
+                );
+                row.remove_rect = if self.locked {
+                    Rect::ZERO
+                } else {
+                    remove_size.to_rect().with_origin(Point::new(
+                        (input_width + self.padding) * 2.0,
+                        height + (size.height - remove_size.height) / 2.0,
+                    ))
+                };
+                height += size.height + self.padding;
+            }
+            if !self.locked {
+                let link = ctx
+                    .text()
+                    .new_text_layout("Add Entry")
+                    .font(font_family, font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let link_rect =
+                    link.size().to_rect().with_origin(Point::new(0.0, height));
+                height += link_rect.height() + self.padding;
+                self.map_add_link = Some((link_rect, link));
+            }
+        }
+
+        self.json_link = None;
+        self.apply_link = None;
+        self.reset_link = None;
+        self.json_error_text = None;
+        if !self.locked && !self.large_value {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let link = ctx
+                .text()
+                .new_text_layout(if self.json_input.is_some() {
+                    "Hide JSON"
+                } else {
+                    "Edit as JSON"
+                })
+                .font(font_family.clone(), font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let link_rect =
+                link.size().to_rect().with_origin(Point::new(0.0, height));
+            height += link_rect.height() + self.padding;
+
+            self.apply_link = Config::linked_setting(&self.kind, &self.name)
+                .filter(|(table, key)| !data.config.locked_by_policy(table, key))
+                .map(|(table, key)| {
+                    let link = ctx
+                        .text()
+                        .new_text_layout(format!("Apply to {table}.{key}"))
+                        .font(font_family.clone(), font_size)
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                                .clone(),
+                        )
+                        .build()
+                        .unwrap();
+                    let rect = link.size().to_rect().with_origin(Point::new(
+                        link_rect.x1 + self.padding * 2.0,
+                        link_rect.y0,
+                    ));
+                    (rect, link, table, key)
+                });
+            self.reset_link = self.default.as_ref().map(|_| {
+                let link = ctx
+                    .text()
+                    .new_text_layout("Reset")
+                    .font(font_family.clone(), font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let x = self
+                    .apply_link
+                    .as_ref()
+                    .map(|(rect, _, _, _)| rect.x1)
+                    .unwrap_or(link_rect.x1);
+                let rect = link
+                    .size()
+                    .to_rect()
+                    .with_origin(Point::new(x + self.padding * 2.0, link_rect.y0));
+                (rect, link)
+            });
+            self.json_link = Some((link_rect, link));
+
+            if let Some(input) = self.json_input.as_mut() {
+                let line_height = ctx
+                    .text()
+                    .new_text_layout("{}")
+                    .font(font_family.clone(), font_size)
+                    .build()
+                    .unwrap()
+                    .size()
+                    .height;
+                let input_bc = BoxConstraints::tight(Size::new(
+                    bc.max().width,
+                    line_height + self.padding * 2.0,
+                ));
+                let input_size = input.layout(ctx, &input_bc, data, env);
+                input.set_origin(ctx, data, env, Point::new(0.0, height));
+                height += input_size.height + self.padding;
+
+                self.json_error_text =
+                    data.settings.value_errors.get(&self.json_doc_name()).map(
+                        |err| {
+                            let text_layout = ctx
+                                .text()
+                                .new_text_layout(err.to_string())
+                                .font(font_family, font_size)
+                                .text_color(
+                                    data.config
+                                        .get_color_unchecked(LapceTheme::LAPCE_ERROR)
+                                        .clone(),
+                                )
+                                .max_width(self.width - 30.0)
+                                .build()
+                                .unwrap();
+                            let origin = Point::new(0.0, height);
+                            height += text_layout.size().height + self.padding;
+                            (origin, text_layout)
+                        },
+                    );
+            }
+        }
+
+        self.large_value_text = match &self.value {
+            serde_json::Value::String(value) if self.large_value => {
+                let font_family = data.config.ui.font_family();
+                let font_size = data.config.ui.font_size() as f64;
+                let start: String = value
+                    .chars()
+                    .take(Self::LARGE_VALUE_PREVIEW_LEN)
+                    .map(|c| if c.is_control() { ' ' } else { c })
+                    .collect();
+                let preview = ctx
+                    .text()
+                    .new_text_layout(format!(
+                        "{start}\u{2026} ({} characters)",
+                        value.chars().count()
+                    ))
+                    .font(font_family.clone(), font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .max_width(self.width - 30.0)
+                    .build()
+                    .unwrap();
+                let link = ctx
+                    .text()
+                    .new_text_layout("Edit in Settings File")
+                    .font(font_family, font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                height += preview.size().height + self.padding;
+                let link_rect =
+                    link.size().to_rect().with_origin(Point::new(0.0, height));
+                height += link_rect.height() + self.padding;
+                Some((origin, preview, link_rect, link))
+            }
+            _ => None,
+        };
+
+        self.error_text = data
+            .settings
+            .value_errors
+            .get(&self.value_doc_name())
+            .map(|err| {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(err.to_string())
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::LAPCE_ERROR)
+                            .clone(),
+                    )
+                    .max_width(self.width - 30.0)
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                height += text_layout.size().height + self.padding;
+                (origin, text_layout)
+            });
+        self.round_link = self
+            .error_text
+            .as_ref()
+            .and_then(|_| self.rounded_input(data))
+            .map(|n| {
+                let link = ctx
+                    .text()
+                    .new_text_layout(format!("Round to {n}"))
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let link_rect =
+                    link.size().to_rect().with_origin(Point::new(0.0, height));
+                height += link_rect.height() + self.padding;
+                (link_rect, link, n)
+            });
+
+        let input_focused = self
+            .input_widget
+            .as_ref()
+            .map(|input| input.has_focus())
+            .unwrap_or(false);
+        self.status_text = match SettingsValueKind::of(&self.value) {
+            kind @ (SettingsValueKind::Integer | SettingsValueKind::Float)
+                if input_focused && self.error_text.is_none() =>
+            {
+                let (status, color) = match numeric_input_status(
+                    &kind,
+                    &self.kind,
+                    &self.name,
+                    &self.value_doc_text(data),
+                ) {
+                    Ok(value) => (
+                        format!("\u{2713} {value}"),
+                        LapceTheme::SOURCE_CONTROL_ADDED,
+                    ),
+                    Err(err) => (format!("\u{2717} {err}"), LapceTheme::LAPCE_ERROR),
+                };
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(status)
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(data.config.get_color_unchecked(color).clone())
+                    .max_width(self.width - 30.0)
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                height += text_layout.size().height + self.padding;
+                Some((origin, text_layout))
+            }
+            _ => None,
+        };
+
+        self.expanded_text = None;
+        if self.expands_env_vars() {
+            let template = self.value_doc_text(data);
+            let expanded = expand_env_vars(&template);
+            if expanded != template {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(format!("Expands to {expanded}"))
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .max_width(self.width - 30.0)
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                height += text_layout.size().height + self.padding;
+                self.expanded_text = Some((origin, text_layout));
+            }
+        }
+
+        self.size_text = None;
+        if self.input_widget.is_some() && self.value.is_string() {
+            let text = self.value_doc_text(data);
+            if text.chars().count() >= Self::SIZE_READOUT_LEN {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(text_size_readout(&text))
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .max_width(self.width - 30.0)
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                height += text_layout.size().height + self.padding;
+                self.size_text = Some((origin, text_layout));
+            }
+        }
+
+        self.lock_note =
+            if self.locked {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(
+                        "Locked by the workspace policy in .lapce/policy.toml",
+                    )
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .max_width(self.width - 50.0)
+                    .build()
+                    .unwrap();
+                let text_height = text_layout.size().height;
+                let icon_size = 13.0;
+                let icon_rect =
+                    Size::new(icon_size, icon_size).to_rect().with_origin(
+                        Point::new(0.0, height + (text_height - icon_size) / 2.0),
+                    );
+                let origin = Point::new(icon_size + 5.0, height);
+                height += text_height + self.padding;
+                Some((icon_rect, origin, text_layout))
+            } else {
+                None
+            };
+
+        self.deprecation_note =
+            self.deprecated.map(|replacement| {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(deprecation_note(replacement))
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::LAPCE_WARN)
+                            .clone(),
+                    )
+                    .max_width(self.width - 50.0)
+                    .build()
+                    .unwrap();
+                let text_height = text_layout.size().height;
+                let icon_size = 13.0;
+                let icon_rect =
+                    Size::new(icon_size, icon_size).to_rect().with_origin(
+                        Point::new(0.0, height + (text_height - icon_size) / 2.0),
+                    );
+                let origin = Point::new(icon_size + 5.0, height);
+                height += text_height + self.padding;
+                (icon_rect, origin, text_layout)
+            });
+
+        // The policy takes precedence over the workspace settings
+        self.workspace_override = if !self.locked
+            && data.config.overridden_by_workspace(&self.kind, &self.name)
+        {
+            let font_family = data.config.ui.font_family();
+            let font_size = data.config.ui.font_size() as f64;
+            let warning = ctx
+                .text()
+                .new_text_layout("This is overridden by workspace settings")
+                .font(font_family.clone(), font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::LAPCE_WARN)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let link = ctx
+                .text()
+                .new_text_layout("Edit Workspace Value")
+                .font(font_family, font_size)
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let origin = Point::new(0.0, height);
+            let warning_size = warning.size();
+            let link_rect = link
+                .size()
+                .to_rect()
+                .with_origin(origin + (warning_size.width + self.padding, 0.0));
+            height += warning_size.height.max(link_rect.height()) + self.padding;
+            Some((origin, warning, link_rect, link))
+        } else {
+            None
+        };
+
+        self.divergence_note = data
+            .config
+            .diverged_setting(&self.kind, &self.name)
+            .filter(|_| !self.locked)
+            .map(|(table, key, value)| {
+                let font_family = data.config.ui.font_family();
+                let font_size = data.config.ui.font_size() as f64;
+                let hint = ctx
+                    .text()
+                    .new_text_layout(format!("Differs from {table}.{key} ({value})"))
+                    .font(font_family.clone(), font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let link = ctx
+                    .text()
+                    .new_text_layout(format!("Follow {table}.{key}"))
+                    .font(font_family, font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                let hint_size = hint.size();
+                let link_rect = link
+                    .size()
+                    .to_rect()
+                    .with_origin(origin + (hint_size.width + self.padding, 0.0));
+                height += hint_size.height.max(link_rect.height()) + self.padding;
+                (origin, hint, link_rect, link)
+            });
+
+        self.reference_note = data
+            .config
+            .reference_difference(&self.kind, &self.name)
+            .filter(|_| !self.locked)
+            .map(|value| {
+                let font_family = data.config.ui.font_family();
+                let font_size = data.config.ui.font_size() as f64;
+                let hint = ctx
+                    .text()
+                    .new_text_layout(format!("Differs from the reference ({value})"))
+                    .font(font_family.clone(), font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::LAPCE_WARN)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let link = ctx
+                    .text()
+                    .new_text_layout("Align to Reference")
+                    .font(font_family, font_size)
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_LINK)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let origin = Point::new(0.0, height);
+                let hint_size = hint.size();
+                let link_rect = link
+                    .size()
+                    .to_rect()
+                    .with_origin(origin + (hint_size.width + self.padding, 0.0));
+                height += hint_size.height.max(link_rect.height()) + self.padding;
+                (origin, hint, link_rect, link, value.clone())
+            });
 
-        Size::new(bc.max().width, bc.max().height.max(y))
+        self.dependency_note = dependency.map(|(table, key)| {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(format!(
+                    "Only applies when \"{}\" in {} settings is turned on",
+                    key.to_title_case(),
+                    table.to_title_case()
+                ))
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                        .clone(),
+                )
+                .max_width(self.width - 30.0)
+                .build()
+                .unwrap();
+            let origin = Point::new(0.0, height);
+            height += text_layout.size().height + self.padding;
+            (origin, text_layout)
+        });
+        Size::new(self.width, height.ceil())
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
-        for child in self.children.iter_mut() {
-            child.paint(ctx, data, env);
-        }
-    }
-}
-
-struct LapceSettingsItemKeypress {
-    input: String,
-    cursor: usize,
-}
-
-struct LapceSettingsItem {
-    kind: String,
-    name: String,
-    desc: String,
-    value: serde_json::Value,
-    padding: f64,
-    checkbox_width: f64,
-    input_max_width: f64,
-    width: f64,
-    cursor: usize,
-    input: String,
-    value_changed: bool,
-    last_idle_timer: TimerToken,
-
-    name_text: Option<PietTextLayout>,
-    desc_text: Option<PietTextLayout>,
-    value_text: Option<Option<PietTextLayout>>,
-    input_widget: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
-}
+        let mut y = 0.0;
+        let padding = self.padding;
 
-impl LapceSettingsItem {
-    /// The amount of time to wait for the next key press before storing settings.
-    const SAVE_DELAY: Duration = Duration::from_millis(500);
+        let rect = ctx
+            .size()
+            .to_rect()
+            .inflate(0.0, padding)
+            .inset((padding, 0.0, -30.0, 0.0));
+        if ctx.is_hot() {
+            ctx.fill(
+                rect,
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_CURRENT_LINE),
+            );
+        }
+        // A setting that differs from the reference is tinted, with a bar
+        // along its left edge
+        if self.reference_note.is_some() {
+            let warn = data.config.get_color_unchecked(LapceTheme::LAPCE_WARN);
+            ctx.fill(rect, &warn.clone().with_alpha(0.08));
+            ctx.fill(Rect::new(rect.x0 - 3.0, rect.y0, rect.x0, rect.y1), warn);
+        }
 
-    pub fn new(
-        data: &mut LapceTabData,
-        kind: String,
-        key: String,
-        desc: String,
-        value: serde_json::Value,
-        event_sink: ExtEventSink,
-    ) -> Self {
-        let input = match &value {
-            serde_json::Value::Number(n) => {
-                if n.is_f64() {
-                    Some((n.to_string(), SettingsValueKind::Float))
-                } else {
-                    Some((n.to_string(), SettingsValueKind::Integer))
+        let text = ctx.text();
+        // Owned, as the name is used along with the item below
+        let text = self.name(text, data).clone();
+        y += padding;
+        ctx.draw_text(&text, Point::new(0.0, y));
+        let name_size = text.size();
+        self.name_rect = name_size.to_rect().with_origin(Point::new(0.0, y));
+        // Struck through on every line of the name
+        if self.deprecated.is_some() {
+            for line in 0..text.line_count() {
+                if let Some(metric) = text.line_metric(line) {
+                    let end = metric.end_offset - metric.trailing_whitespace;
+                    let x1 = text.hit_test_text_position(end).point.x;
+                    let line_y = y + metric.y_offset + metric.baseline * 0.65;
+                    ctx.stroke(
+                        Line::new(Point::new(0.0, line_y), Point::new(x1, line_y)),
+                        self.text_color(data),
+                        1.0,
+                    );
                 }
             }
-            serde_json::Value::String(s) => {
-                Some((s.to_string(), SettingsValueKind::String))
-            }
-            serde_json::Value::Array(_)
-            | serde_json::Value::Object(_)
-            | serde_json::Value::Bool(_)
-            | serde_json::Value::Null => None,
-        };
-        let input = input.map(|(input, value_kind)| {
-            let name = format!("{kind}.{key}");
-            let content = BufferContent::SettingsValue(
-                name.clone(),
-                value_kind,
-                kind.clone(),
-                key.clone(),
-            );
+        }
+        let line_height = text
+            .line_metric(0)
+            .map(|line| line.height)
+            .unwrap_or(name_size.height);
+        self.paint_type_badge(
+            ctx,
+            data,
+            Point::new(name_size.width + 8.0, y),
+            line_height,
+        );
+        y += name_size.height;
 
-            let mut doc = Document::new(
-                content.clone(),
-                data.id,
-                event_sink,
-                data.proxy.clone(),
+        y += padding;
+        let x = if self.is_checkbox() {
+            let width = 13.0;
+            let height = 13.0;
+            let origin = Point::new(0.0, y + 4.0);
+            let rect = Size::new(width, height).to_rect().with_origin(origin);
+            ctx.stroke(
+                rect,
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
+                1.0,
             );
-            doc.reload(Rope::from(&input), true);
-            data.main_split.value_docs.insert(name, Arc::new(doc));
-            let editor =
-                LapceEditorData::new(None, None, None, content, &data.config);
-            let view_id = editor.view_id;
-            let input = LapceEditorView::new(editor.view_id, editor.editor_id, None)
-                .hide_header()
-                .hide_gutter()
-                .padding((5.0, 0.0, 50.0, 0.0));
-            data.main_split.editors.insert(view_id, Arc::new(editor));
-            (view_id, WidgetPod::new(input.boxed()))
+            if ctx.is_focused() {
+                ctx.stroke(
+                    rect.inflate(2.0, 2.0),
+                    data.config.get_color_unchecked(LapceTheme::EDITOR_CARET),
+                    1.0,
+                );
+            }
+            match self.value {
+                serde_json::Value::Bool(true) => {
+                    let mut path = BezPath::new();
+                    path.move_to((origin.x + 3.0, origin.y + 7.0));
+                    path.line_to((origin.x + 6.0, origin.y + 9.5));
+                    path.line_to((origin.x + 10.0, origin.y + 3.0));
+                    ctx.stroke(
+                        path,
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
+                        2.0,
+                    );
+                }
+                // An unset setting shows a dash, it's neither on nor off
+                serde_json::Value::Null => {
+                    ctx.stroke(
+                        Line::new(
+                            (origin.x + 3.0, origin.y + 6.5),
+                            (origin.x + 10.0, origin.y + 6.5),
+                        ),
+                        data.config.get_color_unchecked(LapceTheme::EDITOR_DIM),
+                        2.0,
+                    );
+                }
+                _ => {}
+            }
+
+            self.checkbox_width
+        } else {
+            0.0
+        };
+        let (visible_desc_height, truncated) =
+            self.visible_desc_height(ctx.text(), data);
+        let text = self.desc(ctx.text(), data).clone();
+        // Only the lines of a collapsed description are clipped, a word
+        // wider than the wrap width isn't cut
+        let desc_width = text.size().width.max(self.desc_wrap_width());
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(x, y, x + desc_width, y + visible_desc_height));
+            ctx.draw_text(&text, Point::new(x, y));
         });
-        let input_widget = input.map(|i| i.1);
-        Self {
-            kind,
-            name: key,
-            desc,
-            value,
-            padding: 10.0,
-            width: 0.0,
-            checkbox_width: 20.0,
-            input_max_width: 500.0,
-            cursor: 0,
-            input: "".to_string(),
-            value_changed: false,
-            last_idle_timer: TimerToken::INVALID,
+        if truncated {
+            let more_origin = self.more_rect.origin();
+            let more = self.more(ctx.text(), data);
+            ctx.draw_text(more, more_origin);
+        }
+        let example_y = y + self.desc_text_height(ctx.text(), data);
+        if let Some(example) = self.example(ctx.text(), data) {
+            ctx.draw_text(example, Point::new(x, example_y));
+        }
 
-            name_text: None,
-            desc_text: None,
-            value_text: None,
-            input_widget,
+        if let Some(input) = self.input_widget.as_mut() {
+            input.paint(ctx, data, env);
         }
-    }
 
-    pub fn name(
-        &mut self,
-        text: &mut PietText,
-        data: &LapceTabData,
-    ) -> &PietTextLayout {
-        if self.name_text.is_none() {
-            let text_layout = text
-                .new_text_layout(self.name.to_title_case())
-                .font(
-                    data.config.ui.font_family(),
-                    (data.config.ui.font_size() + 1) as f64,
-                )
-                .text_color(
+        if let Some((origin, preview, link_rect, link)) =
+            self.large_value_text.as_ref()
+        {
+            ctx.draw_text(preview, *origin);
+            ctx.draw_text(link, link_rect.origin());
+        }
+
+        if let Some((origin, text_layout)) = self.unit_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((link_rect, link)) = self.fonts_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+        let current_font = data
+            .config
+            .setting_value(&self.kind, &self.name)
+            .and_then(|value| value.as_str().map(|s| s.to_string()));
+        for (rect, family, text_layout) in self.font_options.iter() {
+            if current_font.as_deref() == Some(family.as_str()) {
+                ctx.fill(
+                    *rect,
                     data.config
-                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                        .clone(),
-                )
-                .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
-                .max_width(self.width - 30.0)
-                .set_line_height(1.5)
-                .build()
-                .unwrap();
-            self.name_text = Some(text_layout);
+                        .get_color_unchecked(LapceTheme::EDITOR_SELECTION),
+                );
+            }
+            ctx.draw_text(
+                text_layout,
+                Point::new(
+                    rect.x0 + 5.0,
+                    rect.y0 + text_layout.y_offset(rect.height()),
+                ),
+            );
+        }
+        if let Some((origin, text_layout)) = self.fonts_more_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
         }
 
-        self.name_text.as_ref().unwrap()
-    }
+        if let Some((link_rect, link)) = self.themes_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+        let highlighted = self.highlighted_theme(data);
+        for (i, (rect, _, text_layout)) in self.theme_options.iter().enumerate() {
+            if highlighted == Some(i) {
+                ctx.fill(
+                    *rect,
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_SELECTION),
+                );
+            }
+            ctx.draw_text(
+                text_layout,
+                Point::new(
+                    rect.x0 + 5.0,
+                    rect.y0 + text_layout.y_offset(rect.height()),
+                ),
+            );
+        }
 
-    pub fn desc(
-        &mut self,
-        text: &mut PietText,
-        data: &LapceTabData,
-    ) -> &PietTextLayout {
-        if self.desc_text.is_none() {
-            let max_width = if self.value.is_boolean() {
-                self.width - self.checkbox_width
-            } else {
-                self.width
-            };
-            let text_layout = text
-                .new_text_layout(self.desc.clone())
+        if !self.map_rows.is_empty() {
+            let remove_text = ctx
+                .text()
+                .new_text_layout("Remove")
                 .font(
                     data.config.ui.font_family(),
                     data.config.ui.font_size() as f64,
                 )
                 .text_color(
                     data.config
-                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .get_color_unchecked(LapceTheme::EDITOR_LINK)
                         .clone(),
                 )
-                .max_width(max_width - 30.0)
-                .set_line_height(1.5)
                 .build()
                 .unwrap();
-            self.desc_text = Some(text_layout);
+            for row in self.map_rows.iter_mut() {
+                row.key_input.paint(ctx, data, env);
+                row.value_input.paint(ctx, data, env);
+                if row.remove_rect != Rect::ZERO {
+                    ctx.draw_text(&remove_text, row.remove_rect.origin());
+                }
+            }
+        }
+        if let Some((link_rect, link)) = self.map_add_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
         }
 
-        self.desc_text.as_ref().unwrap()
-    }
+        if let Some((link_rect, link)) = self.json_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+        if let Some((link_rect, link, _, _)) = self.apply_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+        if let Some((link_rect, link)) = self.reset_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+        if let Some(input) = self.json_input.as_mut() {
+            input.paint(ctx, data, env);
+        }
+        if let Some((origin, text_layout)) = self.json_error_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
 
-    pub fn value(
-        &mut self,
-        text: &mut PietText,
-        data: &LapceTabData,
-    ) -> Option<&PietTextLayout> {
-        if self.value_text.is_none() {
-            let value = match &self.value {
-                serde_json::Value::Number(n) => Some(n.to_string()),
-                serde_json::Value::String(s) => Some(s.to_string()),
-                serde_json::Value::Array(_)
-                | serde_json::Value::Object(_)
-                | serde_json::Value::Bool(_)
-                | serde_json::Value::Null => None,
-            };
-            let text_layout = value.map(|value| {
-                self.input = value.to_string();
-                text.new_text_layout(value)
-                    .font(
-                        data.config.ui.font_family(),
-                        data.config.ui.font_size() as f64,
-                    )
-                    .text_color(
-                        data.config
-                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                            .clone(),
-                    )
-                    .build()
-                    .unwrap()
-            });
-            self.value_text = Some(text_layout);
+        if let Some((origin, text_layout)) = self.error_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((link_rect, link, _)) = self.round_link.as_ref() {
+            ctx.draw_text(link, link_rect.origin());
+        }
+
+        if let Some((origin, text_layout)) = self.status_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((origin, text_layout)) = self.expanded_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((origin, text_layout)) = self.size_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((origin, warning, link_rect, link)) =
+            self.workspace_override.as_ref()
+        {
+            ctx.draw_text(warning, *origin);
+            ctx.draw_text(link, link_rect.origin());
+        }
+
+        if let Some((origin, hint, link_rect, link)) = self.divergence_note.as_ref()
+        {
+            ctx.draw_text(hint, *origin);
+            ctx.draw_text(link, link_rect.origin());
+        }
+
+        if let Some((origin, hint, link_rect, link, _)) =
+            self.reference_note.as_ref()
+        {
+            ctx.draw_text(hint, *origin);
+            ctx.draw_text(link, link_rect.origin());
         }
 
-        self.value_text.as_ref().unwrap().as_ref()
-    }
+        if let Some((origin, text_layout)) = self.dependency_note.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
 
-    fn clear_text_layout_cache(&mut self) {
-        self.name_text = None;
-        self.desc_text = None;
-        self.value_text = None;
+        if let Some((icon_rect, origin, text_layout)) = self.lock_note.as_ref() {
+            if let Some(svg) = get_svg("lock.svg") {
+                ctx.draw_svg(
+                    &svg,
+                    *icon_rect,
+                    Some(data.config.get_color_unchecked(LapceTheme::EDITOR_DIM)),
+                );
+            }
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if let Some((icon_rect, origin, text_layout)) =
+            self.deprecation_note.as_ref()
+        {
+            if let Some(svg) = get_svg("warning.svg") {
+                ctx.draw_svg(
+                    &svg,
+                    *icon_rect,
+                    Some(data.config.get_color_unchecked(LapceTheme::LAPCE_WARN)),
+                );
+            }
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        if self.name_hovered {
+            self.paint_change_tooltip(ctx, data);
+        }
+        if let (Some((link_rect, _)), Some((_, text))) = (
+            self.reset_link.as_ref().filter(|_| self.reset_hovered),
+            self.default.as_ref(),
+        ) {
+            Self::paint_tooltip(
+                ctx,
+                data,
+                text,
+                Point::new(link_rect.x0, link_rect.y1 + 4.0),
+            );
+        }
     }
 }
 
-impl KeyPressFocus for LapceSettingsItemKeypress {
-    fn get_mode(&self) -> Mode {
-        Mode::Insert
-    }
+#[derive(Clone)]
+pub enum ThemeKind {
+    Base,
+    UI,
+    Syntax,
+}
 
-    fn check_condition(&self, _condition: &str) -> bool {
-        false
+impl Display for ThemeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ThemeKind::Base => "theme.base",
+            ThemeKind::UI => "theme.ui",
+            ThemeKind::Syntax => "theme.syntax",
+        })
     }
+}
 
-    fn receive_char(&mut self, _ctx: &mut EventCtx, c: &str) {
-        self.input.insert_str(self.cursor, c);
-        self.cursor += c.len();
+pub struct ThemeSettings {
+    widget_id: WidgetId,
+    kind: ThemeKind,
+    inputs: Vec<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    keys: Vec<String>,
+    text_layouts: Option<Vec<PietTextLayout>>,
+    changed_rects: Vec<(String, String, Rect)>,
+    mouse_down_rect: Option<(String, String, Rect)>,
+    /// The reset button under the mouse, by its index in `changed_rects`
+    reset_hovered: Option<usize>,
+    key_rects: Vec<Rect>,
+    selected: HashSet<String>,
+    selection_anchor: Option<usize>,
+    reset_selected_rect: Rect,
+    mouse_down_reset_selected: bool,
+    error_texts: Vec<(Point, PietTextLayout)>,
+    /// The contrast ratios below the UI color inputs, with whether they're too
+    /// low to read text in
+    contrast_texts: Vec<(Point, bool, PietTextLayout)>,
+    /// The RGB and HSL of the focused color, following what's typed
+    components_text: Option<(Point, PietTextLayout)>,
+    /// The swatches right of the inputs, showing the color each one evaluates to
+    swatch_rects: Vec<Rect>,
+    /// The links next to the header, only shown by the base colors
+    header_links: Vec<(ThemeHeaderLink, Rect)>,
+    /// The input for the theme this theme extends, only shown by the base colors
+    extends_input: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    /// The input renaming the current theme on Enter, only shown by the base
+    /// colors
+    name_input: Option<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
+    /// The theme the name input was filled with, and whether it has a file in
+    /// the themes folder that can be renamed
+    name_theme: Option<(String, bool)>,
+    name_error_text: Option<(Point, PietTextLayout)>,
+    /// The editor views of the color inputs, to move the focus between them
+    view_ids: Vec<WidgetId>,
+    /// The columns before and after this one, the focus moves on to them
+    /// from the first and last color
+    prev_id: Option<WidgetId>,
+    next_id: Option<WidgetId>,
+    scroll_id: WidgetId,
+}
+
+/// The actions in the header of the theme settings
+#[derive(Clone, Copy)]
+enum ThemeHeaderLink {
+    LightTheme,
+    DarkTheme,
+    CopyJson,
+    PasteJson,
+    Rename,
+    Duplicate,
+    /// Replace every color set to one color with another
+    ReplaceColor,
+    /// Store the theme colors typed while theme changes are confirmed
+    Apply,
+    Discard,
+    /// Reveal the theme file in the file explorer
+    OpenFolder,
+}
+
+impl ThemeHeaderLink {
+    const ALL: [ThemeHeaderLink; 10] = [
+        ThemeHeaderLink::LightTheme,
+        ThemeHeaderLink::DarkTheme,
+        ThemeHeaderLink::CopyJson,
+        ThemeHeaderLink::PasteJson,
+        ThemeHeaderLink::Rename,
+        ThemeHeaderLink::Duplicate,
+        ThemeHeaderLink::ReplaceColor,
+        ThemeHeaderLink::Apply,
+        ThemeHeaderLink::Discard,
+        ThemeHeaderLink::OpenFolder,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ThemeHeaderLink::LightTheme => "Light Theme",
+            ThemeHeaderLink::DarkTheme => "Dark Theme",
+            ThemeHeaderLink::CopyJson => "Copy as JSON",
+            ThemeHeaderLink::PasteJson => "Paste JSON",
+            ThemeHeaderLink::Rename => "Rename",
+            ThemeHeaderLink::Duplicate => "Duplicate",
+            ThemeHeaderLink::ReplaceColor => "Replace Color",
+            ThemeHeaderLink::Apply => "Apply",
+            ThemeHeaderLink::Discard => "Discard",
+            ThemeHeaderLink::OpenFolder => "Open Folder",
+        }
     }
 
-    fn run_command(
-        &mut self,
-        _ctx: &mut EventCtx,
-        command: &lapce_data::command::LapceCommand,
-        _count: Option<usize>,
-        _mods: Modifiers,
-        _env: &Env,
-    ) -> CommandExecuted {
-        match &command.kind {
-            CommandKind::Move(cmd) => match cmd {
-                MoveCommand::Right => {
-                    self.cursor += 1;
-                    if self.cursor > self.input.len() {
-                        self.cursor = self.input.len();
-                    }
-                }
-                MoveCommand::Left => {
-                    if self.cursor == 0 {
-                        return CommandExecuted::Yes;
-                    }
-                    self.cursor -= 1;
-                }
-                _ => return CommandExecuted::No,
-            },
-            CommandKind::Edit(EditCommand::DeleteForward) => {
-                if self.cursor == 0 {
-                    return CommandExecuted::Yes;
-                }
-                self.input.remove(self.cursor - 1);
-                self.cursor -= 1;
+    /// Whether the link is shown, applying and discarding is only offered
+    /// while there are typed colors waiting
+    fn shown(&self, data: &LapceTabData) -> bool {
+        match self {
+            ThemeHeaderLink::Apply | ThemeHeaderLink::Discard => {
+                !data.settings.buffered_theme_changes.is_empty()
             }
-            _ => return CommandExecuted::No,
+            _ => true,
         }
-        CommandExecuted::Yes
     }
-}
 
-impl Widget<LapceTabData> for LapceSettingsItem {
-    fn event(
-        &mut self,
-        ctx: &mut EventCtx,
-        event: &Event,
-        data: &mut LapceTabData,
-        env: &Env,
-    ) {
-        if let Some(input) = self.input_widget.as_mut() {
-            match event {
-                Event::Wheel(_) => {}
-                _ => {
-                    input.event(ctx, event, data, env);
-                }
-            }
+    /// The preferred theme the link switches to
+    fn theme<'a>(&self, config: &'a Config) -> Option<&'a str> {
+        match self {
+            ThemeHeaderLink::LightTheme => Some(config.ui.preferred_light_theme()),
+            ThemeHeaderLink::DarkTheme => Some(config.ui.preferred_dark_theme()),
+            ThemeHeaderLink::CopyJson
+            | ThemeHeaderLink::PasteJson
+            | ThemeHeaderLink::Rename
+            | ThemeHeaderLink::Duplicate
+            | ThemeHeaderLink::ReplaceColor
+            | ThemeHeaderLink::Apply
+            | ThemeHeaderLink::Discard
+            | ThemeHeaderLink::OpenFolder => None,
         }
-        match event {
-            Event::MouseDown(mouse_event) => {
-                // ctx.request_focus();
-                let input = self.input.clone();
-                if let Some(_text) = self.value(ctx.text(), data) {
-                    let text = ctx
-                        .text()
-                        .new_text_layout(input)
-                        .font(
-                            data.config.ui.font_family(),
-                            data.config.ui.font_size() as f64,
-                        )
-                        .text_color(
-                            data.config
-                                .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                                .clone(),
-                        )
-                        .build()
-                        .unwrap();
-                    let mut height = self.name(ctx.text(), data).size().height;
-                    height += self.desc(ctx.text(), data).size().height;
-                    height += self.padding * 2.0 + self.padding;
+    }
+}
 
-                    let rect = Size::new(
-                        ctx.size().width.min(self.input_max_width),
-                        text.size().height,
-                    )
-                    .to_rect()
-                    .with_origin(Point::new(0.0, height))
-                    .inflate(0.0, 8.0);
-                    if rect.contains(mouse_event.pos) {
-                        let pos = mouse_event.pos - (8.0, 0.0);
-                        let hit = text.hit_test_point(pos);
-                        self.cursor = hit.idx;
-                    }
-                } else if let serde_json::Value::Bool(checked) = self.value {
-                    let rect = Size::new(self.checkbox_width, self.checkbox_width)
-                        .to_rect()
-                        .with_origin(Point::new(
-                            0.0,
-                            self.name(ctx.text(), data).size().height
-                                + self.padding * 2.0
-                                + 4.0,
-                        ));
-                    if rect.contains(mouse_event.pos) {
-                        self.value = serde_json::json!(!checked);
-                        self.value_changed = true;
-                        self.last_idle_timer =
-                            ctx.request_timer(Self::SAVE_DELAY, None);
-                    }
-                }
-            }
-            Event::MouseMove(_) => {
-                ctx.set_handled();
-            }
-            Event::Timer(token)
-                if self.value_changed && *token == self.last_idle_timer =>
-            {
-                self.value_changed = false;
-                ctx.submit_command(Command::new(
-                    LAPCE_UI_COMMAND,
-                    LapceUICommand::UpdateSettingsFile(
-                        self.kind.clone(),
-                        self.name.clone(),
-                        self.value.clone(),
-                    ),
-                    Target::Widget(data.id),
-                ));
-            }
+impl ThemeSettings {
+    /// How far Alt+Up and Alt+Down move the lightness of a focused color
+    const LIGHTNESS_STEP: f64 = 0.02;
+    /// How many degrees Alt+Left and Alt+Right turn the hue of a focused color
+    const HUE_STEP: f64 = 5.0;
 
-            _ => {}
+    fn new(kind: ThemeKind, scroll_id: WidgetId) -> Self {
+        Self {
+            kind,
+            widget_id: WidgetId::next(),
+            inputs: Vec::new(),
+            keys: Vec::new(),
+            text_layouts: None,
+            changed_rects: Vec::new(),
+            mouse_down_rect: None,
+            reset_hovered: None,
+            key_rects: Vec::new(),
+            selected: HashSet::new(),
+            selection_anchor: None,
+            reset_selected_rect: Rect::ZERO,
+            mouse_down_reset_selected: false,
+            error_texts: Vec::new(),
+            contrast_texts: Vec::new(),
+            components_text: None,
+            swatch_rects: Vec::new(),
+            header_links: Vec::new(),
+            extends_input: None,
+            name_input: None,
+            name_theme: None,
+            name_error_text: None,
+            view_ids: Vec::new(),
+            prev_id: None,
+            next_id: None,
+            scroll_id,
         }
     }
 
-    fn lifecycle(
-        &mut self,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
-        data: &LapceTabData,
-        env: &Env,
+    /// Turn the color of the input at the index by `hue` degrees and add
+    /// `lightness` to it, storing it like a typed color
+    fn nudge_color(
+        &self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        index: usize,
+        hue: f64,
+        lightness: f64,
     ) {
-        if let LifeCycle::HotChanged(_) = event {
-            ctx.request_paint();
+        let key = match self.keys.get(index) {
+            Some(key) => key,
+            None => return,
+        };
+        let color = match self.swatch_color(data, key) {
+            Some(color) => color,
+            None => return,
+        };
+        let value = adjust_color(&color, hue, lightness);
+        let parent = self.kind.to_string();
+        let name = format!("{parent}.{key}");
+        if let Some(doc) = data.main_split.value_docs.get_mut(&name) {
+            Arc::make_mut(doc).reload(Rope::from(value.as_str()), true);
         }
-        if let Some(input) = self.input_widget.as_mut() {
-            input.lifecycle(ctx, event, data, env);
+        if data.settings.value_errors.contains_key(&name) {
+            Arc::make_mut(&mut data.settings).value_errors.remove(&name);
+        }
+        let value = serde_json::Value::String(value);
+        if data.config.ui.confirm_theme_changes() {
+            let current = data.config.setting_value(&parent, key);
+            Arc::make_mut(&mut data.settings).buffer_theme_change(
+                &name,
+                (parent, key.to_string(), value),
+                current.as_ref(),
+            );
+        } else {
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::UpdateSettingsFile(parent, key.to_string(), value),
+                Target::Widget(data.id),
+            ));
         }
     }
 
-    fn update(
-        &mut self,
-        ctx: &mut UpdateCtx,
-        old_data: &LapceTabData,
-        data: &LapceTabData,
-        env: &Env,
-    ) {
-        if data.config.id != old_data.config.id {
-            self.clear_text_layout_cache();
+    /// The color the input of the key evaluates to as it's typed, or the
+    /// color in effect while what's typed isn't a color
+    fn swatch_color(&self, data: &LapceTabData, key: &str) -> Option<Color> {
+        let base = &data.config.color.base;
+        data.main_split
+            .value_docs
+            .get(&format!("{}.{key}", self.kind))
+            .and_then(|doc| {
+                let text = doc.buffer().to_string();
+                let text = match parse_color_components(&text) {
+                    Some(color) => color.ok()?,
+                    None => text,
+                };
+                eval_color(&text, &|name| base.get(name).cloned()).ok()
+            })
+            .or_else(|| match self.kind {
+                ThemeKind::Base => base.get(key).cloned(),
+                ThemeKind::UI => data.config.color.ui.get(key).cloned(),
+                ThemeKind::Syntax => data.config.color.syntax.get(key).cloned(),
+            })
+    }
+
+    /// Show what the hovered reset button changes the color from and to,
+    /// with a swatch of the default color
+    fn paint_reset_tooltip(&self, ctx: &mut PaintCtx, data: &LapceTabData) {
+        let (key, default, reset_rect) =
+            match self.reset_hovered.and_then(|i| self.changed_rects.get(i)) {
+                Some(changed) => changed,
+                None => return,
+            };
+        let current = match self.kind {
+            ThemeKind::Base => data.config.theme.base.get(key),
+            ThemeKind::UI => data.config.theme.ui.get(key),
+            ThemeKind::Syntax => data.config.theme.syntax.get(key),
         }
-        if let Some(input) = self.input_widget.as_mut() {
-            input.update(ctx, data, env);
+        .cloned()
+        .unwrap_or_default();
+        let default_text = if default.is_empty() {
+            "none"
+        } else {
+            default.as_str()
+        };
+        let text_layout = ctx
+            .text()
+            .new_text_layout(format!("{current} \u{2192} {default_text}"))
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
+                data.config
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap();
+        let text_size = text_layout.size();
+        let base = &data.config.color.base;
+        let default_color =
+            eval_color(default, &|name| base.get(name).cloned()).ok();
+        let swatch_size = text_size.height.min(14.0);
+        let swatch_width = if default_color.is_some() {
+            swatch_size + 8.0
+        } else {
+            0.0
+        };
+        let rect = Size::new(
+            text_size.width + swatch_width + 16.0,
+            text_size.height + 10.0,
+        )
+        .to_rect()
+        .with_origin(Point::new(reset_rect.x0, reset_rect.y1 + 4.0));
+        let shadow_width = data.config.ui.drop_shadow_width() as f64;
+        if shadow_width > 0.0 {
+            ctx.blurred_rect(
+                rect,
+                shadow_width,
+                data.config
+                    .get_color_unchecked(LapceTheme::LAPCE_DROPDOWN_SHADOW),
+            );
+        } else {
+            ctx.stroke(
+                rect.inflate(0.5, 0.5),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+        }
+        ctx.fill(
+            rect,
+            data.config
+                .get_color_unchecked(LapceTheme::PANEL_BACKGROUND),
+        );
+        ctx.draw_text(&text_layout, rect.origin() + (8.0, 5.0));
+        if let Some(color) = default_color {
+            let swatch = Size::new(swatch_size, swatch_size)
+                .to_rect()
+                .with_origin(Point::new(
+                    rect.x0 + 8.0 + text_size.width + 8.0,
+                    rect.y0 + (rect.height() - swatch_size) / 2.0,
+                ))
+                .to_rounded_rect(2.0);
+            ctx.fill(swatch, &color);
+            ctx.stroke(
+                swatch,
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
         }
     }
 
-    fn layout(
-        &mut self,
-        ctx: &mut LayoutCtx,
-        bc: &BoxConstraints,
+    /// List the colors referring to the base color at the index in a menu,
+    /// picking one goes to it
+    fn show_references(
+        &self,
+        ctx: &mut EventCtx,
         data: &LapceTabData,
-        env: &Env,
-    ) -> Size {
-        let width = bc.max().width;
-        if width != self.width {
-            self.width = width;
-            self.clear_text_layout_cache();
-        }
-        let text = ctx.text();
-        let name = self.name(text, data).size();
-        let desc = self.desc(text, data).size();
-        let mut height = name.height + desc.height + (self.padding * 3.0);
-        height = height.round();
+        index: usize,
+        pos: Point,
+    ) {
+        let base = &self.keys[index];
+        let references = data.config.base_color_references(base);
+        let items: Vec<MenuKind> = if references.is_empty() {
+            vec![MenuKind::Item(MenuItem {
+                desc: Some(format!("No colors use ${base}")),
+                command: LapceCommand {
+                    kind: CommandKind::Workbench(
+                        LapceWorkbenchCommand::RevealSettingInPanel,
+                    ),
+                    data: None,
+                },
+                enabled: false,
+            })]
+        } else {
+            references
+                .into_iter()
+                .map(|(kind, key)| {
+                    MenuKind::Item(MenuItem {
+                        desc: Some(format!("{kind}.{key}")),
+                        command: LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::RevealSettingInPanel,
+                            ),
+                            data: Some(serde_json::json!((kind, key))),
+                        },
+                        enabled: true,
+                    })
+                })
+                .collect()
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowMenu(ctx.to_window(pos), Arc::new(items)),
+            Target::Widget(data.id),
+        ));
+    }
 
-        if let Some(input) = self.input_widget.as_mut() {
-            input.layout(ctx, bc, data, env);
-            input.set_origin(ctx, data, env, Point::new(0.0, height));
+    /// The context menu of the key at the index, which opens the file the
+    /// color is set in
+    fn show_key_menu(
+        &self,
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        index: usize,
+        pos: Point,
+    ) {
+        let items = vec![MenuKind::Item(MenuItem {
+            desc: Some("Reveal in Theme File".to_string()),
+            command: LapceCommand {
+                kind: CommandKind::Workbench(
+                    LapceWorkbenchCommand::RevealThemeColorInFile,
+                ),
+                data: Some(serde_json::json!((
+                    self.kind.to_string(),
+                    self.keys[index]
+                ))),
+            },
+            enabled: true,
+        })];
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ShowMenu(ctx.to_window(pos), Arc::new(items)),
+            Target::Widget(data.id),
+        ));
+    }
+
+    fn new_boxed() -> Box<dyn Widget<LapceTabData>> {
+        let scroll_id = WidgetId::next();
+        let mut columns = [ThemeKind::Base, ThemeKind::Syntax, ThemeKind::UI]
+            .map(|kind| Self::new(kind, scroll_id));
+        // The focus moves through the columns in the order they're shown
+        let ids: Vec<WidgetId> =
+            columns.iter().map(|column| column.widget_id).collect();
+        for (i, column) in columns.iter_mut().enumerate() {
+            column.prev_id = i.checked_sub(1).map(|i| ids[i]);
+            column.next_id = ids.get(i + 1).copied();
         }
 
-        let text = ctx.text();
-        let value = self
-            .value(text, data)
-            .map(|v| v.size().height)
-            .unwrap_or(0.0);
-        if value > 0.0 {
-            height += value + self.padding * 2.0;
+        let mut split = LapceSplit::new(WidgetId::next()).horizontal().hide_border();
+        for column in columns {
+            split = split.with_child(column.boxed(), None, 1.0);
         }
-        Size::new(self.width, height.ceil())
+        LapceSettingsScroll::new(
+            scroll_id,
+            None,
+            "theme".to_string(),
+            LapceScroll::new(split.boxed()),
+        )
+        .boxed()
     }
 
-    fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
-        let mut y = 0.0;
-        let padding = self.padding;
+    /// Focus the color input at the index, or the next or previous column
+    /// when the index is past the colors of this one
+    fn focus_color(&self, ctx: &mut EventCtx, index: isize) {
+        let (target, command) = if index < 0 {
+            match self.prev_id {
+                Some(id) => (id, LapceUICommand::FocusThemeColor(true)),
+                None => return,
+            }
+        } else if index as usize >= self.view_ids.len() {
+            match self.next_id {
+                Some(id) => (id, LapceUICommand::FocusThemeColor(false)),
+                None => return,
+            }
+        } else {
+            let index = index as usize;
+            ctx.submit_command(Command::new(
+                LAPCE_UI_COMMAND,
+                LapceUICommand::EnsureRectVisible(
+                    self.inputs[index].layout_rect().inflate(0.0, 10.0),
+                ),
+                Target::Widget(self.scroll_id),
+            ));
+            (self.view_ids[index], LapceUICommand::Focus)
+        };
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            command,
+            Target::Widget(target),
+        ));
+    }
 
-        let rect = ctx
-            .size()
-            .to_rect()
-            .inflate(0.0, padding)
-            .inset((padding, 0.0, -30.0, 0.0));
-        if ctx.is_hot() {
-            ctx.fill(
-                rect,
-                data.config
-                    .get_color_unchecked(LapceTheme::EDITOR_CURRENT_LINE),
-            );
+    fn reset_key(
+        &self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        key: &str,
+        default: &str,
+    ) {
+        let name = format!("{}.{key}", self.kind);
+        let doc = data.main_split.value_docs.get_mut(&name).unwrap();
+        let doc = Arc::make_mut(doc);
+        doc.reload(Rope::from(default), true);
+        if data.settings.value_errors.contains_key(&name) {
+            Arc::make_mut(&mut data.settings).value_errors.remove(&name);
         }
+        ctx.submit_command(Command::new(
+            LAPCE_UI_COMMAND,
+            LapceUICommand::ResetSettingsFile(
+                self.kind.to_string(),
+                key.to_string(),
+            ),
+            Target::Widget(data.id),
+        ));
+    }
 
-        let text = ctx.text();
-        let text = self.name(text, data);
-        y += padding;
-        ctx.draw_text(text, Point::new(0.0, y));
-        y += text.size().height;
-
-        y += padding;
-        let x = if let serde_json::Value::Bool(checked) = self.value {
-            let width = 13.0;
-            let height = 13.0;
-            let origin = Point::new(0.0, y + 4.0);
-            let rect = Size::new(width, height).to_rect().with_origin(origin);
-            ctx.stroke(
-                rect,
+    fn extends_label(
+        &self,
+        text: &mut PietText,
+        data: &LapceTabData,
+    ) -> PietTextLayout {
+        text.new_text_layout("extends")
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .text_color(
                 data.config
-                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
-                1.0,
-            );
-            if checked {
-                let mut path = BezPath::new();
-                path.move_to((origin.x + 3.0, origin.y + 7.0));
-                path.line_to((origin.x + 6.0, origin.y + 9.5));
-                path.line_to((origin.x + 10.0, origin.y + 3.0));
-                ctx.stroke(
-                    path,
-                    data.config
-                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND),
-                    2.0,
-                );
-            }
+                    .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                    .clone(),
+            )
+            .build()
+            .unwrap()
+    }
 
-            self.checkbox_width
-        } else {
-            0.0
-        };
-        let text = ctx.text();
-        let text = self.desc(text, data);
-        ctx.draw_text(text, Point::new(x, y));
+    fn header(&self) -> &'static str {
+        match &self.kind {
+            ThemeKind::Base => "Base Colors",
+            ThemeKind::UI => "UI Colors",
+            ThemeKind::Syntax => "Syntax Colors",
+        }
+    }
 
-        if let Some(input) = self.input_widget.as_mut() {
-            input.paint(ctx, data, env);
+    fn run_header_link(
+        &self,
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        link: ThemeHeaderLink,
+    ) {
+        match link {
+            ThemeHeaderLink::LightTheme | ThemeHeaderLink::DarkTheme => {
+                if let Some(theme) = link.theme(&data.config) {
+                    ctx.submit_command(Command::new(
+                        LAPCE_UI_COMMAND,
+                        LapceUICommand::UpdateSettingsFile(
+                            "lapce".to_string(),
+                            "color-theme".to_string(),
+                            serde_json::json!(theme),
+                        ),
+                        Target::Widget(data.id),
+                    ));
+                }
+            }
+            ThemeHeaderLink::CopyJson => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::CopyThemeAsJson,
+            ),
+            ThemeHeaderLink::PasteJson => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::PasteThemeFromJson,
+            ),
+            ThemeHeaderLink::Rename => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::RenameTheme,
+            ),
+            ThemeHeaderLink::Duplicate => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::DuplicateTheme,
+            ),
+            ThemeHeaderLink::ReplaceColor => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::ReplaceThemeColor,
+            ),
+            ThemeHeaderLink::Apply => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::ApplyThemeChanges,
+            ),
+            ThemeHeaderLink::Discard => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::DiscardThemeChanges,
+            ),
+            ThemeHeaderLink::OpenFolder => Self::run_workbench_command(
+                ctx,
+                data,
+                LapceWorkbenchCommand::RevealThemeFileInFileExplorer,
+            ),
         }
     }
-}
 
-#[derive(Clone)]
-pub enum ThemeKind {
-    Base,
-    UI,
-    Syntax,
-}
+    fn run_workbench_command(
+        ctx: &mut EventCtx,
+        data: &LapceTabData,
+        command: LapceWorkbenchCommand,
+    ) {
+        ctx.submit_command(Command::new(
+            LAPCE_COMMAND,
+            LapceCommand {
+                kind: CommandKind::Workbench(command),
+                data: None,
+            },
+            Target::Widget(data.id),
+        ));
+    }
 
-impl Display for ThemeKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            ThemeKind::Base => "theme.base",
-            ThemeKind::UI => "theme.ui",
-            ThemeKind::Syntax => "theme.syntax",
-        })
+    /// Reset every selected key that differs from the default theme,
+    /// and clear the selection afterwards
+    fn reset_selected(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
+        for (key, default, _) in self.changed_rects.iter() {
+            if self.selected.contains(key) {
+                self.reset_key(ctx, data, key, default);
+            }
+        }
+        self.selected.clear();
+        self.selection_anchor = None;
+        ctx.request_paint();
     }
-}
 
-pub struct ThemeSettings {
-    widget_id: WidgetId,
-    kind: ThemeKind,
-    inputs: Vec<WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>>,
-    keys: Vec<String>,
-    text_layouts: Option<Vec<PietTextLayout>>,
-    changed_rects: Vec<(String, String, Rect)>,
-    mouse_down_rect: Option<(String, String, Rect)>,
-}
+    fn toggle_selection(&mut self, index: usize, mods: &Modifiers) {
+        if mods.shift() {
+            if let Some(anchor) = self.selection_anchor {
+                let (start, end) = if anchor < index {
+                    (anchor, index)
+                } else {
+                    (index, anchor)
+                };
+                for key in self.keys[start..=end].iter() {
+                    self.selected.insert(key.clone());
+                }
+                return;
+            }
+        }
 
-impl ThemeSettings {
-    fn new_boxed() -> Box<dyn Widget<LapceTabData>> {
-        LapceScroll::new(
-            LapceSplit::new(WidgetId::next())
-                .horizontal()
-                .hide_border()
-                .with_child(
-                    Self {
-                        kind: ThemeKind::Base,
-                        widget_id: WidgetId::next(),
-                        inputs: Vec::new(),
-                        keys: Vec::new(),
-                        text_layouts: None,
-                        changed_rects: Vec::new(),
-                        mouse_down_rect: None,
-                    }
-                    .boxed(),
-                    None,
-                    1.0,
-                )
-                .with_child(
-                    Self {
-                        kind: ThemeKind::Syntax,
-                        widget_id: WidgetId::next(),
-                        inputs: Vec::new(),
-                        keys: Vec::new(),
-                        text_layouts: None,
-                        changed_rects: Vec::new(),
-                        mouse_down_rect: None,
-                    }
-                    .boxed(),
-                    None,
-                    1.0,
-                )
-                .with_child(
-                    Self {
-                        kind: ThemeKind::UI,
-                        widget_id: WidgetId::next(),
-                        inputs: Vec::new(),
-                        keys: Vec::new(),
-                        text_layouts: None,
-                        changed_rects: Vec::new(),
-                        mouse_down_rect: None,
-                    }
-                    .boxed(),
-                    None,
-                    1.0,
-                )
-                .boxed(),
-        )
-        .boxed()
+        let key = &self.keys[index];
+        if !self.selected.remove(key) {
+            self.selected.insert(key.clone());
+        }
+        self.selection_anchor = Some(index);
     }
 
     fn update_inputs(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
         self.keys.clear();
         self.inputs.clear();
+        self.view_ids.clear();
         self.text_layouts = None;
+        self.selected.clear();
+        self.selection_anchor = None;
 
         let colors: Vec<&str> = match &self.kind {
             ThemeKind::Base => {
@@ -1094,42 +6503,145 @@ fn update_inputs(&mut self, ctx: &mut EventCtx, data: &mut LapceTabData) {
         };
 
         for color in colors {
-            let name = format!("{}.{color}", self.kind);
-            let content = BufferContent::SettingsValue(
-                name.clone(),
-                SettingsValueKind::String,
-                self.kind.to_string(),
-                color.to_string(),
-            );
-            let mut doc = Document::new(
-                content.clone(),
-                data.id,
-                ctx.get_external_handle(),
-                data.proxy.clone(),
-            );
-            doc.reload(
-                Rope::from(match &self.kind {
-                    ThemeKind::Base => data.config.theme.base.get(color).unwrap(),
-                    ThemeKind::UI => data.config.theme.ui.get(color).unwrap(),
-                    ThemeKind::Syntax => {
-                        data.config.theme.syntax.get(color).unwrap()
-                    }
-                }),
-                true,
-            );
-            data.main_split.value_docs.insert(name, Arc::new(doc));
-            let editor =
-                LapceEditorData::new(None, None, None, content, &data.config);
-            let view_id = editor.view_id;
-            let input = LapceEditorView::new(editor.view_id, editor.editor_id, None)
-                .hide_header()
-                .hide_gutter()
-                .padding((5.0, 0.0, 5.0, 0.0));
-            data.main_split.editors.insert(view_id, Arc::new(editor));
+            let value = match &self.kind {
+                ThemeKind::Base => data.config.theme.base.get(color).unwrap(),
+                ThemeKind::UI => data.config.theme.ui.get(color).unwrap(),
+                ThemeKind::Syntax => data.config.theme.syntax.get(color).unwrap(),
+            }
+            .to_string();
+            let (view_id, input) =
+                Self::new_input(ctx, data, &self.kind.to_string(), color, &value);
             self.keys.push(color.to_string());
-            self.inputs.push(WidgetPod::new(input.boxed()));
+            self.view_ids.push(view_id);
+            self.inputs.push(input);
+        }
+
+        if let ThemeKind::Base = self.kind {
+            let extends = data.config.theme.extends.clone();
+            self.extends_input =
+                Some(Self::new_input(ctx, data, "theme", "extends", &extends).1);
+            let theme = data.config.lapce.color_theme.clone();
+            self.name_input =
+                Some(Self::new_input(ctx, data, "theme", "name", &theme).1);
+        }
+    }
+
+    /// Fill the name input with the current theme once it changes
+    fn sync_name_input(&mut self, data: &mut LapceTabData) {
+        if self.name_input.is_none()
+            || self.name_theme.as_ref().map(|(theme, _)| theme)
+                == Some(&data.config.lapce.color_theme)
+        {
+            return;
+        }
+        let theme = data.config.lapce.color_theme.clone();
+        if let Some(doc) = data.main_split.value_docs.get_mut("theme.name") {
+            Arc::make_mut(doc).reload(Rope::from(theme.as_str()), true);
+        }
+        let editable = Config::user_theme_file(&theme).is_some();
+        self.name_theme = Some((theme, editable));
+    }
+
+    /// Why the name typed into the name input can't be the theme's new name,
+    /// nothing while it's still the current name
+    fn name_error(&self, data: &LapceTabData) -> Option<String> {
+        let (theme, editable) = self.name_theme.as_ref()?;
+        let name = data
+            .main_split
+            .value_docs
+            .get("theme.name")?
+            .buffer()
+            .to_string();
+        if name.trim() == theme {
+            return None;
+        }
+        if !editable {
+            return Some(format!(
+                "\"{theme}\" is built in or comes from a plugin, duplicate it to \
+                 rename it"
+            ));
+        }
+        data.config.theme_name_error(&name)
+    }
+
+    /// Rename the theme to what's typed into the name input on Enter, or put
+    /// the current name back on Escape
+    fn name_key_down(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        key: &KbKey,
+    ) -> bool {
+        let theme = match self.name_theme.as_ref() {
+            Some((theme, _)) => theme.clone(),
+            None => return false,
+        };
+        match key {
+            KbKey::Enter => {
+                let name = data
+                    .main_split
+                    .value_docs
+                    .get("theme.name")
+                    .map(|doc| doc.buffer().to_string())
+                    .unwrap_or_default();
+                if name.trim() != theme && self.name_error(data).is_none() {
+                    ctx.submit_command(Command::new(
+                        LAPCE_COMMAND,
+                        LapceCommand {
+                            kind: CommandKind::Workbench(
+                                LapceWorkbenchCommand::ApplyRenameTheme,
+                            ),
+                            data: Some(serde_json::json!(name.trim())),
+                        },
+                        Target::Widget(data.id),
+                    ));
+                }
+                true
+            }
+            KbKey::Escape => {
+                if let Some(doc) = data.main_split.value_docs.get_mut("theme.name") {
+                    Arc::make_mut(doc).reload(Rope::from(theme.as_str()), true);
+                }
+                true
+            }
+            _ => false,
         }
     }
+
+    fn new_input(
+        ctx: &mut EventCtx,
+        data: &mut LapceTabData,
+        parent: &str,
+        key: &str,
+        value: &str,
+    ) -> (
+        WidgetId,
+        WidgetPod<LapceTabData, Box<dyn Widget<LapceTabData>>>,
+    ) {
+        let name = format!("{parent}.{key}");
+        let content = BufferContent::SettingsValue(
+            name.clone(),
+            SettingsValueKind::String,
+            parent.to_string(),
+            key.to_string(),
+        );
+        let mut doc = Document::new(
+            content.clone(),
+            data.id,
+            ctx.get_external_handle(),
+            data.proxy.clone(),
+        );
+        doc.reload(Rope::from(value), true);
+        data.main_split.value_docs.insert(name, Arc::new(doc));
+        let editor = LapceEditorData::new(None, None, None, content, &data.config);
+        let view_id = editor.view_id;
+        let input = LapceEditorView::new(editor.view_id, editor.editor_id, None)
+            .hide_header()
+            .hide_gutter()
+            .padding((5.0, 0.0, 5.0, 0.0));
+        data.main_split.editors.insert(view_id, Arc::new(editor));
+        (view_id, WidgetPod::new(input.boxed()))
+    }
 }
 
 impl Widget<LapceTabData> for ThemeSettings {
@@ -1145,37 +6657,157 @@ fn event(
         env: &Env,
     ) {
         match event {
+            Event::KeyDown(key_event) => {
+                if self
+                    .name_input
+                    .as_ref()
+                    .map(|input| input.has_focus())
+                    .unwrap_or(false)
+                    && self.name_key_down(ctx, data, &key_event.key)
+                {
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some(index) =
+                    self.inputs.iter().position(|input| input.has_focus())
+                {
+                    if key_event.mods.alt() {
+                        let (hue, lightness) = match &key_event.key {
+                            KbKey::ArrowUp => (0.0, Self::LIGHTNESS_STEP),
+                            KbKey::ArrowDown => (0.0, -Self::LIGHTNESS_STEP),
+                            KbKey::ArrowLeft => (-Self::HUE_STEP, 0.0),
+                            KbKey::ArrowRight => (Self::HUE_STEP, 0.0),
+                            _ => (0.0, 0.0),
+                        };
+                        if hue != 0.0 || lightness != 0.0 {
+                            self.nudge_color(ctx, data, index, hue, lightness);
+                            ctx.set_handled();
+                            return;
+                        }
+                    }
+                    let step = match &key_event.key {
+                        KbKey::Tab if key_event.mods.shift() => -1,
+                        KbKey::Tab => 1,
+                        KbKey::ArrowUp => -1,
+                        KbKey::ArrowDown => 1,
+                        _ => 0,
+                    };
+                    if step != 0 {
+                        self.focus_color(ctx, index as isize + step);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+            // Sent to the settings panel, which shows the theme section first
+            Event::Command(cmd)
+                if matches!(
+                    cmd.get(LAPCE_UI_COMMAND),
+                    Some(LapceUICommand::ShowSettingsField(table, _))
+                        if *table == self.kind.to_string()
+                ) =>
+            {
+                if let Some(LapceUICommand::ShowSettingsField(_, key)) =
+                    cmd.get(LAPCE_UI_COMMAND)
+                {
+                    if let Some(index) = self.keys.iter().position(|k| k == key) {
+                        if index < self.view_ids.len() {
+                            self.focus_color(ctx, index as isize);
+                        }
+                    }
+                }
+            }
+            Event::Command(cmd)
+                if cmd.is(LAPCE_UI_COMMAND)
+                    && cmd.target() == Target::Widget(self.widget_id) =>
+            {
+                if let LapceUICommand::FocusThemeColor(last) =
+                    cmd.get_unchecked(LAPCE_UI_COMMAND)
+                {
+                    if !self.view_ids.is_empty() {
+                        let index = if *last { self.view_ids.len() - 1 } else { 0 };
+                        self.focus_color(ctx, index as isize);
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+            }
             Event::MouseDown(mouse_event) => {
+                if let Some((link, _)) = self
+                    .header_links
+                    .iter()
+                    .find(|(_, rect)| rect.contains(mouse_event.pos))
+                {
+                    self.run_header_link(ctx, data, *link);
+                    ctx.set_handled();
+                    return;
+                }
+                if let ThemeKind::Base = self.kind {
+                    if let Some(index) = self
+                        .swatch_rects
+                        .iter()
+                        .position(|rect| rect.contains(mouse_event.pos))
+                    {
+                        self.show_references(ctx, data, index, mouse_event.pos);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
                 self.mouse_down_rect = None;
+                self.mouse_down_reset_selected = !self.selected.is_empty()
+                    && self.reset_selected_rect.contains(mouse_event.pos);
                 for (key, default, change) in self.changed_rects.iter() {
                     if change.contains(mouse_event.pos) {
                         self.mouse_down_rect =
                             Some((key.to_string(), default.to_string(), *change));
                     }
                 }
+                if let Some(index) = self
+                    .key_rects
+                    .iter()
+                    .position(|rect| rect.contains(mouse_event.pos))
+                {
+                    if mouse_event.button.is_right() {
+                        self.show_key_menu(ctx, data, index, mouse_event.pos);
+                        ctx.set_handled();
+                        return;
+                    }
+                    self.toggle_selection(index, &mouse_event.mods);
+                    ctx.request_paint();
+                }
             }
             Event::MouseUp(mouse_event) => {
                 if let Some((key, default, rect)) = self.mouse_down_rect.as_ref() {
                     if rect.contains(mouse_event.pos) {
-                        let name = format!("{}.{key}", self.kind);
-                        let doc = data.main_split.value_docs.get_mut(&name).unwrap();
-                        let doc = Arc::make_mut(doc);
-                        doc.reload(Rope::from(default), true);
-                        ctx.submit_command(Command::new(
-                            LAPCE_UI_COMMAND,
-                            LapceUICommand::ResetSettingsFile(
-                                self.kind.to_string(),
-                                key.clone(),
-                            ),
-                            Target::Widget(data.id),
-                        ));
+                        self.reset_key(ctx, data, key, default);
                     }
                 }
+                if self.mouse_down_reset_selected
+                    && self.reset_selected_rect.contains(mouse_event.pos)
+                {
+                    self.reset_selected(ctx, data);
+                }
                 self.mouse_down_rect = None;
+                self.mouse_down_reset_selected = false;
+            }
+            Event::MouseMove(mouse_event) => {
+                let hovered = self
+                    .changed_rects
+                    .iter()
+                    .position(|(_, _, rect)| rect.contains(mouse_event.pos));
+                if hovered != self.reset_hovered {
+                    self.reset_hovered = hovered;
+                    ctx.request_paint();
+                }
             }
             _ => {}
         }
-        for input in self.inputs.iter_mut() {
+        for input in self
+            .inputs
+            .iter_mut()
+            .chain(self.extends_input.as_mut())
+            .chain(self.name_input.as_mut())
+        {
             match event {
                 Event::Wheel(_) => {}
                 _ => {
@@ -1188,6 +6820,7 @@ fn event(
             self.update_inputs(ctx, data);
             ctx.children_changed();
         }
+        self.sync_name_input(data);
     }
 
     fn lifecycle(
@@ -1197,7 +6830,17 @@ fn lifecycle(
         data: &LapceTabData,
         env: &Env,
     ) {
-        for input in self.inputs.iter_mut() {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.reset_hovered.take().is_some() {
+                ctx.request_paint();
+            }
+        }
+        for input in self
+            .inputs
+            .iter_mut()
+            .chain(self.extends_input.as_mut())
+            .chain(self.name_input.as_mut())
+        {
             input.lifecycle(ctx, event, data, env);
         }
     }
@@ -1212,7 +6855,53 @@ fn update(
         if data.config.id != old_data.config.id {
             self.text_layouts = None;
         }
-        for input in self.inputs.iter_mut() {
+        if data.settings.value_errors != old_data.settings.value_errors
+            || data.settings.buffered_theme_changes.is_empty()
+                != old_data.settings.buffered_theme_changes.is_empty()
+        {
+            ctx.request_layout();
+        }
+        // The RGB and HSL are shown under the focused color
+        let focused = |data: &LapceTabData| {
+            self.view_ids
+                .iter()
+                .position(|id| *id == *data.focus)
+                .map(|i| format!("{}.{}", self.kind, self.keys[i]))
+        };
+        let focused_rev = |data: &LapceTabData| {
+            focused(data).and_then(|name| {
+                data.main_split.value_docs.get(&name).map(|doc| doc.rev())
+            })
+        };
+        if focused(data) != focused(old_data)
+            || focused_rev(data) != focused_rev(old_data)
+        {
+            ctx.request_layout();
+        }
+        // The swatches follow what's typed into the inputs
+        if !data
+            .main_split
+            .value_docs
+            .ptr_eq(&old_data.main_split.value_docs)
+        {
+            ctx.request_paint();
+            // The name input tells why the typed name can't be used below it
+            let name_rev = |data: &LapceTabData| {
+                data.main_split
+                    .value_docs
+                    .get("theme.name")
+                    .map(|doc| doc.rev())
+            };
+            if self.name_input.is_some() && name_rev(data) != name_rev(old_data) {
+                ctx.request_layout();
+            }
+        }
+        for input in self
+            .inputs
+            .iter_mut()
+            .chain(self.extends_input.as_mut())
+            .chain(self.name_input.as_mut())
+        {
             input.update(ctx, data, env);
         }
     }
@@ -1234,7 +6923,15 @@ fn layout(
 
         if self.text_layouts.is_none() {
             let mut text_layouts = Vec::new();
+            let kind = self.kind.to_string();
             for key in self.keys.iter() {
+                // Colors that come from the extended theme are muted, until
+                // they're overridden by this theme or the settings
+                let color = if data.config.inherited_color(&kind, key) {
+                    LapceTheme::EDITOR_DIM
+                } else {
+                    LapceTheme::EDITOR_FOREGROUND
+                };
                 let text_layout = ctx
                     .text()
                     .new_text_layout(key.to_string())
@@ -1242,11 +6939,7 @@ fn layout(
                         data.config.ui.font_family(),
                         data.config.ui.font_size() as f64,
                     )
-                    .text_color(
-                        data.config
-                            .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
-                            .clone(),
-                    )
+                    .text_color(data.config.get_color_unchecked(color).clone())
                     .build()
                     .unwrap();
                 text_layouts.push(text_layout);
@@ -1262,6 +6955,11 @@ fn layout(
             .map(|text_layout| text_layout.size().width.ceil() as usize)
             .max()
             .unwrap_or(0) as f64;
+        let text_width = if self.extends_input.is_some() {
+            text_width.max(self.extends_label(ctx.text(), data).size().width.ceil())
+        } else {
+            text_width
+        };
 
         let mut y = 30.0;
         let input_bc = BoxConstraints::tight(Size::new(
@@ -1285,13 +6983,209 @@ fn layout(
             .unwrap();
         let reset_size = reset_text.size();
         self.changed_rects.clear();
+        self.reset_hovered = None;
+        self.key_rects.clear();
+        self.error_texts.clear();
+        self.contrast_texts.clear();
+        self.components_text = None;
+        self.swatch_rects.clear();
+
+        let reset_selected_text = ctx
+            .text()
+            .new_text_layout("reset selected")
+            .font(
+                data.config.ui.font_family(),
+                data.config.ui.font_size() as f64,
+            )
+            .build()
+            .unwrap();
+        let reset_selected_width = reset_selected_text.size().width + 20.0;
+        self.reset_selected_rect = Rect::new(
+            bc.max().width - reset_selected_width - 10.0,
+            5.0,
+            bc.max().width - 10.0,
+            25.0,
+        );
+
+        if let ThemeKind::Base = self.kind {
+            let text = ctx.text();
+            let font_size = data.config.ui.font_size() as f64;
+            let header_width = text
+                .new_text_layout(self.header())
+                .font(data.config.ui.font_family(), font_size)
+                .default_attribute(TextAttribute::Weight(FontWeight::BOLD))
+                .build()
+                .unwrap()
+                .size()
+                .width;
+            let mut x = header_width + 20.0;
+            self.header_links.clear();
+            for link in ThemeHeaderLink::ALL.iter().filter(|l| l.shown(data)) {
+                let width = text
+                    .new_text_layout(link.label())
+                    .font(data.config.ui.font_family(), font_size)
+                    .build()
+                    .unwrap()
+                    .size()
+                    .width;
+                self.header_links
+                    .push((*link, Rect::new(x, 5.0, x + width, 25.0)));
+                x += width + 15.0;
+            }
+        }
+
+        self.name_error_text = None;
+        if self.name_input.is_some() {
+            let error = self.name_error(data);
+            let input = self.name_input.as_mut().unwrap();
+            let size = input.layout(ctx, &input_bc, data, env);
+            let padding = (size.height * 0.2).round();
+            y += padding;
+            input.set_origin(ctx, data, env, Point::new(text_width + 10.0, y));
+            y += size.height + padding;
+            if let Some(err) = error {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(err)
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::LAPCE_ERROR)
+                            .clone(),
+                    )
+                    .max_width(bc.max().width - text_width - 10.0)
+                    .build()
+                    .unwrap();
+                let height = text_layout.size().height;
+                self.name_error_text =
+                    Some((Point::new(text_width + 10.0, y), text_layout));
+                y += height + padding;
+            }
+        }
+
+        if let Some(input) = self.extends_input.as_mut() {
+            let size = input.layout(ctx, &input_bc, data, env);
+            let padding = (size.height * 0.2).round();
+            y += padding;
+            input.set_origin(ctx, data, env, Point::new(text_width + 10.0, y));
+            y += size.height + padding * 3.0;
+        }
 
+        let focused_color = self
+            .view_ids
+            .iter()
+            .position(|id| *id == *data.focus)
+            .and_then(|i| Some((i, self.swatch_color(data, &self.keys[i])?)));
         for (i, input) in self.inputs.iter_mut().enumerate() {
             let size = input.layout(ctx, &input_bc, data, env);
             let padding = (size.height * 0.2).round();
             y += padding;
             input.set_origin(ctx, data, env, Point::new(text_width + 10.0, y));
             y += size.height + padding;
+            let input_rect = input.layout_rect();
+            let swatch_size = (input_rect.height() * 0.6).round();
+            self.swatch_rects.push(
+                Size::new(swatch_size, swatch_size).to_rect().with_origin(
+                    Point::new(
+                        input_rect.x1 + 10.0,
+                        input_rect.y0 + (input_rect.height() - swatch_size) / 2.0,
+                    ),
+                ),
+            );
+            self.key_rects.push(Rect::new(
+                0.0,
+                input.layout_rect().y0,
+                text_width,
+                input.layout_rect().y1,
+            ));
+
+            let name = format!("{}.{}", self.kind, self.keys[i]);
+            if let Some(err) = data.settings.value_errors.get(&name) {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(err.to_string())
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::LAPCE_ERROR)
+                            .clone(),
+                    )
+                    .max_width(bc.max().width - text_width - 10.0)
+                    .build()
+                    .unwrap();
+                let height = text_layout.size().height;
+                self.error_texts
+                    .push((Point::new(text_width + 10.0, y), text_layout));
+                y += height + padding;
+            }
+
+            if let Some((_, color)) =
+                focused_color.as_ref().filter(|(focused, _)| *focused == i)
+            {
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(color_components(color))
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_DIM)
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                let height = text_layout.size().height;
+                self.components_text =
+                    Some((Point::new(text_width + 10.0, y), text_layout));
+                y += height + padding;
+            }
+
+            if let ThemeKind::UI = self.kind {
+                for (bg, ratio) in data.config.contrast_ratios(&self.keys[i]) {
+                    let low = ratio < LapceTheme::MIN_CONTRAST_RATIO;
+                    let text_layout = ctx
+                        .text()
+                        .new_text_layout(if low {
+                            format!(
+                                "Contrast {ratio:.1}:1 on {bg}, below {}:1",
+                                LapceTheme::MIN_CONTRAST_RATIO
+                            )
+                        } else {
+                            format!("Contrast {ratio:.1}:1 on {bg}")
+                        })
+                        .font(
+                            data.config.ui.font_family(),
+                            data.config.ui.font_size() as f64,
+                        )
+                        .text_color(
+                            data.config
+                                .get_color_unchecked(if low {
+                                    LapceTheme::LAPCE_WARN
+                                } else {
+                                    LapceTheme::EDITOR_DIM
+                                })
+                                .clone(),
+                        )
+                        .max_width(bc.max().width - text_width - 30.0)
+                        .build()
+                        .unwrap();
+                    let height = text_layout.size().height;
+                    self.contrast_texts.push((
+                        Point::new(text_width + 10.0, y),
+                        low,
+                        text_layout,
+                    ));
+                    y += height + padding;
+                }
+            }
 
             let (changed, default) = match self.kind {
                 ThemeKind::Base => {
@@ -1337,7 +7231,7 @@ fn layout(
                 }
             };
             if changed {
-                let x = input.layout_rect().x1 + 10.0;
+                let x = self.swatch_rects[i].x1 + 10.0;
                 let y0 = input.layout_rect().y0;
                 let y1 = input.layout_rect().y1;
                 let rect = Rect::new(x, y0, x + reset_size.width + 20.0, y1);
@@ -1352,11 +7246,7 @@ fn layout(
     fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
         let header_text = ctx
             .text()
-            .new_text_layout(match &self.kind {
-                ThemeKind::Base => "Base Colors",
-                ThemeKind::UI => "UI Colors",
-                ThemeKind::Syntax => "Syntax Colors",
-            })
+            .new_text_layout(self.header())
             .font(
                 data.config.ui.font_family(),
                 data.config.ui.font_size() as f64,
@@ -1371,6 +7261,82 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
             .unwrap();
         ctx.draw_text(&header_text, Point::new(0.0, header_text.y_offset(30.0)));
 
+        if let ThemeKind::Base = self.kind {
+            for (link, rect) in self.header_links.iter() {
+                // The preferred theme that's already active isn't a link
+                let active = link.theme(&data.config).map(|theme| {
+                    theme.eq_ignore_ascii_case(&data.config.lapce.color_theme)
+                }) == Some(true);
+                let text_layout = ctx
+                    .text()
+                    .new_text_layout(link.label())
+                    .font(
+                        data.config.ui.font_family(),
+                        data.config.ui.font_size() as f64,
+                    )
+                    .text_color(
+                        data.config
+                            .get_color_unchecked(if active {
+                                LapceTheme::EDITOR_FOREGROUND
+                            } else {
+                                LapceTheme::EDITOR_LINK
+                            })
+                            .clone(),
+                    )
+                    .build()
+                    .unwrap();
+                ctx.draw_text(
+                    &text_layout,
+                    Point::new(
+                        rect.x0,
+                        rect.y0 + text_layout.y_offset(rect.height()),
+                    ),
+                );
+            }
+        }
+
+        if !self.selected.is_empty() {
+            let reset_selected_text = ctx
+                .text()
+                .new_text_layout("reset selected")
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let rect = self.reset_selected_rect;
+            ctx.stroke(
+                rect.inflate(-0.5, -0.5),
+                data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                1.0,
+            );
+            ctx.draw_text(
+                &reset_selected_text,
+                Point::new(
+                    rect.x0 + 10.0,
+                    rect.y0 + reset_selected_text.y_offset(rect.height()),
+                ),
+            );
+        }
+
+        for (i, key) in self.keys.iter().enumerate() {
+            if self.selected.contains(key) {
+                if let Some(rect) = self.key_rects.get(i) {
+                    ctx.fill(
+                        *rect,
+                        data.config
+                            .get_color_unchecked(LapceTheme::EDITOR_SELECTION),
+                    );
+                }
+            }
+        }
+
         for (i, input) in self.inputs.iter_mut().enumerate() {
             let text_layout = &self.text_layouts.as_ref().unwrap()[i];
             ctx.draw_text(
@@ -1382,6 +7348,84 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
                 ),
             );
             input.paint(ctx, data, env);
+
+            if let (Some(rect), Some(color)) = (
+                self.swatch_rects.get(i),
+                self.swatch_color(data, &self.keys[i]),
+            ) {
+                let rect = rect.to_rounded_rect(2.0);
+                ctx.fill(rect, &color);
+                ctx.stroke(
+                    rect,
+                    data.config.get_color_unchecked(LapceTheme::LAPCE_BORDER),
+                    1.0,
+                );
+            }
+        }
+
+        if self.extends_input.is_some() {
+            let text_layout = self.extends_label(ctx.text(), data);
+            let input = self.extends_input.as_mut().unwrap();
+            let rect = input.layout_rect();
+            ctx.draw_text(
+                &text_layout,
+                Point::new(0.0, rect.y0 + text_layout.y_offset(rect.height())),
+            );
+            input.paint(ctx, data, env);
+        }
+
+        if self.name_input.is_some() {
+            let text_layout = ctx
+                .text()
+                .new_text_layout("name")
+                .font(
+                    data.config.ui.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .build()
+                .unwrap();
+            let input = self.name_input.as_mut().unwrap();
+            let rect = input.layout_rect();
+            ctx.draw_text(
+                &text_layout,
+                Point::new(0.0, rect.y0 + text_layout.y_offset(rect.height())),
+            );
+            input.paint(ctx, data, env);
+        }
+        if let Some((origin, text_layout)) = self.name_error_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        for (origin, text_layout) in self.error_texts.iter() {
+            ctx.draw_text(text_layout, *origin);
+        }
+        if let Some((origin, text_layout)) = self.components_text.as_ref() {
+            ctx.draw_text(text_layout, *origin);
+        }
+
+        for (origin, low, text_layout) in self.contrast_texts.iter() {
+            let mut origin = *origin;
+            if *low {
+                let size = text_layout.size().height.min(16.0);
+                if let Some(svg) = get_svg("warning.svg") {
+                    ctx.draw_svg(
+                        &svg,
+                        Size::new(size, size).to_rect().with_origin(
+                            origin + (0.0, (text_layout.size().height - size) / 2.0),
+                        ),
+                        Some(
+                            data.config.get_color_unchecked(LapceTheme::LAPCE_WARN),
+                        ),
+                    );
+                }
+                origin.x += size + 5.0;
+            }
+            ctx.draw_text(text_layout, origin);
         }
 
         let reset_text = ctx
@@ -1412,5 +7456,101 @@ fn paint(&mut self, ctx: &mut PaintCtx, data: &LapceTabData, env: &Env) {
                 ),
             )
         }
+
+        self.paint_reset_tooltip(ctx, data);
+
+        let top = ctx.region().bounding_box().y0;
+        if top > 30.0 {
+            if let Some(index) = self.key_rects.iter().position(|rect| rect.y1 > top)
+            {
+                paint_scroll_indicator(ctx, data, &self.keys[index]);
+            }
+        }
+    }
+}
+
+/// The settings in effect as read-only JSON, updated whenever they change
+struct EffectiveConfigView {
+    /// The JSON laid out for the width in the layout, cleared when the
+    /// settings change
+    text_layout: Option<(f64, PietTextLayout)>,
+}
+
+impl EffectiveConfigView {
+    fn new() -> Self {
+        Self { text_layout: None }
+    }
+}
+
+impl Widget<LapceTabData> for EffectiveConfigView {
+    fn event(
+        &mut self,
+        _ctx: &mut EventCtx,
+        _event: &Event,
+        _data: &mut LapceTabData,
+        _env: &Env,
+    ) {
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &LapceTabData,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LapceTabData,
+        data: &LapceTabData,
+        _env: &Env,
+    ) {
+        if !Arc::ptr_eq(&data.config, &old_data.config) {
+            self.text_layout = None;
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &LapceTabData,
+        _env: &Env,
+    ) -> Size {
+        let width = bc.max().width;
+        if self.text_layout.as_ref().map(|(w, _)| *w) != Some(width) {
+            let text_layout = ctx
+                .text()
+                .new_text_layout(data.config.effective_json())
+                .font(
+                    data.config.editor.font_family(),
+                    data.config.ui.font_size() as f64,
+                )
+                .text_color(
+                    data.config
+                        .get_color_unchecked(LapceTheme::EDITOR_FOREGROUND)
+                        .clone(),
+                )
+                .max_width(width - 20.0)
+                .build()
+                .unwrap();
+            self.text_layout = Some((width, text_layout));
+        }
+        let height = self
+            .text_layout
+            .as_ref()
+            .map(|(_, text_layout)| text_layout.size().height)
+            .unwrap_or(0.0);
+        Size::new(width, height + 20.0)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &LapceTabData, _env: &Env) {
+        if let Some((_, text_layout)) = self.text_layout.as_ref() {
+            ctx.draw_text(text_layout, Point::new(10.0, 10.0));
+        }
     }
 }
@@ -18,8 +18,8 @@
 use lapce_data::{
     command::{
         CommandKind, LapceCommand, LapceUICommand, LapceWorkbenchCommand,
-        LAPCE_COMMAND, LAPCE_OPEN_FILE, LAPCE_OPEN_FOLDER, LAPCE_SAVE_FILE_AS,
-        LAPCE_UI_COMMAND,
+        LAPCE_COMMAND, LAPCE_IMPORT_THEME, LAPCE_IMPORT_VSCODE_SETTINGS,
+        LAPCE_OPEN_FILE, LAPCE_OPEN_FOLDER, LAPCE_SAVE_FILE_AS, LAPCE_UI_COMMAND,
     },
     completion::CompletionStatus,
     config::{Config, LapceTheme},
@@ -38,6 +38,7 @@
         PanelStyle,
     },
     proxy::path_from_url,
+    settings::stored_settings_value,
 };
 use lapce_rpc::proxy::ProxyResponse;
 use lsp_types::DiagnosticSeverity;
@@ -695,6 +696,16 @@ fn handle_command_event(
                     Target::Widget(data.id),
                 ));
             }
+            Event::Command(cmd) if cmd.is(LAPCE_IMPORT_THEME) => {
+                ctx.set_handled();
+                let file = cmd.get_unchecked(LAPCE_IMPORT_THEME);
+                data.import_theme_file(ctx, &file.path);
+            }
+            Event::Command(cmd) if cmd.is(LAPCE_IMPORT_VSCODE_SETTINGS) => {
+                ctx.set_handled();
+                let file = cmd.get_unchecked(LAPCE_IMPORT_VSCODE_SETTINGS);
+                data.import_vscode_settings(ctx, &file.path);
+            }
             Event::Command(cmd) if cmd.is(LAPCE_UI_COMMAND) => {
                 let command = cmd.get_unchecked(LAPCE_UI_COMMAND);
                 match command {
@@ -1211,18 +1222,75 @@ fn handle_command_event(
                         );
                         ctx.set_handled();
                     }
+                    LapceUICommand::UpdateSettingsFile(parent, key, value)
+                        if value.is_null() =>
+                    {
+                        // Settings files can't hold null, an unset setting
+                        // is one the file leaves out
+                        ctx.submit_command(Command::new(
+                            LAPCE_UI_COMMAND,
+                            LapceUICommand::ResetSettingsFile(
+                                parent.to_string(),
+                                key.to_string(),
+                            ),
+                            Target::Widget(data.id),
+                        ));
+                    }
                     LapceUICommand::UpdateSettingsFile(parent, key, value) => {
-                        if let Some(value) = toml_edit::ser::to_item(value)
+                        Arc::make_mut(&mut data.settings)
+                            .capture_session_change(parent, key);
+                        // The inputs keep what was typed
+                        let stored =
+                            stored_settings_value(&data.config, parent, value);
+                        if let Some(toml_value) = toml_edit::ser::to_item(&stored)
                             .ok()
                             .and_then(|i| i.into_value().ok())
                         {
                             let update_result =
-                                Config::update_file(parent, key, value);
-                            debug_assert!(update_result.is_some());
+                                Config::write_setting(parent, key, Some(toml_value));
+                            if let Err(err) = update_result {
+                                // The inputs go back to what's stored
+                                if let Some(old) =
+                                    data.config.setting_value(parent, key)
+                                {
+                                    data.main_split
+                                        .sync_settings_value_docs(parent, key, &old);
+                                }
+                                Arc::make_mut(&mut data.settings).write_error =
+                                    Some((parent.to_string(), key.to_string(), err));
+                            } else {
+                                if let (("lapce", "color-theme"), Some(theme)) =
+                                    ((parent.as_str(), key.as_str()), value.as_str())
+                                {
+                                    let _ = data.db.save_theme_change(
+                                        &data.config.lapce.color_theme,
+                                        theme,
+                                    );
+                                }
+                                data.settings_file_written(
+                                    parent,
+                                    key,
+                                    Some(stored),
+                                );
+                                data.main_split
+                                    .sync_settings_value_docs(parent, key, value);
+                            }
                         }
                     }
                     LapceUICommand::ResetSettingsFile(parent, key) => {
-                        Config::reset_setting(parent, key);
+                        Arc::make_mut(&mut data.settings)
+                            .capture_session_change(parent, key);
+                        if let Err(err) = Config::write_setting(parent, key, None) {
+                            if let Some(old) = data.config.setting_value(parent, key)
+                            {
+                                data.main_split
+                                    .sync_settings_value_docs(parent, key, &old);
+                            }
+                            Arc::make_mut(&mut data.settings).write_error =
+                                Some((parent.to_string(), key.to_string(), err));
+                        } else {
+                            data.settings_file_written(parent, key, None);
+                        }
                     }
                     LapceUICommand::OpenFileDiff(path, history) => {
                         let editor_view_id = data.main_split.active.clone();
@@ -1250,6 +1318,11 @@ fn handle_command_event(
                         let keypress = Arc::make_mut(&mut data.keypress);
                         keypress.filter_commands(pattern);
                     }
+                    LapceUICommand::UpdateSettingsFilter(pattern) => {
+                        ctx.set_handled();
+                        Arc::make_mut(&mut data.settings).filter_pattern =
+                            pattern.to_string();
+                    }
                     LapceUICommand::FilterKeymaps(
                         pattern,
                         filtered_commands_with_keymap,
@@ -1267,6 +1340,9 @@ fn handle_command_event(
                     LapceUICommand::UpdateKeymap(keymap, keys) => {
                         KeyPressData::update_file(keymap, keys);
                     }
+                    LapceUICommand::UpdateKeymapCondition(keymap, when) => {
+                        KeyPressData::update_condition_file(keymap, when.as_deref());
+                    }
                     LapceUICommand::OpenURI(uri) => {
                         ctx.set_handled();
                         if !uri.is_empty() {
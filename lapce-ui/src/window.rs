@@ -329,6 +329,11 @@ fn event(
                         }
                         Arc::make_mut(&mut data.keypress)
                             .update_keymaps(&data.config);
+                        for (_, tab) in data.tabs.iter_mut() {
+                            if tab.settings.reloading.is_some() {
+                                Arc::make_mut(&mut tab.settings).reloading = None;
+                            }
+                        }
                         ctx.set_handled();
                     }
                     LapceUICommand::ReloadWindow => {
@@ -362,8 +367,55 @@ fn event(
                         return;
                     }
                     LapceUICommand::SetTheme(theme, preview) => {
+                        if !*preview {
+                            // What the settings file had, the palette's
+                            // previews already changed the config
+                            let old = Config::settings_file()
+                                .and_then(|path| {
+                                    Config::file_setting_value_at(
+                                        &path,
+                                        "lapce",
+                                        "color-theme",
+                                    )
+                                })
+                                .or_else(|| {
+                                    Config::default_setting_value(
+                                        "lapce",
+                                        "color-theme",
+                                    )
+                                });
+                            if let Some(old) = old.as_ref().and_then(|v| v.as_str())
+                            {
+                                let _ = data.db.save_theme_change(old, theme);
+                            }
+                            for (_, tab) in data.tabs.iter_mut() {
+                                Arc::make_mut(&mut tab.settings)
+                                    .start_reloading("Switching the theme");
+                            }
+                        }
                         let config = Arc::make_mut(&mut data.config);
-                        config.set_theme(theme, *preview);
+                        let written = config.set_theme(theme, *preview) && !*preview;
+                        if written {
+                            // Recorded like the writes of the settings panel,
+                            // the files are copied and saved once for all tabs
+                            let mirror_error =
+                                data.config.mirror_settings_file().err();
+                            for (_, tab) in data.tabs.iter_mut() {
+                                tab.record_settings_file_write(
+                                    "lapce",
+                                    "color-theme",
+                                    mirror_error.clone(),
+                                );
+                            }
+                            if let Some(tab) = data.tabs.get(&*data.active_id) {
+                                tab.send_settings_changed(
+                                    "lapce",
+                                    "color-theme",
+                                    Some(serde_json::Value::String(theme.clone())),
+                                );
+                                tab.save_settings_changes();
+                            }
+                        }
                         if *preview {
                             for (_, tab) in data.tabs.iter_mut() {
                                 Arc::make_mut(&mut tab.config)